@@ -0,0 +1,13 @@
+fn main() {
+    // compile the protobuf definitions in pure Rust, so building doesn't need protoc installed
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/wordle.proto");
+        let descriptors = protox::compile(["proto/wordle.proto"], ["proto"])
+            .expect("proto/wordle.proto is valid");
+        tonic_build::configure()
+            .build_client(false)
+            .compile_fds(descriptors)
+            .expect("the generated code can be written out");
+    }
+}
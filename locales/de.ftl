@@ -0,0 +1,26 @@
+# German
+
+your-guess = dein Versuch:
+no-more-guesses = keine weiteren Versuche zu lesen
+
+daily-timed = wordle { $number }: die Uhr läuft
+daily = wordle { $number }
+daily-played = das Wort vom { $day } wurde schon gespielt
+daily-no-answer = für heute gibt es keine offizielle Lösung
+archive-no-answer = für den { $day } gibt es keine offizielle Lösung
+solved-timed = gelöst in { $guesses } Versuchen und { $seconds }s
+solved = gelöst in { $guesses } Versuchen
+out-of-guesses = keine Versuche mehr; die Lösung war '{ $answer }'
+hard-mode-illegal = schwerer Modus: alle bisherigen Hinweise müssen verwendet werden
+
+duel-wins = { $player } gewinnt!
+duel-draw = unentschieden
+duel-answer = die Lösung war '{ $answer }'
+
+stats-played = gespielt: { $n }
+stats-win-percent = Gewinnquote %: { $n }
+stats-current-streak = aktuelle Serie: { $n }
+stats-max-streak = längste Serie: { $n }
+stats-distribution = Verteilung der Versuche:
+stats-fastest = am schnellsten: { $seconds }s ({ $answer }, { $day })
+stats-fewest = wenigste Versuche: { $guesses } ({ $answer }, { $day })
@@ -0,0 +1,26 @@
+# English, which every other language falls back to for messages it doesn't have.
+
+your-guess = your guess:
+no-more-guesses = no more guesses to read
+
+daily-timed = wordle { $number }: the clock is running
+daily = wordle { $number }
+daily-played = { $day }'s word has already been played
+daily-no-answer = there's no official answer for today
+archive-no-answer = there's no official answer for { $day }
+solved-timed = solved in { $guesses } guesses and { $seconds }s
+solved = solved in { $guesses } guesses
+out-of-guesses = out of guesses; the answer was '{ $answer }'
+hard-mode-illegal = hard mode: every hint so far has to be used
+
+duel-wins = { $player } wins!
+duel-draw = it's a draw
+duel-answer = the answer was '{ $answer }'
+
+stats-played = played: { $n }
+stats-win-percent = win %: { $n }
+stats-current-streak = current streak: { $n }
+stats-max-streak = max streak: { $n }
+stats-distribution = guess distribution:
+stats-fastest = fastest: { $seconds }s ({ $answer }, { $day })
+stats-fewest = fewest guesses: { $guesses } ({ $answer }, { $day })
@@ -0,0 +1,26 @@
+# Spanish
+
+your-guess = tu intento:
+no-more-guesses = no quedan más intentos que leer
+
+daily-timed = wordle { $number }: el reloj está en marcha
+daily = wordle { $number }
+daily-played = la palabra del { $day } ya se ha jugado
+daily-no-answer = hoy no hay respuesta oficial
+archive-no-answer = no hay respuesta oficial para el { $day }
+solved-timed = resuelto en { $guesses } intentos y { $seconds }s
+solved = resuelto en { $guesses } intentos
+out-of-guesses = sin intentos; la respuesta era '{ $answer }'
+hard-mode-illegal = modo difícil: hay que usar todas las pistas hasta ahora
+
+duel-wins = ¡gana { $player }!
+duel-draw = es un empate
+duel-answer = la respuesta era '{ $answer }'
+
+stats-played = jugadas: { $n }
+stats-win-percent = % de victorias: { $n }
+stats-current-streak = racha actual: { $n }
+stats-max-streak = mejor racha: { $n }
+stats-distribution = distribución de intentos:
+stats-fastest = más rápida: { $seconds }s ({ $answer }, { $day })
+stats-fewest = menos intentos: { $guesses } ({ $answer }, { $day })
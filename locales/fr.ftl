@@ -0,0 +1,26 @@
+# French
+
+your-guess = votre essai :
+no-more-guesses = plus d'essais à lire
+
+daily-timed = wordle { $number } : le chrono tourne
+daily = wordle { $number }
+daily-played = le mot du { $day } a déjà été joué
+daily-no-answer = pas de réponse officielle aujourd'hui
+archive-no-answer = pas de réponse officielle pour le { $day }
+solved-timed = trouvé en { $guesses } essais et { $seconds }s
+solved = trouvé en { $guesses } essais
+out-of-guesses = plus d'essais ; la réponse était '{ $answer }'
+hard-mode-illegal = mode difficile : il faut utiliser tous les indices trouvés
+
+duel-wins = { $player } gagne !
+duel-draw = match nul
+duel-answer = la réponse était '{ $answer }'
+
+stats-played = parties : { $n }
+stats-win-percent = % de victoires : { $n }
+stats-current-streak = série en cours : { $n }
+stats-max-streak = meilleure série : { $n }
+stats-distribution = répartition des essais :
+stats-fastest = la plus rapide : { $seconds }s ({ $answer }, { $day })
+stats-fewest = le moins d'essais : { $guesses } ({ $answer }, { $day })
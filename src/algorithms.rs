@@ -1,3 +1,512 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::AddAssign;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::blocklist::Blocklist;
+use crate::variants::{Spelling, Variants};
+use crate::Guess;
+
+mod bandit;
+mod beam;
+mod cache;
+mod greedy;
+mod last_guess;
+mod model;
 mod naive;
+mod optimal;
+mod patterns;
+mod prior;
+mod scorer;
 
+pub use bandit::{Bandit, Objective, Opener};
+pub use beam::Beam;
+pub use cache::{LoadCacheError, StateCache};
+pub use greedy::Greedy;
+pub use last_guess::{likeliest, LastGuess, RiskMode};
+pub use model::ModelGuesser;
 pub use naive::Naive;
+pub use optimal::Optimal;
+pub use patterns::Patterns;
+pub use prior::{Counts, LogCounts, Prior, SigmoidRank, Uniform};
+pub use scorer::{
+    AnswerBonus, Composite, Entropy, ExpectedLength, ExpectedSize, Frequency, Scorer, WorstCase,
+};
+
+const DICTIONARY: &str = include_str!("../dictionary.txt");
+const ANSWERS: &str = include_str!("../answers.txt");
+
+// the pattern index of all five letters green
+const WIN: usize = 0;
+
+// every dictionary word along with how often it shows up in the wild (a bad line is left out
+// rather than taking the program down, and `selfcheck` reports it)
+pub(crate) fn dictionary() -> impl Iterator<Item = (&'static str, usize)> {
+    DICTIONARY
+        .lines()
+        .filter_map(crate::dict::parse_line)
+        .map(|(word, count)| (word, count as usize))
+}
+
+// the official answer list, in the order the game used them
+pub(crate) fn answers() -> impl Iterator<Item = &'static str> {
+    ANSWERS.split_whitespace()
+}
+
+// The ways the guessers keep the words that could still be the answer, so `Restriction` works
+// on any of them.
+trait Remaining: Clone {
+    fn retain_words(&mut self, keep: impl FnMut(&str) -> bool);
+    // count `word` as `into` from now on, if it's there at all
+    fn merge(&mut self, word: &str, into: &'static str);
+}
+
+impl Remaining for HashMap<&'static str, usize> {
+    fn retain_words(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        self.retain(|word, _| keep(word));
+    }
+
+    fn merge(&mut self, word: &str, into: &'static str) {
+        if let Some(count) = self.remove(word) {
+            *self.entry(into).or_insert(0) += count;
+        }
+    }
+}
+
+impl<N: Copy + AddAssign> Remaining for Vec<(&'static str, N)> {
+    fn retain_words(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        self.retain(|&(word, _)| keep(word));
+    }
+
+    fn merge(&mut self, word: &str, into: &'static str) {
+        if let Some(i) = self.iter().position(|&(w, _)| w == word) {
+            let (_, count) = self.remove(i);
+            match self.iter_mut().find(|(w, _)| *w == into) {
+                Some((_, c)) => *c += count,
+                None => self.push((into, count)),
+            }
+        }
+    }
+}
+
+// without counts
+impl Remaining for Vec<&'static str> {
+    fn retain_words(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        self.retain(|word| keep(word));
+    }
+
+    fn merge(&mut self, word: &str, into: &'static str) {
+        if let Some(i) = self.iter().position(|&w| w == word) {
+            self.remove(i);
+            if !self.contains(&into) {
+                self.push(into);
+            }
+        }
+    }
+}
+
+// The options every guesser has for which words it plays with, applied the same way to each
+// one's candidates and guesses (which stay sorted).
+#[derive(Clone, Copy)]
+enum Restriction<'a> {
+    // never suggest a blocked word unless it's the only thing left to guess
+    Blocklist(&'a Blocklist),
+    // only consider and guess words from the official answer list
+    AnswersOnly,
+    // only the given spelling of a word with regional variants can be the answer, but every
+    // spelling can still be guessed
+    Spelling(&'a Variants<'static>, Spelling),
+}
+
+impl Restriction<'_> {
+    fn apply(self, remaining: &mut impl Remaining, guesses: &mut Vec<&'static str>) {
+        match self {
+            Restriction::Blocklist(blocklist) => {
+                guesses.retain(|guess| !blocklist.contains(guess));
+            }
+            Restriction::AnswersOnly => {
+                let answers: HashSet<_> = answers().collect();
+                remaining.retain_words(|word| answers.contains(word));
+                guesses.retain(|guess| answers.contains(guess));
+            }
+            Restriction::Spelling(variants, spelling) => {
+                for word in variants.words() {
+                    let preferred = variants.normalize(word, spelling);
+                    if word == preferred {
+                        continue;
+                    }
+                    remaining.merge(word, preferred);
+                    guesses.push(word);
+                    guesses.push(preferred);
+                }
+                guesses.sort_unstable();
+                guesses.dedup();
+            }
+        }
+    }
+}
+
+// Keeps track of which guesses a guesser has already filtered its words by. Usually the history
+// grows by one guess a turn, but a resumed game can hand over several guesses at once, or a
+// history that has nothing to do with the one we were following.
+#[derive(Debug)]
+struct Progress<R> {
+    seen: Vec<Guess>,
+    // the candidates and guesses before the first guess, to start over from if the history
+    // changes under us
+    start: Option<(R, Vec<&'static str>)>,
+    // where the guesser counts the guesses it didn't have to score
+    shortcuts: Shortcuts,
+}
+
+impl<R> Default for Progress<R> {
+    fn default() -> Self {
+        Self {
+            seen: Vec::new(),
+            start: None,
+            shortcuts: Shortcuts::default(),
+        }
+    }
+}
+
+impl<R: Clone> Progress<R> {
+    // The guesses in `history` that `remaining` and `guesses` haven't been filtered by yet. When
+    // the history doesn't carry on from the last one, they're put back how they started and
+    // that's every guess in `history`, which comes back as true so the guesser can throw out
+    // anything else it worked out along the way.
+    fn catch_up<'h>(
+        &mut self,
+        history: &'h [Guess],
+        remaining: &mut R,
+        guesses: &mut Vec<&'static str>,
+    ) -> (bool, &'h [Guess]) {
+        let start = self
+            .start
+            .get_or_insert_with(|| (remaining.clone(), guesses.clone()));
+        let restart = !history.starts_with(&self.seen);
+        let unseen = if restart {
+            (*remaining, *guesses) = start.clone();
+            history
+        } else {
+            &history[self.seen.len()..]
+        };
+        self.seen = history.to_vec();
+        (restart, unseen)
+    }
+}
+
+// rough number of guesses needed to pin down one of `n` words once we stop looking ahead
+fn estimate(n: usize) -> f64 {
+    if n == 1 {
+        1.0
+    } else {
+        1.0 + (n as f64).log2() / 2.0
+    }
+}
+
+// How many guesses a guesser made without scoring anything (see `shortcut`). Every guesser
+// counts its own, and clones count together, so the guessers of a run (one per game, say) can be
+// handed one to add up in.
+#[derive(Debug, Clone, Default)]
+pub struct Shortcuts(Arc<[AtomicUsize; 2]>);
+
+impl Shortcuts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // with one word left, and with two
+    pub fn counts(&self) -> (usize, usize) {
+        (
+            self.0[0].load(Ordering::Relaxed),
+            self.0[1].load(Ordering::Relaxed),
+        )
+    }
+}
+
+// The guess when there's no need to score anything: the last candidate, or the likelier of the
+// last two (the earlier word on a tie), since either one splits them as well as any guess could
+// and might win outright. Of two, only one the guesser may guess (`allowed`) will do.
+fn shortcut<'a>(
+    candidates: impl IntoIterator<Item = (&'a str, u64)>,
+    allowed: impl Fn(&str) -> bool,
+    shortcuts: &Shortcuts,
+) -> Option<&'a str> {
+    let mut candidates = candidates.into_iter();
+    let first = candidates.next()?;
+    let Some(second) = candidates.next() else {
+        shortcuts.0[0].fetch_add(1, Ordering::Relaxed);
+        return Some(first.0);
+    };
+    if candidates.next().is_some() {
+        return None;
+    }
+    let (word, _) = [first, second]
+        .into_iter()
+        .filter(|&(word, _)| allowed(word))
+        .max_by(|(a, x), (b, y)| x.cmp(y).then(b.cmp(a)))?;
+    shortcuts.0[1].fetch_add(1, Ordering::Relaxed);
+    Some(word)
+}
+
+fn letter_set(word: &str) -> u32 {
+    word.bytes().fold(0, |acc, b| acc | 1 << (b - b'a'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Guesser, History};
+
+    fn history(answer: &str, guesses: &[&str]) -> History {
+        History::from_answer(answer, guesses.iter().copied())
+    }
+
+    #[test]
+    fn catch_up() {
+        let mut progress = Progress::default();
+        let (mut remaining, mut guesses) = (vec!["fight", "right"], vec!["tares"]);
+        let game = history("right", &["tares", "doily", "fight"]);
+        let mut catch_up = |history| progress.catch_up(history, &mut remaining, &mut guesses);
+        assert_eq!(catch_up(&game[..2]), (false, &game[..2]));
+        assert_eq!(catch_up(&game), (false, &game[2..]));
+        let other = history("wrong", &["tares"]);
+        assert_eq!(catch_up(&other), (true, &other[..]));
+
+        // starting over puts the words back how they were before the first guess
+        let mut progress = Progress::default();
+        let mut remaining = vec!["fight", "right"];
+        progress.catch_up(&game[..1], &mut remaining, &mut guesses);
+        remaining.pop();
+        progress.catch_up(&other, &mut remaining, &mut guesses);
+        assert_eq!(remaining, ["fight", "right"]);
+    }
+
+    #[test]
+    fn restrictions() {
+        let variants = Variants::parse("fibre fiber\n").unwrap();
+        let blocklist = Blocklist::parse("cigar\n");
+        let words = [("cigar", 1), ("fiber", 3), ("fibre", 2), ("tares", 1)];
+        let mut map: HashMap<_, usize> = words.into_iter().collect();
+        let mut vec = words.to_vec();
+        let mut guesses = words.map(|(word, _)| word).to_vec();
+        let mut vec_guesses = guesses.clone();
+        // the same whichever way the candidates are kept
+        let mut apply = |restriction: Restriction| {
+            restriction.apply(&mut map, &mut guesses);
+            restriction.apply(&mut vec, &mut vec_guesses);
+            let mut from_map: Vec<_> = map.iter().map(|(&word, &count)| (word, count)).collect();
+            from_map.sort_unstable();
+            assert_eq!(from_map, vec);
+            assert_eq!(guesses, vec_guesses);
+            (vec.clone(), guesses.clone())
+        };
+
+        let (remaining, guesses) = apply(Restriction::Spelling(&variants, Spelling::American));
+        assert_eq!(remaining, [("cigar", 1), ("fiber", 5), ("tares", 1)]);
+        assert_eq!(guesses, ["cigar", "fiber", "fibre", "tares"]);
+        let (_, guesses) = apply(Restriction::Blocklist(&blocklist));
+        assert_eq!(guesses, ["fiber", "fibre", "tares"]);
+        let (remaining, guesses) = apply(Restriction::AnswersOnly);
+        assert_eq!(remaining, [("cigar", 1), ("fiber", 5)]);
+        assert_eq!(guesses, ["fiber"]);
+    }
+
+    #[test]
+    fn naive_incremental() {
+        // yeast and verso each rule out fewer words than they leave, so the buckets get updated
+        // rather than counted again
+        let game = history("right", &["tares", "yeast", "verso", "doily"]);
+        let mut incremental = Naive::new().incremental();
+        for round in 1..=game.len() {
+            let history: History = game[..round].into();
+            assert_eq!(incremental.guess(&history), Naive::new().guess(&history));
+        }
+    }
+
+    #[test]
+    fn shortcut() {
+        let anything = |_: &str| true;
+        let counts = Shortcuts::new();
+        let shortcuts = &counts.clone();
+        assert_eq!(
+            super::shortcut([("right", 1)], |_| false, shortcuts),
+            Some("right")
+        );
+        assert_eq!(
+            super::shortcut([("wrong", 1), ("right", 3)], anything, shortcuts),
+            Some("right")
+        );
+        // a tie goes to the earlier word
+        assert_eq!(
+            super::shortcut([("wrong", 2), ("right", 2)], anything, shortcuts),
+            Some("right")
+        );
+        assert_eq!(
+            super::shortcut(
+                [("wrong", 1), ("right", 3)],
+                |word| word != "right",
+                shortcuts
+            ),
+            Some("wrong")
+        );
+        assert_eq!(
+            super::shortcut([("wrong", 1), ("right", 3)], |_| false, shortcuts),
+            None
+        );
+        let three = [("fight", 1), ("right", 1), ("wrong", 1)];
+        assert_eq!(super::shortcut(three, anything, shortcuts), None);
+        assert_eq!(super::shortcut([], anything, shortcuts), None);
+        // counted in the clone it was handed, and only when it made a guess
+        assert_eq!(counts.counts(), (1, 3));
+        assert_eq!(Shortcuts::new().counts(), (0, 0));
+    }
+
+    #[test]
+    fn naive_resumes_with_the_whole_history() {
+        let game = history("right", &["tares", "doily"]);
+        let mut turn_by_turn = Naive::new();
+        turn_by_turn.guess(&game[..1].into());
+        let expected = turn_by_turn.guess(&game);
+        assert_eq!(Naive::new().guess(&game), expected);
+
+        // a different game entirely starts over rather than filtering what's left
+        let mut reused = Naive::new();
+        reused.guess(&history("wrong", &["tares"]));
+        assert_eq!(reused.guess(&game), expected);
+    }
+
+    // a few words that all look alike, with "fjord" to tell them apart
+    const WORDS: [(&str, u64); 5] = [
+        ("fight", 10),
+        ("might", 40),
+        ("right", 30),
+        ("sight", 20),
+        ("fjord", 1),
+    ];
+
+    #[test]
+    fn scorers() {
+        let candidates = &WORDS[..4];
+        // right and fight look the same to masts, and the rest are apart
+        assert_eq!(WorstCase.score("masts", candidates), -2.0);
+        assert_eq!(ExpectedSize.score("masts", candidates), -1.4);
+        assert!(Entropy.score("masts", candidates) > Entropy.score("fight", candidates));
+        assert_eq!(Frequency.score("might", candidates), 0.4);
+        assert_eq!(Frequency.score("fjord", candidates), 0.0);
+
+        let composite = Composite::new()
+            .with(2.0, WorstCase)
+            .with(10.0, Frequency)
+            .with(0.0, Entropy);
+        assert_eq!(composite.score("might", candidates), -6.0 + 4.0);
+        assert_eq!(Composite::new().score("might", candidates), 0.0);
+
+        // fjord splits them up better, but might could just be right
+        assert!(Entropy.score("fjord", candidates) > Entropy.score("might", candidates));
+        let bonus = AnswerBonus::new(Entropy);
+        assert!(bonus.score("might", candidates) > bonus.score("fjord", candidates));
+        assert_eq!(
+            bonus.score("fjord", candidates),
+            Entropy.score("fjord", candidates)
+        );
+    }
+
+    #[test]
+    fn greedy() {
+        let game = |scorer: &dyn Scorer, answer: &str| {
+            let mut greedy = Greedy::with_words(scorer, WORDS.iter().copied());
+            let mut history = History::new();
+            while !history.is_won() {
+                let word = greedy.guess(&history);
+                history.push_for(answer, &word);
+            }
+            history.words().collect::<Vec<_>>().join(" ")
+        };
+        // going for the most common word first
+        assert_eq!(game(&Frequency, "sight"), "might right sight");
+        // fjord can't win, but it's the only word that splits the rest up
+        assert_eq!(game(&Entropy, "sight"), "fjord might sight");
+        assert_eq!(game(&WorstCase, "right"), "fjord right");
+
+        // the opener can be handed over rather than worked out
+        let mut greedy = Greedy::with_words(Entropy, WORDS.iter().copied()).with_opener("fjord");
+        assert_eq!(greedy.guess(&History::new()), "fjord");
+
+        // in hard mode, fjord doesn't keep the ight that might found
+        let after = History::from_answer("sight", ["might"]);
+        let greedy = || Greedy::with_words(Entropy, WORDS.iter().copied());
+        assert_eq!(greedy().guess(&after), "fjord");
+        let hard = greedy().hard_mode().guess(&after);
+        assert!(["fight", "right", "sight"].contains(&&*hard));
+
+        // with only might left as an answer there's nothing to find out
+        assert_eq!(greedy().top_answers(1).guess(&History::new()), "might");
+    }
+
+    #[test]
+    fn last_guess() {
+        let greedy = || Greedy::with_words(Entropy, WORDS.iter().copied());
+        // with one guess to go, fjord can't win, and might is the likeliest
+        let mut score = LastGuess::new(greedy(), RiskMode::Score, &WORDS).with_max_guesses(1);
+        assert_eq!(score.guess(&History::new()), "fjord");
+        let mut win = LastGuess::new(greedy(), RiskMode::Win, &WORDS).with_max_guesses(1);
+        assert_eq!(win.guess(&History::new()), "might");
+        // and it's left alone before that
+        let mut win = LastGuess::new(greedy(), RiskMode::Win, &WORDS);
+        assert_eq!(win.guess(&History::new()), "fjord");
+
+        let after = history("sight", &["might"]);
+        assert_eq!(likeliest(&after, &WORDS), Some("right"));
+        assert_eq!(
+            likeliest(&history("sight", &["fjord", "fjord"]), &WORDS[4..]),
+            None
+        );
+    }
+
+    #[test]
+    fn state_cache() {
+        assert_eq!(
+            StateCache::key(["right", "fight"]),
+            StateCache::key(["fight", "right"])
+        );
+        assert_ne!(StateCache::key(["ab", "c"]), StateCache::key(["a", "bc"]));
+
+        let cache = StateCache::new();
+        let play = |cache: Option<&StateCache>, answer: &str| {
+            let greedy = Greedy::with_words(Entropy, WORDS.iter().copied());
+            let mut greedy = match cache {
+                Some(cache) => greedy.with_cache(cache.clone()),
+                None => greedy,
+            };
+            let mut history = History::new();
+            while !history.is_won() {
+                let word = greedy.guess(&history);
+                history.push_for(answer, &word);
+            }
+            history
+        };
+        let first = play(Some(&cache), "sight");
+        let (hits, _) = cache.stats();
+        assert_eq!(hits, 0);
+        assert!(!cache.is_empty());
+        // the second game goes through the same states, and plays the same as without a cache
+        assert_eq!(play(Some(&cache), "sight"), first);
+        assert_eq!(play(None, "sight"), first);
+        assert!(cache.stats().0 > hits);
+
+        let mut saved = Vec::new();
+        cache.save(7, &mut saved).unwrap();
+        let saved = String::from_utf8(saved).unwrap();
+        let loaded = StateCache::load(&saved, 7).unwrap();
+        assert_eq!(loaded.len(), cache.len());
+        let mut again = Vec::new();
+        loaded.save(7, &mut again).unwrap();
+        assert_eq!(String::from_utf8(again).unwrap(), saved);
+        assert_eq!(
+            StateCache::load(&saved, 8).unwrap_err(),
+            LoadCacheError::Stale
+        );
+        assert!(StateCache::load("tares", 7).is_err());
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{Guesser, History};
+
+// What the bandit is trying to get better at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    // the fewest guesses on average
+    Average,
+    // solving within six guesses, however long it takes
+    WithinSix,
+}
+
+impl Objective {
+    // how good a game went, between 0 and 1
+    fn reward(self, result: Option<usize>) -> f64 {
+        match (self, result) {
+            (_, None) => 0.0,
+            (Objective::Average, Some(n)) => (7 - n.min(7)) as f64 / 6.0,
+            (Objective::WithinSix, Some(n)) => (n <= 6) as u8 as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Arm {
+    opener: &'static str,
+    pulls: usize,
+    reward: f64,
+}
+
+// Treats picking an opener as a multi-armed bandit over the games of a benchmark run: every
+// game plays the opener with the best upper confidence bound (UCB1), and what happened is fed
+// back in with `record`, so the run gradually settles on the openers that do best.
+pub struct Bandit {
+    arms: Vec<Arm>,
+    objective: Objective,
+}
+
+impl Bandit {
+    pub fn new(openers: &[&'static str], objective: Objective) -> Self {
+        assert!(
+            !openers.is_empty(),
+            "need at least one opener to choose from"
+        );
+        Self {
+            arms: openers
+                .iter()
+                .map(|&opener| Arm {
+                    opener,
+                    pulls: 0,
+                    reward: 0.0,
+                })
+                .collect(),
+            objective,
+        }
+    }
+
+    // the arm to play next
+    pub fn choose(&self) -> usize {
+        // try everything once before trusting any averages
+        if let Some(i) = self.arms.iter().position(|arm| arm.pulls == 0) {
+            return i;
+        }
+        let total: usize = self.arms.iter().map(|arm| arm.pulls).sum();
+        let ucb = |arm: &Arm| {
+            arm.reward / arm.pulls as f64 + (2.0 * (total as f64).ln() / arm.pulls as f64).sqrt()
+        };
+        (0..self.arms.len())
+            .max_by(|&a, &b| ucb(&self.arms[a]).total_cmp(&ucb(&self.arms[b])))
+            .expect("there is at least one arm")
+    }
+
+    // wrap `inner` so that it opens with the given arm's word
+    pub fn guesser<G>(&self, arm: usize, inner: G) -> Opener<G> {
+        Opener::new(self.arms[arm].opener, inner)
+    }
+
+    // feed back how a game with the given arm went
+    pub fn record(&mut self, arm: usize, result: Option<usize>) {
+        let arm = &mut self.arms[arm];
+        arm.pulls += 1;
+        arm.reward += self.objective.reward(result);
+    }
+
+    // every opener with how often it was played and its mean reward, best first
+    pub fn ranking(&self) -> Vec<(&'static str, usize, f64)> {
+        let mut ranking: Vec<_> = self
+            .arms
+            .iter()
+            .map(|arm| {
+                let mean = if arm.pulls == 0 {
+                    0.0
+                } else {
+                    arm.reward / arm.pulls as f64
+                };
+                (arm.opener, arm.pulls, mean)
+            })
+            .collect();
+        ranking.sort_by(|a, b| b.2.total_cmp(&a.2));
+        ranking
+    }
+}
+
+// Plays a fixed first guess and leaves the rest of the game to another guesser.
+pub struct Opener<G> {
+    opener: &'static str,
+    inner: G,
+}
+
+impl<G> Opener<G> {
+    pub fn new(opener: &'static str, inner: G) -> Self {
+        Self { opener, inner }
+    }
+}
+
+impl<G: Guesser> Guesser for Opener<G> {
+    fn guess(&mut self, history: &History) -> String {
+        if history.is_empty() {
+            return self.opener.to_string();
+        }
+        self.inner.guess(history)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Wordle;
+
+    #[test]
+    fn tries_every_arm_first() {
+        let mut bandit = Bandit::new(&["tares", "salet", "crane"], Objective::Average);
+        let mut seen = Vec::new();
+        for _ in 0..3 {
+            let arm = bandit.choose();
+            seen.push(arm);
+            bandit.record(arm, Some(4));
+        }
+        seen.sort_unstable();
+        assert_eq!(seen, [0, 1, 2]);
+    }
+
+    #[test]
+    fn settles_on_the_best_arm() {
+        let mut bandit = Bandit::new(&["tares", "salet"], Objective::Average);
+        for _ in 0..200 {
+            let arm = bandit.choose();
+            bandit.record(arm, Some(if arm == 1 { 3 } else { 5 }));
+        }
+        let ranking = bandit.ranking();
+        assert_eq!(ranking[0].0, "salet");
+        assert!(ranking[0].1 > ranking[1].1);
+    }
+
+    #[test]
+    fn within_six_ignores_speed() {
+        assert_eq!(Objective::WithinSix.reward(Some(2)), 1.0);
+        assert_eq!(Objective::WithinSix.reward(Some(6)), 1.0);
+        assert_eq!(Objective::WithinSix.reward(Some(7)), 0.0);
+        assert_eq!(Objective::WithinSix.reward(None), 0.0);
+    }
+
+    #[test]
+    fn opener_then_inner() {
+        let w = Wordle::new();
+        let bandit = Bandit::new(&["wrong"], Objective::Average);
+        let inner = guesser!(|_history| { "right".to_string() });
+        assert_eq!(w.play("right", bandit.guesser(0, inner)), Ok(Some(2)));
+    }
+}
@@ -0,0 +1,338 @@
+use std::ops::Range;
+
+use super::{
+    dictionary, estimate, letter_set, shortcut, Progress, Restriction, Shortcuts, StateCache, WIN,
+};
+use crate::blocklist::Blocklist;
+use crate::cancel::Stop;
+use crate::core::{entropy, partition_counts, pattern_index};
+use crate::openers;
+use crate::timings::{self, Phase};
+use crate::variants::{Spelling, Variants};
+use crate::{Correctness, Guesser, History};
+
+// words that could still be the answer, with their counts
+type Candidates = Vec<(&'static str, usize)>;
+
+// Looks a few guesses ahead, but only through the `width` most informative guesses at each
+// step rather than all of them. A width of 1 is roughly greedy entropy; a huge width and depth
+// is an exhaustive search.
+pub struct Beam {
+    width: usize,
+    depth: usize,
+    remaining: Candidates,
+    guesses: Vec<&'static str>,
+    cache: Option<StateCache>,
+    // Where the search keeps the candidates of every state it's looking at, used like a stack: a
+    // state's buckets go on the end, and are cut off again once it's done with them. A whole game
+    // of searching reuses the one allocation instead of making a vec per bucket per guess tried.
+    arena: Candidates,
+    // the pattern of each candidate while one state is split into buckets
+    patterns: Vec<u8>,
+    progress: Progress<Candidates>,
+    // what its opener is bundled as (see `openers`), until its words stop being the bundled ones
+    bundled: Option<String>,
+    stop: Option<Stop>,
+}
+
+impl Beam {
+    pub fn new(width: usize) -> Self {
+        Self::with_depth(width, 2)
+    }
+
+    pub fn with_depth(width: usize, depth: usize) -> Self {
+        assert!(width > 0, "beam must keep at least one guess");
+        assert!(depth > 0, "beam must look at least one guess ahead");
+        let remaining: Vec<_> = timings::time(Phase::Load, || dictionary().collect());
+        let guesses = remaining.iter().map(|&(word, _)| word).collect();
+        Self {
+            width,
+            depth,
+            remaining,
+            guesses,
+            cache: None,
+            arena: Vec::new(),
+            patterns: Vec::new(),
+            progress: Progress::default(),
+            // only the default depth is bundled
+            bundled: (depth == 2).then(|| format!("beam:{}", width)),
+            stop: None,
+        }
+    }
+
+    // remember the best guess for each state in `cache`, and look there before searching
+    pub fn with_cache(mut self, cache: StateCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    // count the guesses it makes without scoring anything in `shortcuts` (see `Shortcuts`)
+    pub fn with_shortcuts(mut self, shortcuts: Shortcuts) -> Self {
+        self.progress.shortcuts = shortcuts;
+        self
+    }
+
+    // Stop scoring once `stop` says to (see `Stop`), and go with the best guess so far (what's
+    // worked out after that isn't cached).
+    pub fn with_stop(mut self, stop: Stop) -> Self {
+        self.stop = Some(stop);
+        self
+    }
+
+    pub fn with_variants(self, variants: &Variants<'static>, spelling: Spelling) -> Self {
+        self.restrict(Restriction::Spelling(variants, spelling))
+    }
+
+    pub fn answers_only(self) -> Self {
+        self.restrict(Restriction::AnswersOnly)
+    }
+
+    pub fn with_blocklist(self, blocklist: &Blocklist) -> Self {
+        self.restrict(Restriction::Blocklist(blocklist))
+    }
+
+    // the options every guesser has for which words it plays with (see `Restriction`)
+    fn restrict(mut self, restriction: Restriction) -> Self {
+        // the bundled opener still goes with a blocklist, unless it's blocked (see `guess`)
+        if !matches!(restriction, Restriction::Blocklist(_)) {
+            self.bundled = None;
+        }
+        restriction.apply(&mut self.remaining, &mut self.guesses);
+        self
+    }
+
+    // work the opener out like any other guess, even where there's a bundled one, which is how
+    // the bundled ones are made
+    pub(crate) fn without_bundled_opener(mut self) -> Self {
+        self.bundled = None;
+        self
+    }
+
+    // the `width` guesses with the most expected information against `candidates`
+    fn top(&self, candidates: &[(&'static str, usize)]) -> Vec<&'static str> {
+        let mut scored: Vec<(&'static str, f64)> = self
+            .guesses
+            .iter()
+            // what's been scored so far, once it's told to stop
+            .take_while(|_| !self.stopping())
+            .map(|&guess| {
+                let buckets = partition_counts(
+                    guess,
+                    candidates.iter().map(|&(word, count)| (word, count as u64)),
+                );
+                (guess, entropy(buckets))
+            })
+            // a guess that can't split the candidates up gets us nowhere
+            .filter(|&(_, entropy)| entropy > 0.0)
+            .collect();
+        // stable sort so ties go to the earlier word, like the other algorithms
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(self.width);
+        scored.into_iter().map(|(guess, _)| guess).collect()
+    }
+
+    // the best guess for the candidates in `range` of the arena along with the expected number
+    // of guesses (including that one) it takes to finish the game
+    fn search(&mut self, range: Range<usize>, depth: usize) -> (Option<&'static str>, f64) {
+        let first = self.arena[range.start].0;
+        if range.len() == 1 {
+            return (Some(first), 1.0);
+        }
+        if depth == 0 {
+            return (None, estimate(range.len()));
+        }
+
+        let total_count: usize = self.arena[range.clone()]
+            .iter()
+            .map(|&(_, count)| count)
+            .sum();
+        let mut best: Option<(&'static str, f64)> = None;
+        for guess in self.top(&self.arena[range.clone()]) {
+            if self.stopping() {
+                break;
+            }
+            let end = self.arena.len();
+            let buckets = self.split(range.clone(), guess);
+            // we spend this guess no matter what, and then need however many the bucket needs
+            let mut expected = 1.0;
+            for pattern in 0..243 {
+                let bucket = buckets[pattern]..buckets[pattern + 1];
+                if pattern == WIN || bucket.is_empty() {
+                    continue;
+                }
+                let weight: usize = self.arena[bucket.clone()]
+                    .iter()
+                    .map(|&(_, count)| count)
+                    .sum();
+                let p = weight as f64 / total_count as f64;
+                expected += p * self.search(bucket, depth - 1).1;
+            }
+            self.arena.truncate(end);
+            if best.is_none_or(|(_, e)| expected < e) {
+                best = Some((guess, expected));
+            }
+        }
+        match best {
+            Some((guess, expected)) => (Some(guess), expected),
+            // only blocked words could tell these apart, so just try one of them
+            None => (Some(first), estimate(range.len())),
+        }
+    }
+
+    fn stopping(&self) -> bool {
+        self.stop.as_ref().is_some_and(Stop::check)
+    }
+
+    // Put the candidates in `range` of the arena on its end again, bucketed by the pattern
+    // `guess` gets against them (in the same order within a bucket). Bucket `p` is
+    // `buckets[p]..buckets[p + 1]`.
+    fn split(&mut self, range: Range<usize>, guess: &str) -> [usize; 244] {
+        let mut counts = [0; 243];
+        self.patterns.clear();
+        for &(word, _) in &self.arena[range.clone()] {
+            let pattern = pattern_index(Correctness::compute(word, guess));
+            counts[pattern] += 1;
+            self.patterns.push(pattern as u8);
+        }
+        let mut buckets = [self.arena.len(); 244];
+        for pattern in 0..243 {
+            buckets[pattern + 1] = buckets[pattern] + counts[pattern];
+        }
+        let mut next = buckets;
+        self.arena.resize(buckets[243], ("", 0));
+        for (i, k) in range.zip(0..) {
+            let pattern = self.patterns[k] as usize;
+            self.arena[next[pattern]] = self.arena[i];
+            next[pattern] += 1;
+        }
+        buckets
+    }
+}
+
+impl Guesser for Beam {
+    fn guess(&mut self, history: &History) -> String {
+        // the opener is the same every game and expensive to compute, so use the bundled one
+        let opener = self.bundled.as_deref().filter(|_| history.is_empty());
+        if let Some(opener) = opener.and_then(openers::bundled) {
+            if self.guesses.binary_search(&opener).is_ok() {
+                return opener.to_string();
+            }
+        }
+        let (_, unseen) = self
+            .progress
+            .catch_up(history, &mut self.remaining, &mut self.guesses);
+        timings::time(Phase::Filtering, || {
+            for guess in unseen {
+                // a mask that rules out every word left was misread or mistyped, and going by the
+                // rest of the game beats giving up on it
+                if self.remaining.iter().any(|&(word, _)| guess.matches(word)) {
+                    self.remaining.retain(|&(word, _)| guess.matches(word));
+                }
+            }
+        });
+        let candidates = self
+            .remaining
+            .iter()
+            .map(|&(word, count)| (word, count as u64));
+        let allowed = |word: &str| self.guesses.binary_search(&word).is_ok();
+        if let Some(word) = shortcut(candidates, allowed, &self.progress.shortcuts) {
+            return word.to_string();
+        }
+        let key = self
+            .cache
+            .as_ref()
+            .map(|_| StateCache::key(self.remaining.iter().map(|&(word, _)| word)));
+        if let (Some(cache), Some(key)) = (&self.cache, key) {
+            if let Some((guess, _)) = cache.get(key) {
+                return guess;
+            }
+        }
+        // same cheap pruning as naive: guesses sharing no letters with any candidate are useless
+        let letters = self
+            .remaining
+            .iter()
+            .fold(0u32, |acc, &(word, _)| acc | letter_set(word));
+        self.guesses
+            .retain(|&guess| letter_set(guess) & letters != 0);
+
+        // the state being searched sits at the bottom of the arena, under everything the search
+        // puts on top of it
+        self.arena.clear();
+        self.arena.extend_from_slice(&self.remaining);
+        let (guess, expected) = self.search(0..self.arena.len(), self.depth);
+        let guess = guess.expect("search always guesses when depth is nonzero");
+        if let (Some(cache), Some(key), false) = (
+            &self.cache,
+            key,
+            self.stop.as_ref().is_some_and(Stop::stopped),
+        ) {
+            cache.insert(key, guess, expected);
+        }
+        guess.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::partition;
+
+    // over the answers only, to keep it quick in a debug build
+    fn beam(width: usize) -> Beam {
+        Beam::new(width).answers_only()
+    }
+
+    #[test]
+    fn solves() {
+        for answer in ["cigar", "rebut", "sissy"] {
+            // with the opener given, since working it out is the slow part
+            let mut history = History::from_answer(answer, ["tares"]);
+            let mut beam = beam(2);
+            while !history.is_won() && history.len() < 6 {
+                let guess = beam.guess(&history);
+                history.push_for(answer, &guess);
+            }
+            assert!(history.is_won(), "{}: {:?}", answer, history);
+        }
+    }
+
+    #[test]
+    fn widths() {
+        let history = History::from_answer("rebut", ["tares"]);
+        let search = |width| {
+            let mut beam = beam(width);
+            beam.remaining
+                .retain(|&(word, _)| history.iter().all(|guess| guess.matches(word)));
+            beam.arena = beam.remaining.clone();
+            let top = beam.top(&beam.remaining);
+            (top, beam.search(0..beam.arena.len(), 2))
+        };
+        let (top, (narrow, narrow_expected)) = search(1);
+        let (_, (wide, wide_expected)) = search(8);
+        // a width of one just goes with the most informative guess
+        assert_eq!(narrow, Some(top[0]));
+        // and looking through more of them can only find something as good or better
+        assert!(wide.is_some());
+        assert!(wide_expected <= narrow_expected);
+    }
+
+    #[test]
+    fn split() {
+        let words = ["right", "sight", "wrong", "fight", "masts"];
+        let mut beam = Beam::new(1);
+        beam.arena = words.iter().map(|&word| (word, 1)).collect();
+        let buckets = beam.split(0..5, "masts");
+        for (pattern, expected) in partition("masts", &words).iter().enumerate() {
+            let bucket: Vec<_> = beam.arena[buckets[pattern]..buckets[pattern + 1]]
+                .iter()
+                .map(|&(word, _)| word)
+                .collect();
+            assert_eq!(&bucket, expected);
+        }
+        // and what was there is left alone
+        assert_eq!(
+            beam.arena[..5].iter().map(|&(w, _)| w).collect::<Vec<_>>(),
+            words
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::core::stable_hash;
+
+const HEADER: &str = "wordle_solver state cache";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadCacheError {
+    // it was saved with different words or settings, so it's no use
+    Stale,
+    Malformed { line: usize, reason: String },
+}
+
+impl fmt::Display for LoadCacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stale => write!(f, "the cache was saved with different words or settings"),
+            Self::Malformed { line, reason } => write!(f, "line {}: {}", line, reason),
+        }
+    }
+}
+
+impl std::error::Error for LoadCacheError {}
+
+// The best guess (and what it scored) for candidate sets that have been seen before. Lots of
+// answers go through the same states partway through a game, so sharing one of these between
+// every game of a benchmark saves working the same guess out over and over. Clones share the
+// same cache, and it can be shared between threads.
+//
+// The key only covers the candidates, so only share a cache between guessers that are set up
+// the same way (same algorithm, scorer, guess list and so on).
+#[derive(Debug, Clone, Default)]
+pub struct StateCache {
+    states: Arc<Mutex<HashMap<u64, (String, f64)>>>,
+    hits: Arc<AtomicUsize>,
+    misses: Arc<AtomicUsize>,
+}
+
+impl StateCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The key for a set of candidates, whatever order they come in. It's a `stable_hash`, so it
+    // stays the same from one build to the next.
+    pub fn key<'a>(candidates: impl IntoIterator<Item = &'a str>) -> u64 {
+        let mut words: Vec<_> = candidates.into_iter().collect();
+        words.sort_unstable();
+        Self::fingerprint(words)
+    }
+
+    // A hash of everything that decides what the best guesses are (the word lists, the
+    // algorithm and its settings), in order. A saved cache only gets loaded back with the same
+    // fingerprint.
+    pub fn fingerprint<'a>(parts: impl IntoIterator<Item = &'a str>) -> u64 {
+        stable_hash(parts)
+    }
+
+    // Every state, one per line after a line with the fingerprint. The order is by key, so
+    // saving the same cache twice gives the same file.
+    pub fn save(&self, fingerprint: u64, mut out: impl Write) -> io::Result<()> {
+        writeln!(out, "{} {:016x}", HEADER, fingerprint)?;
+        let states = self.states.lock().unwrap();
+        let mut keys: Vec<_> = states.keys().copied().collect();
+        keys.sort_unstable();
+        for key in keys {
+            let (guess, value) = &states[&key];
+            writeln!(out, "{:016x} {} {}", key, guess, value)?;
+        }
+        out.flush()
+    }
+
+    // read back what `save` wrote, as long as it was saved with the same fingerprint. Lines
+    // starting with # are comments (like the word list the cache was made with).
+    pub fn load(text: &str, fingerprint: u64) -> Result<Self, LoadCacheError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.starts_with('#'));
+        let saved = lines
+            .next()
+            .and_then(|(_, line)| line.strip_prefix(HEADER))
+            .and_then(|rest| u64::from_str_radix(rest.trim(), 16).ok())
+            .ok_or_else(|| LoadCacheError::Malformed {
+                line: 1,
+                reason: "not a saved state cache".to_string(),
+            })?;
+        if saved != fingerprint {
+            return Err(LoadCacheError::Stale);
+        }
+        let cache = Self::new();
+        let mut states = cache.states.lock().unwrap();
+        for (i, line) in lines {
+            let err = |reason: &str| LoadCacheError::Malformed {
+                line: i + 1,
+                reason: reason.to_string(),
+            };
+            let fields: Vec<_> = line.split_whitespace().collect();
+            let [key, guess, value] = fields[..] else {
+                return Err(err("expected a key, a guess and a value"));
+            };
+            let key = u64::from_str_radix(key, 16).map_err(|_| err("bad key"))?;
+            let value = value.parse().map_err(|_| err("bad value"))?;
+            states.insert(key, (guess.to_string(), value));
+        }
+        drop(states);
+        Ok(cache)
+    }
+
+    pub fn get(&self, key: u64) -> Option<(String, f64)> {
+        let found = self.states.lock().unwrap().get(&key).cloned();
+        let counter = if found.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    pub fn insert(&self, key: u64, guess: &str, value: f64) {
+        self.states
+            .lock()
+            .unwrap()
+            .insert(key, (guess.to_string(), value));
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // how many lookups found something, and how many didn't
+    pub fn stats(&self) -> (usize, usize) {
+        (
+            self.hits.load(Ordering::Relaxed),
+            self.misses.load(Ordering::Relaxed),
+        )
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::HashSet;
+
+use super::{
+    dictionary, letter_set, shortcut, Prior, Progress, Restriction, Scorer, Shortcuts, StateCache,
+};
+use crate::blocklist::Blocklist;
+use crate::cancel::Stop;
+use crate::hard_mode::legal_guesses;
+use crate::timings::{self, Phase};
+use crate::variants::{Spelling, Variants};
+use crate::{Guesser, History};
+
+// words that could still be the answer, with their counts
+type Candidates = Vec<(&'static str, u64)>;
+
+// Plays whichever guess the scorer likes best against what's left, one guess at a time with no
+// looking ahead.
+pub struct Greedy<S> {
+    scorer: S,
+    remaining: Candidates,
+    // sorted, so ties go to the earlier word
+    guesses: Vec<&'static str>,
+    opener: Option<String>,
+    cache: Option<StateCache>,
+    hard_mode: bool,
+    progress: Progress<Candidates>,
+    stop: Option<Stop>,
+}
+
+impl<S: Scorer> Greedy<S> {
+    pub fn new(scorer: S) -> Self {
+        let words: Candidates = timings::time(Phase::Load, || {
+            dictionary()
+                .map(|(word, count)| (word, count as u64))
+                .collect()
+        });
+        Self::with_words(scorer, words)
+    }
+
+    // play from these words (and counts) instead of the bundled dictionary
+    pub fn with_words(scorer: S, words: impl IntoIterator<Item = (&'static str, u64)>) -> Self {
+        let remaining: Candidates = words.into_iter().collect();
+        let mut guesses: Vec<_> = remaining.iter().map(|&(word, _)| word).collect();
+        guesses.sort_unstable();
+        Self {
+            scorer,
+            remaining,
+            guesses,
+            opener: None,
+            cache: None,
+            hard_mode: false,
+            progress: Progress::default(),
+            stop: None,
+        }
+    }
+
+    // Working out the first guess means scoring every word against every word, and it comes out
+    // the same every game, so it's worth working out once (by asking a fresh guesser) and
+    // handing over.
+    pub fn with_opener(mut self, opener: impl Into<String>) -> Self {
+        self.opener = Some(opener.into());
+        self
+    }
+
+    // remember the best guess for each state in `cache`, and look there before working it out
+    pub fn with_cache(mut self, cache: StateCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    // count the guesses it makes without scoring anything in `shortcuts` (see `Shortcuts`)
+    pub fn with_shortcuts(mut self, shortcuts: Shortcuts) -> Self {
+        self.progress.shortcuts = shortcuts;
+        self
+    }
+
+    // Stop scoring once `stop` says to (see `Stop`), and go with the best guess so far (what's
+    // worked out after that isn't cached).
+    pub fn with_stop(mut self, stop: Stop) -> Self {
+        self.stop = Some(stop);
+        self
+    }
+
+    pub fn with_variants(self, variants: &Variants<'static>, spelling: Spelling) -> Self {
+        self.restrict(Restriction::Spelling(variants, spelling))
+    }
+
+    pub fn answers_only(self) -> Self {
+        self.restrict(Restriction::AnswersOnly)
+    }
+
+    // Only the `n` most common words can be the answer, though any word can still be guessed.
+    // Scoring goes faster against fewer candidates.
+    pub fn top_answers(mut self, n: usize) -> Self {
+        // alphabetical among equals so it's the same every time
+        self.remaining
+            .sort_by(|(a, x), (b, y)| y.cmp(x).then(a.cmp(b)));
+        self.remaining.truncate(n);
+        self
+    }
+
+    // weigh the candidates by `prior` rather than by their counts
+    pub fn with_prior(mut self, prior: &dyn Prior) -> Self {
+        let weights = prior.weights(&self.remaining);
+        for ((_, count), weight) in self.remaining.iter_mut().zip(weights) {
+            *count = weight;
+        }
+        self
+    }
+
+    // only make guesses that use every hint so far
+    pub fn hard_mode(mut self) -> Self {
+        self.hard_mode = true;
+        self
+    }
+
+    pub fn with_blocklist(self, blocklist: &Blocklist) -> Self {
+        self.restrict(Restriction::Blocklist(blocklist))
+    }
+
+    // the options every guesser has for which words it plays with (see `Restriction`)
+    fn restrict(mut self, restriction: Restriction) -> Self {
+        restriction.apply(&mut self.remaining, &mut self.guesses);
+        self
+    }
+}
+
+impl<S: Scorer> Guesser for Greedy<S> {
+    fn guess(&mut self, history: &History) -> String {
+        if let (true, Some(opener)) = (history.is_empty(), &self.opener) {
+            return opener.clone();
+        }
+        let (_, unseen) = self
+            .progress
+            .catch_up(history, &mut self.remaining, &mut self.guesses);
+        timings::time(Phase::Filtering, || {
+            for guess in unseen {
+                // a mask that rules out every word left was misread or mistyped, and going by the
+                // rest of the game beats giving up on it
+                if self.remaining.iter().any(|&(word, _)| guess.matches(word)) {
+                    self.remaining.retain(|&(word, _)| guess.matches(word));
+                }
+            }
+        });
+        if self.hard_mode {
+            // the hints only pile up, so a guess that's out now stays out
+            self.guesses = legal_guesses(history, self.guesses.drain(..)).collect();
+        }
+        let allowed = |word: &str| self.guesses.binary_search(&word).is_ok();
+        if let Some(word) = shortcut(
+            self.remaining.iter().copied(),
+            allowed,
+            &self.progress.shortcuts,
+        ) {
+            return word.to_string();
+        }
+        let key = self
+            .cache
+            .as_ref()
+            .map(|_| StateCache::key(self.remaining.iter().map(|&(word, _)| word)));
+        if let (Some(cache), Some(key)) = (&self.cache, key) {
+            if let Some((guess, _)) = cache.get(key) {
+                return guess;
+            }
+        }
+        // same cheap pruning as naive: guesses sharing no letters with any candidate are useless
+        let letters = self
+            .remaining
+            .iter()
+            .fold(0u32, |acc, &(word, _)| acc | letter_set(word));
+        self.guesses
+            .retain(|&guess| letter_set(guess) & letters != 0);
+
+        // on a tie, a guess that could win outright beats one that can't
+        let candidates: HashSet<_> = self.remaining.iter().map(|&(word, _)| word).collect();
+        let mut best: Option<(&'static str, f64, bool)> = None;
+        let mut stopped = false;
+        for &guess in &self.guesses {
+            if self.stop.as_ref().is_some_and(Stop::check) {
+                stopped = true;
+                break;
+            }
+            let score = self.scorer.score(guess, &self.remaining);
+            let candidate = candidates.contains(guess);
+            if best.is_none_or(|(_, s, c)| score > s || (score == s && candidate && !c)) {
+                best = Some((guess, score, candidate));
+            }
+        }
+        if let (Some(cache), Some(key), Some((guess, score, _)), false) =
+            (&self.cache, key, best, stopped)
+        {
+            cache.insert(key, guess, score);
+        }
+        match best {
+            Some((guess, _, _)) => guess.to_string(),
+            // only blocked words could tell the rest apart, so just try one of them
+            None => self
+                .remaining
+                .iter()
+                .map(|&(word, _)| word)
+                .min()
+                .unwrap_or_default()
+                .to_string(),
+        }
+    }
+}
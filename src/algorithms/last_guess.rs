@@ -0,0 +1,62 @@
+use crate::{Guess, Guesser, History};
+
+// What to play with the last guess a game allows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RiskMode {
+    // whatever the guesser would play anyway
+    #[default]
+    Score,
+    // The likeliest word that could still be the answer. A guess that only narrows things down
+    // is wasted when there's nothing after it, and the scorers don't know that (entropy will
+    // happily split six words up rather than take a one in six shot).
+    Win,
+}
+
+// Wraps a guesser to play the last guess of a game by `RiskMode`. It only counts guesses, so it
+// has to be told how many the game allows.
+pub struct LastGuess<'w, G> {
+    guesser: G,
+    mode: RiskMode,
+    // the words that could be the answer, with their counts
+    words: &'w [(&'static str, u64)],
+    max_guesses: usize,
+}
+
+impl<'w, G: Guesser> LastGuess<'w, G> {
+    pub fn new(guesser: G, mode: RiskMode, words: &'w [(&'static str, u64)]) -> Self {
+        Self {
+            guesser,
+            mode,
+            words,
+            max_guesses: 6,
+        }
+    }
+
+    pub fn with_max_guesses(mut self, max_guesses: usize) -> Self {
+        self.max_guesses = max_guesses;
+        self
+    }
+}
+
+impl<G: Guesser> Guesser for LastGuess<'_, G> {
+    fn guess(&mut self, history: &History) -> String {
+        if self.mode == RiskMode::Win && history.len() + 1 == self.max_guesses {
+            if let Some(word) = likeliest(history, self.words) {
+                return word.to_string();
+            }
+        }
+        self.guesser.guess(history)
+    }
+}
+
+// the most common word that fits the history, the earlier one on a tie
+pub fn likeliest<'a, const N: usize>(
+    history: &[Guess<N>],
+    words: &[(&'a str, u64)],
+) -> Option<&'a str> {
+    words
+        .iter()
+        .filter(|&&(word, _)| history.iter().all(|guess| guess.matches(word)))
+        .max_by(|(a, x), (b, y)| x.cmp(y).then(b.cmp(a)))
+        .map(|&(word, _)| word)
+}
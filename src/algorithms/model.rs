@@ -0,0 +1,119 @@
+use super::{answers, dictionary, shortcut, Progress, Restriction, Shortcuts};
+use crate::blocklist::Blocklist;
+use crate::features::Features;
+use crate::timings::{self, Phase};
+use crate::{Guesser, History};
+
+// Leaves the choice of guess to an outside scoring function, such as a learned policy. Every
+// turn, each allowed guess is scored against the encoded game state (see `Features::encode`)
+// and the highest scoring one is played.
+pub struct ModelGuesser<F> {
+    score: F,
+    answers: Vec<&'static str>,
+    guesses: Vec<&'static str>,
+    candidates_only: bool,
+    progress: Progress<Vec<&'static str>>,
+}
+
+impl<F> ModelGuesser<F>
+where
+    F: FnMut(&[f32], &str) -> f64,
+{
+    // consider every dictionary word as a guess
+    pub fn new(score: F) -> Self {
+        Self {
+            score,
+            answers: answers().collect(),
+            guesses: dictionary().map(|(word, _)| word).collect(),
+            candidates_only: false,
+            progress: Progress::default(),
+        }
+    }
+
+    // only consider guesses that could still be the answer
+    pub fn candidates_only(score: F) -> Self {
+        Self {
+            score,
+            answers: answers().collect(),
+            guesses: answers().collect(),
+            candidates_only: true,
+            progress: Progress::default(),
+        }
+    }
+
+    pub fn with_blocklist(mut self, blocklist: &Blocklist) -> Self {
+        Restriction::Blocklist(blocklist).apply(&mut self.answers, &mut self.guesses);
+        self
+    }
+
+    // count the guesses it makes without scoring anything in `shortcuts` (see `Shortcuts`)
+    pub fn with_shortcuts(mut self, shortcuts: Shortcuts) -> Self {
+        self.progress.shortcuts = shortcuts;
+        self
+    }
+}
+
+impl<F> Guesser for ModelGuesser<F>
+where
+    F: FnMut(&[f32], &str) -> f64,
+{
+    fn guess(&mut self, history: &History) -> String {
+        let (_, unseen) = self
+            .progress
+            .catch_up(history, &mut self.answers, &mut self.guesses);
+        timings::time(Phase::Filtering, || {
+            for guess in unseen {
+                self.answers.retain(|word| guess.matches(word));
+                if self.candidates_only {
+                    self.guesses.retain(|word| guess.matches(word));
+                }
+            }
+        });
+        // the answers come without counts, so of two it's the earlier one
+        let candidates = self.answers.iter().map(|&word| (word, 1));
+        let allowed = |word: &str| self.guesses.contains(&word);
+        if let Some(word) = shortcut(candidates, allowed, &self.progress.shortcuts) {
+            return word.to_string();
+        }
+        let state = Features::new(history, &self.answers).encode();
+        let mut best: Option<(&'static str, f64)> = None;
+        for &guess in &self.guesses {
+            let score = (self.score)(&state, guess);
+            if best.is_none_or(|(_, s)| score > s) {
+                best = Some((guess, score));
+            }
+        }
+        best.expect("there is always something to guess")
+            .0
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Wordle;
+
+    #[test]
+    fn plays_the_best_scoring_guess() {
+        let w = Wordle::new();
+        let model = ModelGuesser::new(|_: &[f32], guess: &str| (guess == "right") as u8 as f64);
+        assert_eq!(w.play("right", model), Ok(Some(1)));
+    }
+
+    #[test]
+    fn sees_the_game_state() {
+        let w = Wordle::new();
+        let mut rounds = Vec::new();
+        let model = ModelGuesser::new(|state: &[f32], guess: &str| {
+            let round = state[state.len() - 2];
+            rounds.push(round);
+            match (round as usize, guess) {
+                (0, "wrong") | (1, "right") => 1.0,
+                _ => 0.0,
+            }
+        });
+        assert_eq!(w.play("right", model), Ok(Some(2)));
+        assert!(rounds.contains(&0.0) && rounds.contains(&1.0));
+    }
+}
@@ -1,59 +1,374 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::{Guess, Guesser};
-
-const DICTIONARY: &str = include_str!("../../dictionary.txt");
+use super::{dictionary, letter_set, shortcut, Prior, Progress, Restriction, Shortcuts};
+use crate::blocklist::Blocklist;
+use crate::cancel::Stop;
+use crate::core::{entropy, partition_counts, partition_counts_on_gpu, pattern_index};
+use crate::hard_mode::legal_guesses;
+use crate::openers;
+use crate::timings::{self, Phase};
+use crate::variants::{Spelling, Variants};
+use crate::{Correctness, Guesser, History};
 
 pub struct Naive {
     remaining: HashMap<&'static str, usize>,
+    // every word we might still want to guess; shrinks as guesses become useless
+    guesses: Vec<&'static str>,
+    progress: Progress<HashMap<&'static str, usize>>,
+    // how many of the best guesses to pick from at random, and the xorshift state for picking
+    handicap: Option<(usize, u64)>,
+    hard_mode: bool,
+    tables: Option<Tables>,
+    // what its opener is bundled as (see `openers`), until its words or weights stop being the
+    // bundled ones
+    bundled: Option<&'static str>,
+    stop: Option<Stop>,
+}
+
+// Every guess's bucket weights against `remaining` as of the last time they were counted, and
+// the words ruled out since. Taking those out of the buckets beats counting everything that's
+// left again whenever fewer words went than stayed, which is the late game, or a guess that
+// didn't tell us much.
+#[derive(Default)]
+struct Tables {
+    buckets: HashMap<&'static str, [u64; 243]>,
+    removed: Vec<(&'static str, usize)>,
 }
 
 #[derive(Debug, Clone, Copy)]
 struct Candidate {
     word: &'static str,
-    count: usize,
     goodness: f64,
 }
 
+impl Default for Naive {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Naive {
     pub fn new() -> Self {
+        let remaining: HashMap<_, _> = timings::time(Phase::Load, || dictionary().collect());
+        let mut guesses: Vec<_> = remaining.keys().copied().collect();
+        // keep guess order stable so ties are broken the same way every run
+        guesses.sort_unstable();
         Self {
-            remaining: HashMap::from_iter(DICTIONARY.lines().map(|line| {
-                let (word, count) = line
-                    .split_once(' ')
-                    .expect("every line is word + space + word count");
-                let count: usize = count.parse().expect("every count is a number");
-                (word, count)
-            })),
+            remaining,
+            guesses,
+            progress: Progress::default(),
+            handicap: None,
+            hard_mode: false,
+            tables: None,
+            bundled: Some("naive"),
+            stop: None,
+        }
+    }
+
+    pub fn with_variants(self, variants: &Variants<'static>, spelling: Spelling) -> Self {
+        self.restrict(Restriction::Spelling(variants, spelling))
+    }
+
+    pub fn answers_only(self) -> Self {
+        self.restrict(Restriction::AnswersOnly)
+    }
+
+    // Only the `n` most common words can be the answer, though any word can still be guessed.
+    // Scoring goes faster against fewer candidates.
+    pub fn top_answers(mut self, n: usize) -> Self {
+        let mut words: Vec<_> = self
+            .remaining
+            .iter()
+            .map(|(&word, &count)| (word, count))
+            .collect();
+        // alphabetical among equals so it's the same every time
+        words.sort_by(|(a, x), (b, y)| y.cmp(x).then(a.cmp(b)));
+        let top: HashSet<_> = words.into_iter().take(n).map(|(word, _)| word).collect();
+        self.remaining.retain(|word, _| top.contains(word));
+        self.bundled = None;
+        self
+    }
+
+    // weigh the candidates by `prior` rather than by their counts
+    pub fn with_prior(mut self, prior: &dyn Prior) -> Self {
+        let words: Vec<_> = self
+            .remaining
+            .iter()
+            .map(|(&word, &count)| (word, count as u64))
+            .collect();
+        let weights = prior.weights(&words);
+        for ((word, count), weight) in words.into_iter().zip(weights) {
+            // weighing by the counts themselves keeps the bundled opener
+            if weight != count {
+                self.bundled = None;
+            }
+            self.remaining.insert(word, weight as usize);
         }
+        self
+    }
+
+    pub fn with_blocklist(self, blocklist: &Blocklist) -> Self {
+        self.restrict(Restriction::Blocklist(blocklist))
+    }
+
+    // the options every guesser has for which words it plays with (see `Restriction`)
+    fn restrict(mut self, restriction: Restriction) -> Self {
+        self.bundled = match restriction {
+            // the bundled opener still goes, unless it's blocked (see `guess`)
+            Restriction::Blocklist(_) => self.bundled,
+            Restriction::AnswersOnly => self.bundled.and(Some("naive:answers-only")),
+            Restriction::Spelling(..) => None,
+        };
+        restriction.apply(&mut self.remaining, &mut self.guesses);
+        self
+    }
+
+    // work the opener out like any other guess, even where there's a bundled one, which is how
+    // the bundled ones are made
+    pub(crate) fn without_bundled_opener(mut self) -> Self {
+        self.bundled = None;
+        self
+    }
+
+    // Keep every guess's buckets between rounds and update them as words are ruled out (see
+    // `Tables`). It takes a couple of kilobytes per guess, so tens of megabytes for the whole
+    // dictionary.
+    pub fn incremental(mut self) -> Self {
+        self.tables = Some(Tables::default());
+        self
+    }
+
+    // count the guesses it makes without scoring anything in `shortcuts` (see `Shortcuts`)
+    pub fn with_shortcuts(mut self, shortcuts: Shortcuts) -> Self {
+        self.progress.shortcuts = shortcuts;
+        self
+    }
+
+    // stop scoring once `stop` says to (see `Stop`), and go with the best guess so far
+    pub fn with_stop(mut self, stop: Stop) -> Self {
+        self.stop = Some(stop);
+        self
+    }
+
+    // only make guesses that use every hint so far
+    pub fn hard_mode(mut self) -> Self {
+        self.hard_mode = true;
+        self
+    }
+
+    // play a random one of the `top` best guesses instead of the very best, for an opponent
+    // people can actually beat
+    pub fn with_handicap(mut self, top: usize, seed: u64) -> Self {
+        assert!(top > 0, "need at least one guess to pick from");
+        // xorshift gets stuck on zero
+        self.handicap = Some((top, seed.max(1)));
+        // it's meant to miss the best guess now and then, the first one included
+        self.bundled = None;
+        self
+    }
+
+    // Drop every guess sharing no letters with any word that's left, since it can only come back
+    // all gray. Guesses that give one mask for some other reason are dropped while scoring (see
+    // `useless` in `guess`). `remaining` only ever shrinks, so a guess that is useless now stays
+    // useless.
+    fn prune(&mut self) {
+        let letters = self
+            .remaining
+            .keys()
+            .fold(0u32, |acc, word| acc | letter_set(word));
+        self.guesses
+            .retain(|&guess| letter_set(guess) & letters != 0);
     }
 }
 
 impl Guesser for Naive {
-    fn guess(&mut self, history: &[Guess]) -> String {
-        if let Some(last) = history.last() {
-            self.remaining.retain(|word, _| last.matches(word));
+    fn guess(&mut self, history: &History) -> String {
+        // the opener is the same every game and expensive to compute, so use the bundled one
+        let opener = self.bundled.filter(|_| history.is_empty());
+        if let Some(opener) = opener.and_then(openers::bundled) {
+            if self.guesses.binary_search(&opener).is_ok() {
+                return opener.to_string();
+            }
         }
-        let mut best: Option<Candidate> = None;
-        let goodness = 0.0;
-        for (&word, &count) in &self.remaining {
-            if let Some(c) = best {
-                // todo!();
-                if goodness > c.goodness {
-                    best = Some(Candidate {
-                        word,
-                        count,
-                        goodness,
-                    })
+        let (restart, unseen) =
+            self.progress
+                .catch_up(history, &mut self.remaining, &mut self.guesses);
+        if restart {
+            if let Some(tables) = &mut self.tables {
+                *tables = Tables::default();
+            }
+        }
+        timings::time(Phase::Filtering, || {
+            for guess in unseen {
+                // a mask that rules out every word left was misread or mistyped, and going by the
+                // rest of the game beats giving up on it
+                if self.remaining.keys().any(|word| guess.matches(word)) {
+                    if let Some(tables) = &mut self.tables {
+                        let removed = self.remaining.iter().filter(|(w, _)| !guess.matches(w));
+                        tables
+                            .removed
+                            .extend(removed.map(|(&word, &count)| (word, count)));
+                    }
+                    self.remaining.retain(|word, _| guess.matches(word));
                 }
+            }
+        });
+        if self.hard_mode {
+            // the hints only pile up, so a guess that's out now stays out
+            self.guesses = legal_guesses(history, self.guesses.drain(..)).collect();
+        }
+        // With one word left every guess scores zero, so just go for it (even if it's blocked,
+        // since it's the only way to win). A handicap is meant to miss the best guess now and
+        // then, so it still gets to with two left.
+        if self.remaining.len() == 1 || self.handicap.is_none() {
+            let candidates = self
+                .remaining
+                .iter()
+                .map(|(&word, &count)| (word, count as u64));
+            let allowed = |word: &str| self.guesses.binary_search(&word).is_ok();
+            if let Some(word) = shortcut(candidates, allowed, &self.progress.shortcuts) {
+                return word.to_string();
+            }
+        }
+        self.prune();
+
+        let mut best: Option<Candidate> = None;
+        let mut scored = Vec::new();
+        let mut useless = vec![false; self.guesses.len()];
+        let update = self
+            .tables
+            .as_ref()
+            .is_some_and(|t| !t.buckets.is_empty() && t.removed.len() < self.remaining.len());
+        let remaining: Vec<_> = self
+            .remaining
+            .iter()
+            .map(|(&candidate, &count)| (candidate, count as u64))
+            .collect();
+        let on_gpu = match update {
+            true => None,
+            false => partition_counts_on_gpu(&self.guesses, &remaining),
+        };
+        let mut stopped = false;
+        for (i, &word) in self.guesses.iter().enumerate() {
+            if self.stop.as_ref().is_some_and(Stop::check) {
+                stopped = true;
+                break;
+            }
+            // sum up the weight of the remaining words that would give each possible mask
+            let count = || match &on_gpu {
+                Some(counts) => counts[i],
+                None => partition_counts(word, remaining.iter().copied()),
+            };
+            let buckets = match &mut self.tables {
+                Some(Tables { buckets, removed }) => match buckets.get_mut(word) {
+                    Some(buckets) if update => {
+                        for &(gone, count) in removed.iter() {
+                            buckets[pattern_index(Correctness::compute(gone, word))] -=
+                                count as u64;
+                        }
+                        *buckets
+                    }
+                    _ => *buckets.entry(word).insert_entry(count()).get(),
+                },
+                None => count(),
+            };
+            if buckets.iter().filter(|&&b| b != 0).count() == 1 {
+                // every remaining word gives the same mask, so this guess tells us nothing
+                useless[i] = true;
+                continue;
+            }
+            // expected information gained, in bits
+            let goodness = entropy(buckets);
+            if best.is_none_or(|c| goodness > c.goodness) {
+                best = Some(Candidate { word, goodness });
+            }
+            if self.handicap.is_some() {
+                scored.push(Candidate { word, goodness });
+            }
+        }
+        if let (Some((top, rng)), false) = (&mut self.handicap, scored.is_empty()) {
+            // stable, so ties stay in alphabetical order
+            scored.sort_by(|a, b| b.goodness.total_cmp(&a.goodness));
+            scored.truncate(*top);
+            // xorshift64
+            *rng ^= *rng << 13;
+            *rng ^= *rng >> 7;
+            *rng ^= *rng << 17;
+            best = Some(scored[(*rng % scored.len() as u64) as usize]);
+        }
+        if let Some(tables) = &mut self.tables {
+            if stopped {
+                // only some of the buckets were brought up to date, so count them all next time
+                *tables = Tables::default();
             } else {
-                best = Some(Candidate {
-                    word,
-                    count,
-                    goodness,
-                });
+                tables.removed.clear();
             }
         }
-        best.unwrap().word.to_string()
+        let mut useless = useless.into_iter();
+        self.guesses.retain(|_| !useless.next().unwrap());
+        match best {
+            Some(c) => c.word.to_string(),
+            // only blocked words could tell the rest apart, so just try one of them
+            None => self.remaining.keys().min().unwrap().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::bits;
+
+    fn naive(candidates: &[&'static str], guesses: &[&'static str]) -> Naive {
+        let mut guesses = guesses.to_vec();
+        guesses.sort_unstable();
+        Naive {
+            remaining: candidates.iter().map(|&word| (word, 1)).collect(),
+            guesses,
+            progress: Progress::default(),
+            handicap: None,
+            hard_mode: false,
+            tables: None,
+            bundled: None,
+            stop: None,
+        }
+    }
+
+    #[test]
+    fn prune() {
+        let candidates = ["right", "might", "sight", "fight", "light"];
+        let mut guesses = candidates.to_vec();
+        // shares no letters with any of them, unlike the rest
+        guesses.extend(["woken", "flams", "mists"]);
+        let mut pruned = naive(&candidates, &guesses);
+        pruned.prune();
+        assert!(!pruned.guesses.contains(&"woken"));
+        assert_eq!(pruned.guesses.len(), guesses.len() - 1);
+
+        // the best guess out of all of them, without any pruning
+        let weighted: Vec<_> = candidates.iter().map(|&word| (word, 1)).collect();
+        let mut sorted = guesses.clone();
+        sorted.sort_unstable();
+        let best = sorted
+            .iter()
+            .fold(("", 0.0), |best, &guess| match bits(&weighted, guess) {
+                b if b > best.1 => (guess, b),
+                _ => best,
+            });
+        let mut naive = naive(&candidates, &guesses);
+        assert_eq!(naive.guess(&History::new()), best.0);
+    }
+
+    #[test]
+    fn handicapped_openers() {
+        let candidates = ["right", "might", "sight", "fight", "light", "tares"];
+        let openers: HashSet<_> = (1..20)
+            .map(|seed| {
+                naive(&candidates, &candidates)
+                    .with_handicap(3, seed)
+                    .guess(&History::new())
+            })
+            .collect();
+        assert!(openers.len() > 1, "{:?}", openers);
     }
 }
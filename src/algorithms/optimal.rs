@@ -0,0 +1,449 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::rc::Rc;
+
+use super::{answers, dictionary, Patterns, WIN};
+use crate::blocklist::Blocklist;
+use crate::cancel::{Cancel, Cancelled};
+use crate::core::pattern_index;
+use crate::timings::{self, Phase};
+use crate::tree::Tree;
+use crate::{Correctness, GuessPolicy, Guesser, History};
+
+// Plays a precomputed decision tree that minimizes the total number of guesses over a fixed
+// answer list. Building the tree is expensive, so build it once and share it between games.
+pub struct Optimal {
+    tree: Rc<Tree>,
+}
+
+impl Optimal {
+    // How many guesses per state the binary tries unless it's told otherwise. An unlimited width
+    // is provably optimal but doesn't finish in any reasonable time on the official words, while
+    // this builds the tree in seconds (in a release build) and is only a few guesses worse.
+    pub const DEFAULT_WIDTH: usize = 2;
+
+    pub fn new(tree: Rc<Tree>) -> Self {
+        Self { tree }
+    }
+
+    // the tree for the official answer list, considering every word the policy allows that isn't
+    // blocked as a guess (permissive games still only try dictionary words). `width` limits how
+    // many guesses are tried per state (best bound first); with `None` the result is provably
+    // optimal, but that can take a very long time. `table_limit` caps the mask table in bytes (see
+    // `Patterns`).
+    pub fn official(
+        width: Option<usize>,
+        blocklist: &Blocklist,
+        policy: GuessPolicy,
+        table_limit: Option<usize>,
+    ) -> Tree {
+        Self::official_until(width, blocklist, policy, table_limit, &Cancel::new())
+            .expect("nothing else can cancel it")
+    }
+
+    // `official`, giving up once `cancel` is cancelled
+    pub fn official_until(
+        width: Option<usize>,
+        blocklist: &Blocklist,
+        policy: GuessPolicy,
+        table_limit: Option<usize>,
+        cancel: &Cancel,
+    ) -> Result<Tree, Cancelled> {
+        let (candidates, guesses) = official_words(blocklist, policy);
+        Self::build_until(&candidates, &guesses, width, table_limit, cancel)
+    }
+
+    // how many bytes the mask table for `official` takes, if it's allowed that many
+    pub fn official_table_size(blocklist: &Blocklist, policy: GuessPolicy) -> usize {
+        let (candidates, guesses) = official_words(blocklist, policy);
+        Patterns::size(guesses.len(), candidates.len())
+    }
+
+    pub fn build(
+        candidates: &[&'static str],
+        guesses: &[&'static str],
+        width: Option<usize>,
+        table_limit: Option<usize>,
+    ) -> Tree {
+        Self::build_until(candidates, guesses, width, table_limit, &Cancel::new())
+            .expect("nothing else can cancel it")
+    }
+
+    // `build`, giving up once `cancel` is cancelled (which the search checks at every candidate
+    // set it looks at, so it stops soon after)
+    pub fn build_until(
+        candidates: &[&'static str],
+        guesses: &[&'static str],
+        width: Option<usize>,
+        table_limit: Option<usize>,
+        cancel: &Cancel,
+    ) -> Result<Tree, Cancelled> {
+        timings::time(Phase::Precompute, || {
+            assert!(!candidates.is_empty(), "need at least one candidate answer");
+            assert!(
+                candidates.len() <= u16::MAX as usize,
+                "candidates are indexed by u16"
+            );
+            // before the mask table, which takes a while itself
+            if cancel.is_cancelled() {
+                return Err(Cancelled);
+            }
+            let mut search = Search {
+                candidates,
+                guesses,
+                patterns: Patterns::new(guesses, candidates, table_limit),
+                width,
+                solved: HashMap::new(),
+                bounds: HashMap::new(),
+                arena: (0..candidates.len() as u16).collect(),
+                scratch: Vec::new(),
+                cancel,
+            };
+            let solved = search.solve(0..candidates.len(), usize::MAX);
+            if cancel.is_cancelled() {
+                return Err(Cancelled);
+            }
+            let (_, tree) = solved.expect("some guess always splits a set of distinct words");
+            Ok(tree)
+        })
+    }
+}
+
+// the answers, and the guesses the policy allows that aren't blocked
+fn official_words(
+    blocklist: &Blocklist,
+    policy: GuessPolicy,
+) -> (Vec<&'static str>, Vec<&'static str>) {
+    let candidates: Vec<_> = answers().collect();
+    let guesses: Vec<_> = match policy {
+        GuessPolicy::Strict | GuessPolicy::Permissive => {
+            dictionary().map(|(word, _)| word).collect()
+        }
+        GuessPolicy::AnswersOnly => candidates.clone(),
+    };
+    let guesses = guesses
+        .into_iter()
+        .filter(|word| !blocklist.contains(word))
+        .collect();
+    (candidates, guesses)
+}
+
+impl Guesser for Optimal {
+    fn guess(&mut self, history: &History) -> String {
+        self.tree
+            .follow(history)
+            .expect("the game strayed from the decision tree")
+            .guess
+            .clone()
+    }
+}
+
+// A depth-first branch and bound over guesses, visiting the guesses with the smallest lower
+// bound first (as in A*) and memoizing every candidate set it has fully solved.
+struct Search<'a> {
+    candidates: &'a [&'static str],
+    guesses: &'a [&'static str],
+    patterns: Patterns<'a>,
+    width: Option<usize>,
+    // exact total guess counts for candidate sets we've solved
+    solved: HashMap<Vec<u16>, (usize, Tree)>,
+    // better-than-size lower bounds learned from searches that failed to beat a budget
+    bounds: HashMap<Vec<u16>, usize>,
+    // The candidate sets being searched, used like a stack (as in `Beam`): a set's buckets go on
+    // the end, and are cut off again once it's done with them.
+    arena: Vec<u16>,
+    // the pattern of each candidate while one set is split into buckets
+    scratch: Vec<u8>,
+    // once it's cancelled every set fails to beat its budget, which unwinds the whole search
+    cancel: &'a Cancel,
+}
+
+impl Search<'_> {
+    fn lower_bound(&self, set: &[u16]) -> usize {
+        let learned = self.bounds.get(set).copied().unwrap_or(0);
+        size_bound(set.len()).max(learned)
+    }
+
+    // the optimal total number of guesses (summed over every candidate in `range` of the arena)
+    // and the tree achieving it, as long as that total is below `budget`
+    fn solve(&mut self, range: Range<usize>, budget: usize) -> Option<(usize, Tree)> {
+        if self.cancel.is_cancelled() {
+            return None;
+        }
+        let n = range.len();
+        if n == 1 {
+            let word = self.candidates[self.arena[range.start] as usize];
+            return (1 < budget).then(|| (1, Tree::leaf(word)));
+        }
+        let set = &self.arena[range.clone()];
+        if let Some((total, tree)) = self.solved.get(set) {
+            return (*total < budget).then(|| (*total, tree.clone()));
+        }
+        if self.lower_bound(set) >= budget {
+            return None;
+        }
+
+        // bound every guess by the sizes of the buckets it splits the set into
+        let mut options = Vec::new();
+        // if none of the allowed guesses help, the candidates themselves always do (and they
+        // aren't in the table, so they go without a row)
+        let fallback = (0..self.guesses.len()).all(|row| !self.splits(row, set));
+        let rows = (!fallback)
+            .then_some(0..self.guesses.len())
+            .into_iter()
+            .flatten()
+            .map(|row| (Some(row), self.guesses[row]));
+        let candidates = fallback
+            .then_some(set)
+            .into_iter()
+            .flatten()
+            .map(|&c| (None, self.candidates[c as usize]));
+        for guess in rows.chain(candidates) {
+            let mut counts = [0u64; 243];
+            for &c in set {
+                counts[self.pattern(guess, c)] += 1;
+            }
+            if counts.contains(&(n as u64)) {
+                // everything lands in the same bucket, so this guess gets us nowhere
+                continue;
+            }
+            let bound = n + counts
+                .iter()
+                .enumerate()
+                .filter(|&(pattern, _)| pattern != WIN)
+                .map(|(_, &count)| size_bound(count as usize))
+                .sum::<usize>();
+            let largest = counts.iter().max().copied().unwrap_or(0) as usize;
+            options.push((bound, largest, guess));
+        }
+        options.sort_by_key(|&(bound, largest, _)| (bound, largest));
+        if let Some(width) = self.width {
+            options.truncate(width);
+        }
+
+        let mut best = budget;
+        let mut best_tree = None;
+        for (bound, _, guess) in options {
+            if bound >= best {
+                // options are sorted, so nothing after this can do better either
+                break;
+            }
+            let end = self.arena.len();
+            let buckets = self.split(range.clone(), guess);
+            let bucket = |pattern: usize| buckets[pattern]..buckets[pattern + 1];
+            // the guess itself needs nothing more
+            let patterns =
+                (0..243).filter(|&pattern| pattern != WIN && !bucket(pattern).is_empty());
+            let mut pending: usize = patterns
+                .clone()
+                .map(|pattern| self.lower_bound(&self.arena[bucket(pattern)]))
+                .sum();
+            let mut total = n;
+            let mut children = Vec::new();
+            let mut complete = total + pending < best;
+            for pattern in patterns {
+                if !complete {
+                    break;
+                }
+                pending -= self.lower_bound(&self.arena[bucket(pattern)]);
+                let first = self.candidates[self.arena[bucket(pattern).start] as usize];
+                match self.solve(bucket(pattern), best - total - pending) {
+                    Some((cost, tree)) => {
+                        total += cost;
+                        children.push((Correctness::compute(first, guess.1), tree));
+                    }
+                    None => complete = false,
+                }
+            }
+            self.arena.truncate(end);
+            if complete && total < best {
+                best = total;
+                best_tree = Some(Tree {
+                    guess: guess.1.to_string(),
+                    children,
+                });
+            }
+        }
+
+        let set = self.arena[range].to_vec();
+        match best_tree {
+            Some(tree) => {
+                self.solved.insert(set, (best, tree.clone()));
+                Some((best, tree))
+            }
+            None => {
+                let bound = self.lower_bound(&set).max(budget);
+                self.bounds.insert(set, bound);
+                None
+            }
+        }
+    }
+
+    // the pattern index of `guess` against candidate `c`, from the table when the guess has a row
+    // in it
+    fn pattern(&self, (row, guess): (Option<usize>, &str), c: u16) -> usize {
+        match row {
+            Some(row) => self.patterns.get(row, c as usize),
+            None => pattern_index(Correctness::compute(self.candidates[c as usize], guess)),
+        }
+    }
+
+    // whether the guess at `row` gives at least two different masks for `set`
+    fn splits(&self, row: usize, set: &[u16]) -> bool {
+        let first = self.patterns.get(row, set[0] as usize);
+        set.iter()
+            .any(|&c| self.patterns.get(row, c as usize) != first)
+    }
+
+    // Put the candidates in `range` of the arena on its end again, bucketed by the pattern
+    // `guess` gets against them (in the same order within a bucket). Bucket `p` is
+    // `buckets[p]..buckets[p + 1]`, and bucket `WIN` is the guess itself if it's there.
+    fn split(&mut self, range: Range<usize>, guess: (Option<usize>, &str)) -> [usize; 244] {
+        let mut counts = [0; 243];
+        self.scratch.clear();
+        for &c in &self.arena[range.clone()] {
+            let pattern = self.pattern(guess, c);
+            counts[pattern] += 1;
+            self.scratch.push(pattern as u8);
+        }
+        let mut buckets = [self.arena.len(); 244];
+        for pattern in 0..243 {
+            buckets[pattern + 1] = buckets[pattern] + counts[pattern];
+        }
+        let mut next = buckets;
+        self.arena.resize(buckets[243], 0);
+        for (i, k) in range.zip(0..) {
+            let pattern = self.scratch[k] as usize;
+            self.arena[next[pattern]] = self.arena[i];
+            next[pattern] += 1;
+        }
+        buckets
+    }
+}
+
+// An admissible lower bound on the total guesses needed for `n` candidates: one of them can be
+// guessed right away, each of the (at most 242) other masks can then be won one guess later,
+// each of those has 242 masks of its own, and so on.
+fn size_bound(n: usize) -> usize {
+    let mut total = 0;
+    let mut left = n;
+    let mut depth = 1;
+    let mut capacity = 1usize;
+    while left > 0 {
+        let here = left.min(capacity);
+        total += here * depth;
+        left -= here;
+        depth += 1;
+        capacity = capacity.saturating_mul(242);
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(tree: &Tree, depth: usize, candidates: &[&str]) -> usize {
+        candidates
+            .iter()
+            .filter(|&&c| c == tree.guess)
+            .map(|_| depth)
+            .sum::<usize>()
+            + tree
+                .children
+                .iter()
+                .map(|(mask, child)| {
+                    let bucket: Vec<_> = candidates
+                        .iter()
+                        .copied()
+                        .filter(|c| Correctness::compute(c, &tree.guess) == *mask)
+                        .collect();
+                    total(child, depth + 1, &bucket)
+                })
+                .sum::<usize>()
+    }
+
+    #[test]
+    fn size_bound_counts_one_word_per_bucket() {
+        assert_eq!(size_bound(1), 1);
+        assert_eq!(size_bound(2), 3);
+        assert_eq!(size_bound(243), 1 + 242 * 2);
+        assert_eq!(size_bound(244), 1 + 242 * 2 + 3);
+    }
+
+    #[test]
+    fn two_candidates() {
+        let tree = Optimal::build(&["right", "wrong"], &["right", "wrong"], None, None);
+        assert_eq!(total(&tree, 1, &["right", "wrong"]), 3);
+    }
+
+    #[test]
+    fn probe_beats_candidates() {
+        // guessing any of the four candidates leaves three we can't tell apart, while the
+        // probe splits all four apart at once
+        let candidates = ["baker", "caker", "daker", "faker"];
+        let guesses = ["baker", "caker", "daker", "faker", "bcdfg"];
+        let tree = Optimal::build(&candidates, &guesses, None, None);
+        assert_eq!(tree.guess, "bcdfg");
+        assert_eq!(total(&tree, 1, &candidates), 8);
+    }
+
+    #[test]
+    fn falls_back_to_candidates() {
+        // as if every useful guess had been blocked
+        let tree = Optimal::build(&["right", "wrong"], &["zzzzz"], None, None);
+        assert!(tree.guess == "right" || tree.guess == "wrong");
+        assert_eq!(total(&tree, 1, &["right", "wrong"]), 3);
+    }
+
+    #[test]
+    fn width_limited_search_still_solves_everything() {
+        let candidates = ["baker", "caker", "daker", "faker", "maker", "taker"];
+        let mut guesses = candidates.to_vec();
+        guesses.push("bcdfg");
+        guesses.push("mtbcx");
+        let exact = Optimal::build(&candidates, &guesses, None, None);
+        let narrow = Optimal::build(&candidates, &guesses, Some(1), None);
+        assert!(total(&exact, 1, &candidates) <= total(&narrow, 1, &candidates));
+        for &answer in &candidates {
+            let mut node = &narrow;
+            while node.guess != answer {
+                node = node
+                    .child(&Correctness::compute(answer, &node.guess))
+                    .expect("every answer has a path");
+            }
+        }
+    }
+
+    #[test]
+    fn same_tree_without_the_table() {
+        let candidates = ["baker", "caker", "daker", "faker", "maker", "taker"];
+        let mut guesses = candidates.to_vec();
+        guesses.push("bcdfg");
+        let table = Optimal::build(&candidates, &guesses, None, None);
+        let on_the_fly = Optimal::build(&candidates, &guesses, None, Some(0));
+        assert_eq!(table, on_the_fly);
+    }
+
+    #[test]
+    fn same_trees_as_before_the_arena() {
+        // the totals from before `split` shared one arena between the sets it tried, so a
+        // different one means the search changed and not just where it keeps its words
+        let candidates: Vec<_> = answers().step_by(5).collect();
+        let guesses: Vec<_> = answers().step_by(2).collect();
+        for (width, expected) in [(1, 1366), (2, 1363)] {
+            let tree = Optimal::build(&candidates, &guesses, Some(width), None);
+            assert_eq!(tree.guess, "slate");
+            assert_eq!(total(&tree, 1, &candidates), expected, "width {}", width);
+        }
+    }
+
+    #[test]
+    fn cancelled() {
+        let cancel = Cancel::new();
+        cancel.cancel();
+        let candidates = ["baker", "caker", "daker", "faker"];
+        let built = Optimal::build_until(&candidates, &candidates, None, None, &cancel);
+        assert_eq!(built, Err(Cancelled));
+    }
+}
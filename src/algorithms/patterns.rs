@@ -0,0 +1,83 @@
+use crate::core::pattern_index;
+use crate::Correctness;
+
+// The pattern index of every guess against every candidate, worked out up front for searches that
+// keep asking for the same ones (like `Optimal`'s). It takes a byte per pair, which for the whole
+// dictionary against the answers is about 30MB. Past the limit the table isn't built at all, and
+// every mask is worked out when it's asked for instead: slower, but it takes no memory.
+pub struct Patterns<'a> {
+    guesses: &'a [&'static str],
+    candidates: &'a [&'static str],
+    // a row per guess, a column per candidate
+    table: Option<Vec<u8>>,
+}
+
+impl<'a> Patterns<'a> {
+    // `limit` is in bytes, and `None` always builds the table
+    pub fn new(
+        guesses: &'a [&'static str],
+        candidates: &'a [&'static str],
+        limit: Option<usize>,
+    ) -> Self {
+        let size = Self::size(guesses.len(), candidates.len());
+        let table = limit.is_none_or(|limit| size <= limit).then(|| {
+            guesses
+                .iter()
+                .flat_map(|guess| {
+                    candidates
+                        .iter()
+                        .map(move |candidate| pattern_index(Correctness::compute(candidate, guess)))
+                })
+                // 243 patterns fit in a byte
+                .map(|pattern| pattern as u8)
+                .collect()
+        });
+        Self {
+            guesses,
+            candidates,
+            table,
+        }
+    }
+
+    // how many bytes the table for this many guesses and candidates takes
+    pub fn size(guesses: usize, candidates: usize) -> usize {
+        guesses.saturating_mul(candidates)
+    }
+
+    // how many bytes the table actually took, which is none if it was over the limit
+    pub fn memory(&self) -> usize {
+        self.table.as_ref().map_or(0, Vec::len)
+    }
+
+    // the pattern index of `guesses[guess]` against `candidates[candidate]`
+    pub fn get(&self, guess: usize, candidate: usize) -> usize {
+        match &self.table {
+            Some(table) => table[guess * self.candidates.len() + candidate] as usize,
+            None => pattern_index(Correctness::compute(
+                self.candidates[candidate],
+                self.guesses[guess],
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_and_without_the_table() {
+        let guesses = ["tares", "fjord", "right"];
+        let candidates = ["right", "sight", "wrong"];
+        let table = Patterns::new(&guesses, &candidates, None);
+        let on_the_fly = Patterns::new(&guesses, &candidates, Some(8));
+        assert_eq!((table.memory(), on_the_fly.memory()), (9, 0));
+        for (g, guess) in guesses.iter().enumerate() {
+            for (c, candidate) in candidates.iter().enumerate() {
+                let pattern = pattern_index(Correctness::compute(candidate, guess));
+                assert_eq!(table.get(g, c), pattern);
+                assert_eq!(on_the_fly.get(g, c), pattern);
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+// How likely each word is to be the answer, going by how often it was seen. Raw counts make the
+// most common words overwhelmingly likely, which is close to right for a word list made from a
+// corpus but far too sure of itself for the real game, where a word only has to be reasonably
+// well known to be picked. Which works best is worth trying out rather than guessing at.
+
+// weights worked out as fractions are scaled up by this, to be whole numbers like the counts
+const SCALE: f64 = 1_000_000.0;
+
+pub trait Prior {
+    // How much each of `words` (with how often it was seen) counts for, in the same order. They
+    // stay whole numbers like the counts, and only raw counts can make one zero.
+    fn weights(&self, words: &[(&str, u64)]) -> Vec<u64>;
+}
+
+impl<P: Prior + ?Sized> Prior for Box<P> {
+    fn weights(&self, words: &[(&str, u64)]) -> Vec<u64> {
+        (**self).weights(words)
+    }
+}
+
+fn scaled(p: f64) -> u64 {
+    (p * SCALE).round().max(1.0) as u64
+}
+
+// the counts as they are, which is what everything used before there was a choice
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Counts;
+
+impl Prior for Counts {
+    fn weights(&self, words: &[(&str, u64)]) -> Vec<u64> {
+        words.iter().map(|&(_, count)| count).collect()
+    }
+}
+
+// The log of the counts, so a word seen a thousand times more is only a few times as likely.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogCounts;
+
+impl Prior for LogCounts {
+    fn weights(&self, words: &[(&str, u64)]) -> Vec<u64> {
+        words
+            .iter()
+            .map(|&(_, count)| scaled((count as f64).ln_1p()))
+            .collect()
+    }
+}
+
+// Every word as likely as the rest.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Uniform;
+
+impl Prior for Uniform {
+    fn weights(&self, words: &[(&str, u64)]) -> Vec<u64> {
+        vec![1; words.len()]
+    }
+}
+
+// Goes by where a word ranks rather than its count: about the `common` most common words are
+// close to certain to be fair game, the rest fall off along a sigmoid `width` wide, spread over
+// the whole list. It's what a lot of the well known solvers use, and it fits the real answers
+// (a few thousand reasonably common words) better than the counts do.
+#[derive(Debug, Clone, Copy)]
+pub struct SigmoidRank {
+    pub common: usize,
+    pub width: f64,
+}
+
+impl Default for SigmoidRank {
+    fn default() -> Self {
+        Self {
+            common: 3000,
+            width: 10.0,
+        }
+    }
+}
+
+impl Prior for SigmoidRank {
+    fn weights(&self, words: &[(&str, u64)]) -> Vec<u64> {
+        let mut order: Vec<_> = (0..words.len()).collect();
+        order.sort_by(|&a, &b| words[b].1.cmp(&words[a].1));
+        let mut weights = vec![0; words.len()];
+        let n = words.len() as f64;
+        let mut start = 0;
+        while start < order.len() {
+            // words seen just as often share the rank in the middle of theirs, so ties come
+            // out the same whichever order they're in
+            let count = words[order[start]].1;
+            let end = start + order[start..].partition_point(|&i| words[i].1 == count);
+            let rank = (start + end - 1) as f64 / 2.0;
+            let x = self.width * (self.common as f64 - rank) / n;
+            for &i in &order[start..end] {
+                weights[i] = scaled(1.0 / (1.0 + (-x).exp()));
+            }
+            start = end;
+        }
+        weights
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn priors() {
+        let words = [("crane", 1000), ("salet", 10), ("xylyl", 10), ("qajaq", 0)];
+        assert_eq!(Counts.weights(&words), [1000, 10, 10, 0]);
+        assert_eq!(Uniform.weights(&words), [1, 1, 1, 1]);
+        let log = LogCounts.weights(&words);
+        assert!(log[0] < log[1] * 3 && log[1] == log[2] && log[3] == 1);
+
+        let sigmoid = SigmoidRank {
+            common: 2,
+            width: 10.0,
+        };
+        let weights = sigmoid.weights(&words);
+        assert!(weights[0] > 990_000);
+        assert_eq!(weights[1], weights[2]);
+        assert!(weights[0] > weights[1] && weights[2] > weights[3] && weights[3] > 0);
+        // far enough down the list a word isn't worth much
+        let many: Vec<_> = (0..100).map(|i| ("words", 100 - i)).collect();
+        let weights = sigmoid.weights(&many);
+        assert!(weights[99] < 1000);
+    }
+}
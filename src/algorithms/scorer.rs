@@ -0,0 +1,174 @@
+use super::{estimate, WIN};
+use crate::core::{entropy, partition_counts, pattern_index};
+use crate::Correctness;
+
+// How good a guess looks against the candidates that are left (each with how common it is),
+// higher being better. `Greedy` plays whichever guess scores best, so a new heuristic only needs
+// one of these.
+pub trait Scorer {
+    fn score(&self, guess: &str, candidates: &[(&str, u64)]) -> f64;
+}
+
+impl<S: Scorer + ?Sized> Scorer for Box<S> {
+    fn score(&self, guess: &str, candidates: &[(&str, u64)]) -> f64 {
+        (**self).score(guess, candidates)
+    }
+}
+
+impl<S: Scorer + ?Sized> Scorer for &S {
+    fn score(&self, guess: &str, candidates: &[(&str, u64)]) -> f64 {
+        (**self).score(guess, candidates)
+    }
+}
+
+// The weight and the number of candidates that end up in each bucket, in one pass. Only the
+// totals are kept rather than the buckets themselves, so scoring takes the same memory however
+// long the word list is.
+fn totals(guess: &str, candidates: &[(&str, u64)]) -> [(u64, usize); 243] {
+    let mut buckets = [(0, 0); 243];
+    for &(candidate, count) in candidates {
+        let bucket = &mut buckets[pattern_index(Correctness::compute(candidate, guess))];
+        bucket.0 += count;
+        bucket.1 += 1;
+    }
+    buckets
+}
+
+// Expected information gained, in bits.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Entropy;
+
+impl Scorer for Entropy {
+    fn score(&self, guess: &str, candidates: &[(&str, u64)]) -> f64 {
+        entropy(partition_counts(guess, candidates.iter().copied()))
+    }
+}
+
+// The fewest candidates left on average afterwards (negated, so fewer scores higher).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExpectedSize;
+
+impl Scorer for ExpectedSize {
+    fn score(&self, guess: &str, candidates: &[(&str, u64)]) -> f64 {
+        let buckets = totals(guess, candidates);
+        let total: u64 = buckets.iter().map(|&(weight, _)| weight).sum();
+        if total == 0 {
+            return 0.0;
+        }
+        let expected: f64 = buckets
+            .iter()
+            .map(|&(weight, size)| weight as f64 * size as f64)
+            .sum();
+        -expected / total as f64
+    }
+}
+
+// The fewest candidates left however the mask comes out (negated, like `ExpectedSize`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WorstCase;
+
+impl Scorer for WorstCase {
+    fn score(&self, guess: &str, candidates: &[(&str, u64)]) -> f64 {
+        let largest = totals(guess, candidates)
+            .iter()
+            .map(|&(_, size)| size)
+            .max()
+            .unwrap_or(0);
+        -(largest as f64)
+    }
+}
+
+// The chance the guess is the answer, going by how common the candidates are. Always goes for
+// the most likely word, which wins fast on common answers and slowly on the rest.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Frequency;
+
+impl Scorer for Frequency {
+    fn score(&self, guess: &str, candidates: &[(&str, u64)]) -> f64 {
+        let total: u64 = candidates.iter().map(|&(_, count)| count).sum();
+        let count = candidates
+            .iter()
+            .find(|&&(word, _)| word == guess)
+            .map_or(0, |&(_, count)| count);
+        if total == 0 {
+            return 0.0;
+        }
+        count as f64 / total as f64
+    }
+}
+
+// The fewest guesses expected to finish the game, counting this one (negated, like
+// `ExpectedSize`). Rather than look further ahead, each bucket is guessed to take however many
+// guesses a set that size usually does, which rewards a guess for maybe winning and for splits
+// the entropy can't tell apart.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExpectedLength;
+
+impl Scorer for ExpectedLength {
+    fn score(&self, guess: &str, candidates: &[(&str, u64)]) -> f64 {
+        let total: u64 = candidates.iter().map(|&(_, count)| count).sum();
+        if total == 0 {
+            return 0.0;
+        }
+        let expected: f64 = totals(guess, candidates)
+            .iter()
+            .enumerate()
+            .filter(|&(pattern, &(_, size))| pattern != WIN && size != 0)
+            .map(|(_, &(weight, size))| weight as f64 / total as f64 * estimate(size))
+            .sum();
+        -(1.0 + expected)
+    }
+}
+
+// Credits a guess that might be the answer with its chance of ending the game there and then,
+// which a plain score undervalues: entropy only counts a right guess as one more bucket, when it
+// actually settles everything that's still unknown. The bonus is in bits, so it's meant to go
+// with scorers in bits, like `Entropy`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AnswerBonus<S> {
+    inner: S,
+}
+
+impl<S: Scorer> AnswerBonus<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+impl<S: Scorer> Scorer for AnswerBonus<S> {
+    fn score(&self, guess: &str, candidates: &[(&str, u64)]) -> f64 {
+        let chance = Frequency.score(guess, candidates);
+        let unknown = entropy(candidates.iter().map(|&(_, count)| count));
+        self.inner.score(guess, candidates) + chance * unknown
+    }
+}
+
+// A weighted sum of other scores, to trade them off against each other. The scores are on
+// different scales (bits, candidates, a probability), so the weights have to make up for that.
+#[derive(Default)]
+pub struct Composite {
+    parts: Vec<(f64, Box<dyn Scorer>)>,
+}
+
+impl Composite {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // parts with no weight are left out, since they'd only cost time
+    pub fn with(mut self, weight: f64, scorer: impl Scorer + 'static) -> Self {
+        if weight != 0.0 {
+            self.parts.push((weight, Box::new(scorer)));
+        }
+        self
+    }
+}
+
+impl Scorer for Composite {
+    fn score(&self, guess: &str, candidates: &[(&str, u64)]) -> f64 {
+        self.parts
+            .iter()
+            .map(|(weight, scorer)| weight * scorer.score(guess, candidates))
+            .sum()
+    }
+}
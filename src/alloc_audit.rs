@@ -0,0 +1,95 @@
+// Counts heap allocations, to keep the game loop from quietly going back to allocating on every
+// guess. With the alloc-audit feature this is the allocator for the crate's own test binary (and
+// nothing else that links the crate), and `game_loop` checks a game's guesses don't allocate more
+// than the budget. Only the guesses the game takes are counted: turning one down allocates the
+// error that says why, and a game shouldn't be held to how often it's mistyped at.
+//
+// The budget is a handful per game by default: the answer is copied once, and the history grows
+// a couple of times. WORDLE_SOLVER_ALLOC_BUDGET changes it.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct Counting;
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+thread_local! {
+    // per thread, so tests running alongside each other don't count each other's allocations
+    static COUNT: Cell<u64> = const { Cell::new(0) };
+}
+
+fn counted() {
+    // there's no counter left while a thread is being torn down
+    let _ = COUNT.try_with(|count| count.set(count.get() + 1));
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        counted();
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        counted();
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        counted();
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+// run `f`, and say how many allocations it made on this thread
+fn count<T>(f: impl FnOnce() -> T) -> (T, u64) {
+    let before = COUNT.with(Cell::get);
+    let result = f();
+    (result, COUNT.with(Cell::get) - before)
+}
+
+const DEFAULT_BUDGET: u64 = 8;
+
+// how many allocations a game's guesses may make between them
+fn budget() -> u64 {
+    std::env::var("WORDLE_SOLVER_ALLOC_BUDGET")
+        .ok()
+        .and_then(|budget| budget.parse().ok())
+        .unwrap_or(DEFAULT_BUDGET)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Wordle;
+
+    #[test]
+    fn counts_allocations() {
+        let (_, n) = count(|| Vec::<u8>::with_capacity(10));
+        assert_eq!(n, 1);
+        let (_, n) = count(|| 1 + 1);
+        assert_eq!(n, 0);
+    }
+
+    #[test]
+    fn game_loop() {
+        let w = Wordle::new();
+        let mut game = w.start("right").unwrap();
+        let (_, mut n) = count(|| game.guess("tares").unwrap());
+        // more mistakes than a game has guesses, which mustn't use up the budget
+        for _ in 0..12 {
+            assert!(game.guess("qqqqq").is_err());
+        }
+        for guess in ["lying", "wrong", "right"] {
+            let (_, allocations) = count(|| game.guess(guess).unwrap());
+            n += allocations;
+        }
+        assert_eq!(game.history().len(), 4);
+        assert!(n <= budget(), "{} allocations for one game", n);
+    }
+}
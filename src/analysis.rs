@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use crate::core::{all_masks, entropy, pattern_index};
+use crate::{Correctness, Guesser, History, Mask, MaskExt};
+
+// no game goes on longer than this, same as `Wordle::play`
+const MAX_GUESSES: usize = 32;
+
+// Finish a game from the given history, and return how many more guesses it took.
+fn play_from<G: Guesser>(guesser: &mut G, answer: &str, history: &History) -> usize {
+    let mut history = history.clone();
+    let start = history.len();
+    while history.len() < MAX_GUESSES {
+        let word = guesser.guess(&history);
+        if history.push_for(answer, &word).is_win() {
+            break;
+        }
+    }
+    history.len() - start
+}
+
+// A state where two guessers would play different words.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Disagreement {
+    pub history: History,
+    // what each guesser plays here
+    pub choices: [String; 2],
+    // the answers that lead here
+    pub answers: Vec<&'static str>,
+    // the total guesses from here on over those answers, when each guesser gets its way
+    pub costs: [usize; 2],
+}
+
+impl Disagreement {
+    // how many more guesses on average the second guesser's choice costs (negative when it's
+    // the better one)
+    pub fn mean_delta(&self) -> f64 {
+        (self.costs[1] as f64 - self.costs[0] as f64) / self.answers.len() as f64
+    }
+}
+
+// Play every answer with the first guesser, and at each step ask the second what it would have
+// done. Where they differ, both choices are played out to the end (each guesser carrying on with
+// its own choice) to see what the difference costs. The guessers have to cope with being handed
+// any history, as the bundled ones do. The biggest total differences come first.
+pub fn disagreements<A, B>(
+    mut mk_a: impl FnMut() -> A,
+    mut mk_b: impl FnMut() -> B,
+    answers: &[&'static str],
+) -> Vec<Disagreement>
+where
+    A: Guesser,
+    B: Guesser,
+{
+    let mut found: HashMap<History, Disagreement> = HashMap::new();
+    for &answer in answers {
+        let mut a = mk_a();
+        let mut b = mk_b();
+        let mut history = History::new();
+        let mut played = Vec::new();
+        while history.len() < MAX_GUESSES {
+            let word = a.guess(&history);
+            let other = b.guess(&history);
+            if other != word {
+                played.push((history.clone(), word.clone(), other));
+            }
+            if history.push_for(answer, &word).is_win() {
+                break;
+            }
+        }
+        for (state, word, other) in played {
+            let cost_a = history.len() - state.len();
+            let mut after = state.clone();
+            let cost_b = if after.push_for(answer, &other).is_win() {
+                1
+            } else {
+                1 + play_from(&mut mk_b(), answer, &after)
+            };
+            let entry = found.entry(state.clone()).or_insert_with(|| Disagreement {
+                history: state,
+                choices: [word, other],
+                answers: Vec::new(),
+                costs: [0, 0],
+            });
+            entry.answers.push(answer);
+            entry.costs[0] += cost_a;
+            entry.costs[1] += cost_b;
+        }
+    }
+    let mut found: Vec<_> = found.into_values().collect();
+    found.sort_by_key(|d| {
+        let delta = d.costs[1] as i64 - d.costs[0] as i64;
+        (-delta.abs(), d.history.len())
+    });
+    found
+}
+
+// How well a fixed sequence of openers, played whatever the masks, splits up the candidates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Opening {
+    // how many different sets of masks the candidates can give
+    pub groups: usize,
+    // how many candidates are left on average afterwards
+    pub expected_remaining: f64,
+    // the most candidates that can be left
+    pub largest: usize,
+    // the share of candidates that are known for certain afterwards
+    pub solved: f64,
+    // information gained, in bits
+    pub bits: f64,
+}
+
+impl Opening {
+    pub fn evaluate(openers: &[&str], candidates: &[&str]) -> Self {
+        let mut groups: HashMap<Vec<usize>, usize> = HashMap::new();
+        for candidate in candidates {
+            let masks = openers
+                .iter()
+                .map(|opener| pattern_index(Correctness::compute(candidate, opener)))
+                .collect();
+            *groups.entry(masks).or_default() += 1;
+        }
+        let n = candidates.len() as f64;
+        Self {
+            groups: groups.len(),
+            expected_remaining: groups.values().map(|&g| (g * g) as f64).sum::<f64>() / n,
+            largest: groups.values().copied().max().unwrap_or(0),
+            solved: groups.values().filter(|&&g| g == 1).count() as f64 / n,
+            bits: entropy(groups.values().map(|&g| g as u64)),
+        }
+    }
+}
+
+// The answers behind one mask an opener can get back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome<'a> {
+    pub mask: Mask,
+    pub answers: Vec<&'a str>,
+    // the chance of getting this mask, with every answer as likely as the next
+    pub probability: f64,
+}
+
+// Every one of the 243 masks `opener` can get back, most likely first. Masks no answer gives
+// come last, in bucket order.
+pub fn breakdown<'a>(opener: &str, answers: &[&'a str]) -> Vec<Outcome<'a>> {
+    let mut outcomes: Vec<_> = all_masks()
+        .map(|mask| Outcome {
+            mask,
+            answers: Vec::new(),
+            probability: 0.0,
+        })
+        .collect();
+    for &answer in answers {
+        let index = pattern_index(Correctness::compute(answer, opener));
+        outcomes[index].answers.push(answer);
+    }
+    for outcome in &mut outcomes {
+        outcome.probability = outcome.answers.len() as f64 / answers.len().max(1) as f64;
+    }
+    // stable, so equally likely masks stay in bucket order
+    outcomes.sort_by_key(|outcome| std::cmp::Reverse(outcome.answers.len()));
+    outcomes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disagreements() {
+        // both play "wrong" first, and then one goes straight for the answer
+        let direct = || {
+            guesser!(|history| { if history.is_empty() { "wrong" } else { "right" }.to_string() })
+        };
+        let detour = || {
+            guesser!(|history| {
+                match history.len() {
+                    0 => "wrong",
+                    1 => "light",
+                    _ => "right",
+                }
+                .to_string()
+            })
+        };
+        let found = super::disagreements(detour, direct, &["right"]);
+        assert_eq!(found.len(), 1);
+        let d = &found[0];
+        assert_eq!(d.history.len(), 1);
+        assert_eq!(d.choices, ["light", "right"]);
+        assert_eq!(d.answers, ["right"]);
+        assert_eq!(d.costs, [2, 1]);
+        assert_eq!(d.mean_delta(), -1.0);
+    }
+
+    #[test]
+    fn opening() {
+        let candidates = ["right", "might", "sight", "fight"];
+        let one = Opening::evaluate(&["masts"], &candidates);
+        // right and fight look the same to masts
+        assert_eq!((one.groups, one.largest), (3, 2));
+        assert_eq!(one.expected_remaining, 1.5);
+        assert_eq!(one.solved, 0.5);
+        assert_eq!(one.bits, 1.5);
+        let two = Opening::evaluate(&["masts", "fjord"], &candidates);
+        assert_eq!((two.groups, two.largest, two.solved), (4, 1, 1.0));
+    }
+
+    #[test]
+    fn breakdown() {
+        let outcomes = super::breakdown("masts", &["right", "might", "sight", "fight"]);
+        assert_eq!(outcomes.len(), 243);
+        assert_eq!(outcomes[0].mask, mask![W W W M W]);
+        assert_eq!(outcomes[0].answers, ["right", "fight"]);
+        assert_eq!(outcomes[0].probability, 0.5);
+        assert_eq!(outcomes.iter().filter(|o| !o.answers.is_empty()).count(), 3);
+        // the win can't happen, so it's with the rest of the impossible masks
+        assert!(outcomes[3].mask.is_win());
+    }
+}
@@ -0,0 +1,55 @@
+// The official answers in the order they were played: answers.txt is the game's own list, one a
+// day from the first puzzle on, so where a word is in it says which day it was the answer.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::daily::Day;
+
+// every answer, the first puzzle's first
+pub fn all() -> &'static [&'static str] {
+    static ALL: OnceLock<Vec<&'static str>> = OnceLock::new();
+    ALL.get_or_init(|| crate::algorithms::answers().collect())
+}
+
+// the answer to puzzle `n`, counting the first as 0 (nothing past the end of the list; see
+// `Day::answer` for the days after it)
+pub fn by_index(n: usize) -> Option<&'static str> {
+    all().get(n).copied()
+}
+
+// the puzzle a word was the answer to, or the first one for a word the list has more than once
+pub fn index_of(word: &str) -> Option<usize> {
+    static INDEX: OnceLock<HashMap<&'static str, usize>> = OnceLock::new();
+    let index = INDEX.get_or_init(|| {
+        let mut index = HashMap::new();
+        for (n, &answer) in all().iter().enumerate() {
+            index.entry(answer).or_insert(n);
+        }
+        index
+    });
+    index.get(word).copied()
+}
+
+// the day a word was the answer
+pub fn date_of(word: &str) -> Option<Day> {
+    index_of(word).map(Day::of_number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_order() {
+        assert_eq!(by_index(0), Some("cigar"));
+        assert_eq!(by_index(1), Some("rebut"));
+        assert_eq!(by_index(all().len()), None);
+        assert_eq!(index_of("rebut"), Some(1));
+        assert_eq!(index_of("tares"), None);
+        assert_eq!(date_of("cigar").unwrap().to_string(), "2021-06-19");
+        for (n, &answer) in all().iter().enumerate().step_by(97) {
+            assert_eq!(date_of(answer).and_then(Day::answer), Some(answer), "{}", n);
+        }
+    }
+}
@@ -0,0 +1,277 @@
+// The header every file the crate saves starts with, saying what the file is and what it was made
+// with, so a file from another version or made under other rules is refused rather than misread:
+//
+//   # wordle_solver tree v1 rules=strict wordlist=4d109548e8502961
+//
+// Everything after the version is key=value, so later versions can add fields; readers skip the
+// keys they don't know. A version newer than this build knows is refused, since it might mean
+// something else by the fields it shares with this one.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::dict::{WordlistHash, WordlistMismatch};
+use crate::GuessPolicy;
+
+const MAGIC: &str = "# wordle_solver ";
+
+// the newest version of the header (and the files under it) this build reads and writes
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Tree,
+    Cache,
+    Results,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Tree => "tree",
+            Self::Cache => "cache",
+            Self::Results => "results",
+        }
+    }
+}
+
+impl FromStr for Kind {
+    type Err = HeaderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [Self::Tree, Self::Cache, Self::Results]
+            .into_iter()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| HeaderError::Malformed(format!("unknown kind of file '{}'", s)))
+    }
+}
+
+// The rules a file was made under, since a strategy for one set is no good for another.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rules {
+    pub policy: GuessPolicy,
+}
+
+impl fmt::Display for Rules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let policy = match self.policy {
+            GuessPolicy::Strict => "strict",
+            GuessPolicy::Permissive => "permissive",
+            GuessPolicy::AnswersOnly => "answers-only",
+        };
+        write!(f, "{}", policy)
+    }
+}
+
+impl FromStr for Rules {
+    type Err = HeaderError;
+
+    // unlike unknown fields, an unknown rule can't be skipped, since it changes what's in the file
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rules = Self::default();
+        for flag in s.split(',') {
+            rules.policy = match flag {
+                "strict" => GuessPolicy::Strict,
+                "permissive" => GuessPolicy::Permissive,
+                "answers-only" => GuessPolicy::AnswersOnly,
+                _ => return Err(HeaderError::Malformed(format!("unknown rule '{}'", flag))),
+            };
+        }
+        Ok(rules)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    pub kind: Kind,
+    pub version: u32,
+    pub rules: Rules,
+    pub wordlist: WordlistHash,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeaderError {
+    Malformed(String),
+    WrongKind { expected: Kind, found: Kind },
+    TooNew { version: u32 },
+    WrongRules { expected: Rules, found: Rules },
+    Wordlist(WordlistMismatch),
+}
+
+impl fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed(reason) => write!(f, "bad header: {}", reason),
+            Self::WrongKind { expected, found } => write!(
+                f,
+                "expected a {} file, but this is a {} file",
+                expected.name(),
+                found.name()
+            ),
+            Self::TooNew { version } => write!(
+                f,
+                "made by a newer version (file version {}, but this reads up to {})",
+                version, VERSION
+            ),
+            Self::WrongRules { expected, found } => write!(
+                f,
+                "made for {} rules, but these are {} rules",
+                found, expected
+            ),
+            Self::Wordlist(mismatch) => write!(f, "{}", mismatch),
+        }
+    }
+}
+
+impl std::error::Error for HeaderError {}
+
+impl Header {
+    // a header for a file made now
+    pub fn new(kind: Kind, rules: Rules, wordlist: WordlistHash) -> Self {
+        Self {
+            kind,
+            version: VERSION,
+            rules,
+            wordlist,
+        }
+    }
+
+    // The header at the top of `text`, or None for files from before there were headers (or
+    // from other programs).
+    pub fn read(text: &str) -> Result<Option<Self>, HeaderError> {
+        let Some(rest) = text
+            .lines()
+            .next()
+            .and_then(|line| line.strip_prefix(MAGIC))
+        else {
+            return Ok(None);
+        };
+        let malformed = |reason: &str| HeaderError::Malformed(reason.to_string());
+        let mut fields = rest.split_whitespace();
+        let kind: Kind = fields.next().ok_or_else(|| malformed("no kind"))?.parse()?;
+        let version = fields
+            .next()
+            .and_then(|v| v.strip_prefix('v'))
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| malformed("no version"))?;
+        if version > VERSION {
+            return Err(HeaderError::TooNew { version });
+        }
+        let (mut rules, mut wordlist) = (None, None);
+        for field in fields {
+            match field.split_once('=') {
+                Some(("rules", value)) => rules = Some(value.parse()?),
+                Some(("wordlist", value)) => {
+                    let hash = u64::from_str_radix(value, 16)
+                        .map_err(|_| malformed("the word list hash isn't hex"))?;
+                    wordlist = Some(WordlistHash(hash));
+                }
+                // something from a later version
+                _ => {}
+            }
+        }
+        Ok(Some(Self {
+            kind,
+            version,
+            rules: rules.ok_or_else(|| malformed("no rules"))?,
+            wordlist: wordlist.ok_or_else(|| malformed("no word list hash"))?,
+        }))
+    }
+
+    // Check `text` was made the way this header describes. Files without a header are let
+    // through, since there's no telling.
+    pub fn check(&self, text: &str) -> Result<(), HeaderError> {
+        let Some(found) = Self::read(text)? else {
+            return Ok(());
+        };
+        if found.kind != self.kind {
+            return Err(HeaderError::WrongKind {
+                expected: self.kind,
+                found: found.kind,
+            });
+        }
+        if found.rules != self.rules {
+            return Err(HeaderError::WrongRules {
+                expected: self.rules,
+                found: found.rules,
+            });
+        }
+        if found.wordlist != self.wordlist {
+            return Err(HeaderError::Wordlist(WordlistMismatch {
+                expected: self.wordlist,
+                found: found.wordlist.to_string(),
+            }));
+        }
+        Ok(())
+    }
+}
+
+// the whole line, without a newline
+impl fmt::Display for Header {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{} v{} rules={} wordlist={}",
+            MAGIC,
+            self.kind.name(),
+            self.version,
+            self.rules,
+            self.wordlist
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header() -> Header {
+        Header::new(Kind::Tree, Rules::default(), WordlistHash(0x4d10))
+    }
+
+    #[test]
+    fn roundtrip() {
+        let text = format!("{}\nsalet BBBBB\n", header());
+        assert!(
+            text.starts_with("# wordle_solver tree v1 rules=strict wordlist=0000000000004d10\n")
+        );
+        assert_eq!(Header::read(&text), Ok(Some(header())));
+        assert_eq!(header().check(&text), Ok(()));
+        assert_eq!(Header::read("salet BBBBB\n"), Ok(None));
+    }
+
+    #[test]
+    fn forward_compatible() {
+        // new fields are skipped, but a new version isn't
+        let text = "# wordle_solver tree v1 rules=strict wordlist=4d10 shiny=yes\n";
+        assert_eq!(Header::read(text), Ok(Some(header())));
+        let text = "# wordle_solver tree v2 rules=strict wordlist=4d10\n";
+        assert_eq!(Header::read(text), Err(HeaderError::TooNew { version: 2 }));
+        let text = "# wordle_solver tree v1 rules=strict,sideways wordlist=4d10\n";
+        assert!(Header::read(text).is_err());
+    }
+
+    #[test]
+    fn mismatches() {
+        let other = |f: fn(&mut Header)| {
+            let mut h = header();
+            f(&mut h);
+            h.to_string()
+        };
+        let kind = other(|h| h.kind = Kind::Cache);
+        assert!(matches!(
+            header().check(&kind),
+            Err(HeaderError::WrongKind { .. })
+        ));
+        let rules = other(|h| h.rules.policy = GuessPolicy::AnswersOnly);
+        assert!(matches!(
+            header().check(&rules),
+            Err(HeaderError::WrongRules { .. })
+        ));
+        let wordlist = other(|h| h.wordlist = WordlistHash(1));
+        assert!(matches!(
+            header().check(&wordlist),
+            Err(HeaderError::Wordlist(_))
+        ));
+    }
+}
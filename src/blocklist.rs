@@ -0,0 +1,53 @@
+use std::collections::HashSet;
+
+// Words that should never be suggested, such as slurs a public-facing bot shouldn't say. This
+// only keeps words out of the guesses an algorithm picks from; a blocked word can still be the
+// answer, and will be guessed if nothing else is left.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Blocklist {
+    words: HashSet<String>,
+}
+
+impl Blocklist {
+    // one word per line; blank lines and lines starting with '#' are ignored
+    pub fn parse(text: &str) -> Self {
+        Self {
+            words: text
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_lowercase)
+                .collect(),
+        }
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(word)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for Blocklist {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        Self {
+            words: iter.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let blocklist = Blocklist::parse("# words we don't want\nCrane\n\n  slate  \n");
+        assert!(blocklist.contains("crane"));
+        assert!(blocklist.contains("slate"));
+        assert!(!blocklist.contains("# words we don't want"));
+        assert!(!blocklist.contains(""));
+    }
+}
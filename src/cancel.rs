@@ -0,0 +1,118 @@
+// A way to stop long work (building a decision tree, a benchmark, scoring every word for a
+// suggestion) from another thread, for UIs with a stop button and servers shutting down, instead
+// of killing the whole process. The work checks in every so often and winds down once it's
+// cancelled, so it stops soon after rather than right away.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+// Clones share the one flag: hand a clone to the work, and cancel through any of them.
+#[derive(Debug, Clone, Default)]
+pub struct Cancel(Arc<AtomicBool>);
+
+impl Cancel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// For work that's asked for again and again, like a session's guesser: it stops once `cancel` is
+// cancelled, or once it's past the deadline `start` gave the piece of work it's on. Clones share
+// everything, so whoever asks for the work can set the deadline and see afterwards whether it
+// stopped short.
+#[derive(Debug, Clone, Default)]
+pub struct Stop {
+    cancel: Cancel,
+    deadline: Arc<Mutex<Option<Instant>>>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl Stop {
+    pub fn new(cancel: Cancel) -> Self {
+        Self {
+            cancel,
+            ..Default::default()
+        }
+    }
+
+    pub fn cancel(&self) -> &Cancel {
+        &self.cancel
+    }
+
+    // the next piece of work has until `deadline` (or as long as it takes for None)
+    pub fn start(&self, deadline: Option<Instant>) {
+        *self.deadline.lock().unwrap() = deadline;
+        self.stopped.store(false, Ordering::Relaxed);
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        *self.deadline.lock().unwrap()
+    }
+
+    // whether the work should stop now, which `stopped` remembers
+    pub fn check(&self) -> bool {
+        let stop =
+            self.cancel.is_cancelled() || self.deadline().is_some_and(|d| Instant::now() >= d);
+        if stop {
+            self.stopped.store(true, Ordering::Relaxed);
+        }
+        stop
+    }
+
+    // whether the work since `start` stopped short
+    pub fn stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+}
+
+// what work that was cancelled before it finished hands back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clones_share_the_flag() {
+        let cancel = Cancel::new();
+        let work = cancel.clone();
+        assert!(!work.is_cancelled());
+        std::thread::spawn(move || cancel.cancel()).join().unwrap();
+        assert!(work.is_cancelled());
+    }
+
+    #[test]
+    fn stop() {
+        let stop = Stop::default();
+        let work = stop.clone();
+        assert!(!work.check());
+        stop.start(Some(Instant::now()));
+        assert!(work.check());
+        assert!(stop.stopped());
+        // and the next piece of work starts over
+        stop.start(None);
+        assert!(!work.check());
+        assert!(!stop.stopped());
+        stop.cancel().cancel();
+        assert!(work.check());
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+// Defaults read from a TOML file, so they don't have to be passed on every run:
+//
+//   algorithm = "beam"
+//   beam_width = 8
+//   opener = "salet"
+//   blocklist = "/home/me/blocked.txt"
+//   palette = "high-contrast"
+//   prior = "sigmoid"
+//
+// Command line flags win over environment variables, which win over the file.
+//
+// It can also hold named benchmark setups, run with `bench --profile nightly-full`:
+//
+//   [profiles.nightly-full]
+//   algorithms = ["naive", "beam"]
+//   outputs = ["nightly.csv", "nightly.ndjson"]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    // "naive", "beam" or "optimal"
+    pub algorithm: Option<String>,
+    pub beam_width: Option<usize>,
+    // the first guess algorithms should make
+    pub opener: Option<String>,
+    // word lists to use instead of the bundled ones
+    pub blocklist: Option<PathBuf>,
+    pub variants: Option<PathBuf>,
+    // false to never color the output
+    pub color: Option<bool>,
+    // "standard" (green and yellow) or "high-contrast" (orange and blue)
+    pub palette: Option<String>,
+    // the language for messages, like "es"; going by the locale when unset
+    pub lang: Option<String>,
+    // how likely words are taken to be the answer: "counts" (as seen), "log" (the log of the
+    // counts), "sigmoid" (going by their rank) or "uniform"
+    pub prior: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+// A benchmark run kept in the config so it can be repeated, and versioned with the config.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    // a file of answers to play (one per line) instead of the official ones
+    pub answers: Option<PathBuf>,
+    // each is benchmarked in turn; the usual algorithm when empty
+    #[serde(default)]
+    pub algorithms: Vec<String>,
+    // only play this many of the answers
+    pub sample: Option<usize>,
+    // where to save the results, in a format that goes by the extension
+    #[serde(default)]
+    pub outputs: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.reason)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|e| e.message().to_string())
+    }
+
+    // config.toml in the platform's config directory (see `platform::config_dir`)
+    pub fn default_path() -> Option<PathBuf> {
+        Some(crate::platform::config_dir()?.join("config.toml"))
+    }
+
+    // a missing file is the same as an empty one
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let err = |reason: String| ConfigError {
+            path: path.to_path_buf(),
+            reason,
+        };
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).map_err(err),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(err(e.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let config = Config::parse("algorithm = \"beam\"\nbeam_width = 8\n").unwrap();
+        assert_eq!(config.algorithm.as_deref(), Some("beam"));
+        assert_eq!(config.beam_width, Some(8));
+        assert_eq!(config.opener, None);
+        assert_eq!(
+            Config::parse("prior = \"sigmoid\"")
+                .unwrap()
+                .prior
+                .as_deref(),
+            Some("sigmoid")
+        );
+        assert!(Config::parse("colour = true").is_err());
+    }
+
+    #[test]
+    fn profiles() {
+        let config = Config::parse(
+            "[profiles.nightly-full]\n\
+             algorithms = [\"naive\", \"beam\"]\n\
+             sample = 100\n\
+             [profiles.quick]\n",
+        )
+        .unwrap();
+        let nightly = &config.profiles["nightly-full"];
+        assert_eq!(nightly.algorithms, ["naive", "beam"]);
+        assert_eq!(nightly.sample, Some(100));
+        assert_eq!(config.profiles["quick"], Profile::default());
+        assert!(Config::parse("[profiles.x]\nalgorithm = \"beam\"").is_err());
+    }
+
+    #[test]
+    fn missing_file() {
+        let config = Config::load(Path::new("/nonexistent/config.toml")).unwrap();
+        assert_eq!(config, Config::default());
+    }
+}
@@ -0,0 +1,1003 @@
+//! The building blocks of the game, for writing solvers of your own: words, masks, histories,
+//! filtering candidates and scoring guesses. The algorithms and the binary are built on these, and
+//! they're kept stable so other crates can build on them too. Everything here is also exported
+//! from the crate root.
+//!
+//! Words and guesses take their length as a const parameter, five unless they say otherwise, so
+//! `Word` is a five letter word and `Word<6>` a six letter one; a mask of six is a plain
+//! `[Correctness; 6]`. Most of the crate only plays five letters; see `length` for the rest.
+
+use std::fmt;
+use std::str::FromStr;
+
+pub use crate::dict::Dictionary;
+
+/// A word of `N` letters (five by default) packed into as many bytes, so it's cheap to copy
+/// around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Word<const N: usize = 5>([u8; N]);
+
+impl Word {
+    /// None unless it's five lowercase letters
+    pub fn new(word: &str) -> Option<Self> {
+        Self::new_n(word)
+    }
+}
+
+impl<const N: usize> Word<N> {
+    /// `new` for words of any length: None unless it's `N` lowercase letters
+    pub fn new_n(word: &str) -> Option<Self> {
+        let bytes: [u8; N] = word.as_bytes().try_into().ok()?;
+        bytes
+            .iter()
+            .all(u8::is_ascii_lowercase)
+            .then_some(Self(bytes))
+    }
+
+    /// the word itself
+    pub fn as_str(&self) -> &str {
+        // only ever made from ascii letters
+        std::str::from_utf8(&self.0).expect("words are ascii")
+    }
+
+    /// its letters, as ascii
+    pub fn bytes(&self) -> [u8; N] {
+        self.0
+    }
+}
+
+impl<const N: usize> fmt::Display for Word<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What parsing a `Word` out of anything but five lowercase letters gives back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWordError(pub String);
+
+impl fmt::Display for ParseWordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' isn't five lowercase letters", self.0)
+    }
+}
+
+impl std::error::Error for ParseWordError {}
+
+impl FromStr for Word {
+    type Err = ParseWordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s).ok_or_else(|| ParseWordError(s.to_string()))
+    }
+}
+
+/// The colours a guess gets back, one per letter. Masks of other lengths are plain
+/// `[Correctness; N]`, and get `MaskExt` all the same.
+pub type Mask = [Correctness; 5];
+
+/// The small conversions everything needs on masks. `Mask` is a plain array, so these come in
+/// with `use wordle_solver::MaskExt`.
+pub trait MaskExt: Sized {
+    fn is_win(&self) -> bool;
+    /// how many letters are green and yellow
+    fn greens(&self) -> usize;
+    fn yellows(&self) -> usize;
+    /// the squares people share their games with, like 🟩🟨⬛⬛🟩
+    fn to_emoji(&self) -> String;
+    /// either palette's squares, with white or black for gray
+    fn from_emoji(s: &str) -> Option<Self>;
+    /// B for gray, Y for yellow and G for green, like "BYBBG"
+    fn to_compact_string(&self) -> String;
+    fn from_compact_str(s: &str) -> Option<Self>;
+    /// the same as `pattern_index`
+    fn index(&self) -> usize;
+    fn from_index(index: usize) -> Option<Self>;
+}
+
+impl<const N: usize> MaskExt for [Correctness; N] {
+    fn is_win(&self) -> bool {
+        *self == [Correctness::Correct; N]
+    }
+
+    fn greens(&self) -> usize {
+        self.iter().filter(|&&c| c == Correctness::Correct).count()
+    }
+
+    fn yellows(&self) -> usize {
+        self.iter()
+            .filter(|&&c| c == Correctness::Misplaced)
+            .count()
+    }
+
+    fn to_emoji(&self) -> String {
+        self.iter()
+            .map(|c| match c {
+                Correctness::Correct => '🟩',
+                Correctness::Misplaced => '🟨',
+                Correctness::Wrong => '⬛',
+            })
+            .collect()
+    }
+
+    fn from_emoji(s: &str) -> Option<Self> {
+        // some clients put a variation selector after each square
+        let squares: Vec<_> = s.chars().filter(|&c| c != '\u{fe0f}').collect();
+        if squares.len() != N {
+            return None;
+        }
+        let mut mask = [Correctness::Wrong; N];
+        for (m, c) in mask.iter_mut().zip(squares) {
+            *m = square(c)?;
+        }
+        Some(mask)
+    }
+
+    fn to_compact_string(&self) -> String {
+        self.iter()
+            .map(|c| match c {
+                Correctness::Correct => 'G',
+                Correctness::Misplaced => 'Y',
+                Correctness::Wrong => 'B',
+            })
+            .collect()
+    }
+
+    fn from_compact_str(s: &str) -> Option<Self> {
+        if s.len() != N {
+            return None;
+        }
+        let mut mask = [Correctness::Wrong; N];
+        for (m, c) in mask.iter_mut().zip(s.chars()) {
+            *m = match c {
+                'G' => Correctness::Correct,
+                'Y' => Correctness::Misplaced,
+                'B' => Correctness::Wrong,
+                _ => return None,
+            };
+        }
+        Some(mask)
+    }
+
+    fn index(&self) -> usize {
+        pattern_index(*self)
+    }
+
+    fn from_index(mut index: usize) -> Option<Self> {
+        if index >= 3usize.pow(N as u32) {
+            return None;
+        }
+        let mut mask = [Correctness::Wrong; N];
+        for c in mask.iter_mut().rev() {
+            *c = match index % 3 {
+                0 => Correctness::Correct,
+                1 => Correctness::Misplaced,
+                _ => Correctness::Wrong,
+            };
+            index /= 3;
+        }
+        Some(mask)
+    }
+}
+
+/// every mask there is, in bucket order (so the win comes first)
+pub fn all_masks() -> impl Iterator<Item = Mask> {
+    (0..243).filter_map(Mask::from_index)
+}
+
+/// Ordered the same way as `pattern_index`, so sorted masks come out in bucket order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Correctness {
+    /// Green
+    Correct,
+    /// Yellow
+    Misplaced,
+    /// Gray
+    Wrong,
+}
+
+impl Correctness {
+    /// The mask `guess` gets when the answer is `answer`. Both have to be five letters.
+    pub fn compute(answer: &str, guess: &str) -> Mask {
+        Self::compute_n(answer, guess)
+    }
+
+    /// `compute` for words of any length; both have to be `N` letters
+    pub fn compute_n<const N: usize>(answer: &str, guess: &str) -> [Correctness; N] {
+        assert_eq!(answer.len(), N);
+        assert_eq!(guess.len(), N);
+        // initialise c as an array of N Wrong guesses
+        let mut c = [Correctness::Wrong; N];
+
+        // Mark guesses correct
+        for (i, (a, g)) in answer.chars().zip(guess.chars()).enumerate() {
+            if a == g {
+                c[i] = Correctness::Correct;
+            }
+        }
+        // Mark guesses misplaced
+        let mut used = [false; N];
+        for (i, &c) in c.iter().enumerate() {
+            if c == Correctness::Correct {
+                used[i] = true;
+            }
+        }
+        for (i, g) in guess.chars().enumerate() {
+            if c[i] == Correctness::Correct {
+                continue; // already marked as correct
+            }
+            // if the current guess letter matches any letters inside the answer
+            // true => mark as Misplaced
+            // false => remains Wrong
+            if answer.chars().enumerate().any(|(i, a)| {
+                if a == g && !used[i] {
+                    used[i] = true;
+                    return true;
+                }
+                false
+            }) {
+                c[i] = Correctness::Misplaced
+            }
+        }
+        c
+    }
+}
+
+/// Both halves are packed, so a guess (and a whole history) copies without allocating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Guess<const N: usize = 5> {
+    pub word: Word<N>,
+    pub mask: [Correctness; N],
+}
+
+impl<const N: usize> Guess<N> {
+    /// whether `word` could be the answer, going by this guess alone
+    pub fn matches(&self, word: &str) -> bool {
+        // if `word` were the answer, our guess would have produced exactly this mask
+        Correctness::compute_n(word, self.word.as_str()) == self.mask
+    }
+}
+
+/// The guesses of a game so far, oldest first. It derefs to a slice of guesses, so everything
+/// that works on slices works on it too.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct History(Vec<Guess>);
+
+/// Why a history couldn't be read, or couldn't have happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistoryError {
+    /// a word or mask that doesn't parse, as written
+    Malformed(String),
+    /// a guess came after the game was already won
+    AfterWin { round: usize },
+    /// two guesses that no answer could have given both of
+    Contradiction { round: usize, reason: String },
+    /// a pasted board whose rows don't add up to a game
+    Board(String),
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed(guess) => write!(
+                f,
+                "'{}' isn't a guess and its mask, like crane=BBYGB or crane=WWMCW",
+                guess
+            ),
+            Self::AfterWin { round } => write!(f, "guess {} came after the game was won", round),
+            Self::Board(reason) => write!(f, "couldn't read the board: {}", reason),
+            Self::Contradiction { round, reason } => {
+                write!(
+                    f,
+                    "guess {} contradicts the ones before it: {}",
+                    round, reason
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for HistoryError {}
+
+impl History {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Guesses separated by whitespace or commas, each a word and its mask joined by '='. A mask
+    /// can be in B/Y/G like everywhere else (see `MaskExt::from_compact_str`) or in C/M/W, with C
+    /// for green, M for yellow and W for gray, like "crane=WWMCW tares=CWWWM"; the letters don't
+    /// overlap, so either reads the same. This only checks the format; see `check` for whether
+    /// the masks make sense together.
+    pub fn parse(s: &str) -> Result<Self, HistoryError> {
+        s.split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|guess| !guess.is_empty())
+            .map(|guess| {
+                let err = || HistoryError::Malformed(guess.to_string());
+                let (word, mask) = guess.split_once('=').ok_or_else(err)?;
+                let word = Word::new(word).ok_or_else(err)?;
+                let mask = Mask::from_compact_str(mask)
+                    .or_else(|| mask_from_cmw(mask))
+                    .ok_or_else(err)?;
+                Ok(Guess { word, mask })
+            })
+            .collect()
+    }
+
+    /// The text a lot of clients copy a board as: a row of letters for each guess and a row of
+    /// squares for each mask, either one after the other or all the letters first. The letters can
+    /// be spaced out, and a row can have its squares next to its letters. Lines with anything
+    /// else on them, like the "Wordle 1,234 4/6" header, are skipped.
+    pub fn from_board(text: &str) -> Result<Self, HistoryError> {
+        let mut words = Vec::new();
+        let mut masks = Vec::new();
+        for line in text.lines() {
+            let is_square = |c: char| square(c).is_some();
+            if !line.chars().all(|c| {
+                c.is_ascii_alphabetic() || c.is_whitespace() || c == '\u{fe0f}' || is_square(c)
+            }) {
+                continue;
+            }
+            let letters: String = line.chars().filter(char::is_ascii_alphabetic).collect();
+            if let Some(word) = Word::new(&letters.to_lowercase()) {
+                words.push(word);
+            }
+            let squares: String = line.chars().filter(|&c| is_square(c)).collect();
+            if let Some(mask) = Mask::from_emoji(&squares) {
+                masks.push(mask);
+            }
+        }
+        if words.is_empty() && masks.is_empty() {
+            return Err(HistoryError::Board("no rows of letters or squares".into()));
+        }
+        if words.len() != masks.len() {
+            return Err(HistoryError::Board(format!(
+                "{} rows of letters but {} rows of squares",
+                words.len(),
+                masks.len()
+            )));
+        }
+        Ok(words
+            .into_iter()
+            .zip(masks)
+            .map(|(word, mask)| Guess { word, mask })
+            .collect())
+    }
+
+    /// the history of guessing these words when the answer is `answer`
+    pub fn from_answer<'a>(answer: &str, words: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut history = Self::new();
+        for word in words {
+            history.push_for(answer, word);
+        }
+        history
+    }
+
+    /// add a guess after the others
+    pub fn push(&mut self, guess: Guess) {
+        self.0.push(guess);
+    }
+
+    /// play a word against the answer (which only makes sense for five letter words)
+    pub fn push_for(&mut self, answer: &str, word: &str) -> Mask {
+        let mask = Correctness::compute(answer, word);
+        let word = Word::new(word).unwrap_or_else(|| panic!("'{}' isn't a word to guess", word));
+        self.0.push(Guess { word, mask });
+        mask
+    }
+
+    /// the words guessed, oldest first
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|guess| guess.word.as_str())
+    }
+
+    /// the masks they got, oldest first
+    pub fn masks(&self) -> impl Iterator<Item = Mask> + '_ {
+        self.0.iter().map(|guess| guess.mask)
+    }
+
+    /// whether the last guess got all greens
+    pub fn is_won(&self) -> bool {
+        self.0.last().is_some_and(|guess| guess.mask.is_win())
+    }
+
+    /// the words that could still be the answer
+    pub fn candidates<'a>(
+        &'a self,
+        words: impl IntoIterator<Item = &'a str>,
+    ) -> impl Iterator<Item = &'a str> {
+        candidates(&self.0, words)
+    }
+
+    /// Make sure some answer could have given every mask, without needing a word list: nothing
+    /// after a win, no two greens in the same place, and no letter both known to be in the
+    /// answer more times than another guess says it can be.
+    pub fn check(&self) -> Result<(), HistoryError> {
+        let mut greens: [Option<u8>; 5] = [None; 5];
+        let mut at_least = [0u8; 26];
+        let mut at_most = [u8::MAX; 26];
+        for (i, guess) in self.0.iter().enumerate() {
+            let round = i + 1;
+            let contradiction = |reason: String| HistoryError::Contradiction { round, reason };
+            if i > 0 && self.0[i - 1].mask.is_win() {
+                return Err(HistoryError::AfterWin { round });
+            }
+            let word = guess.word.bytes();
+            let mut seen = [0u8; 26];
+            let mut gray = [false; 26];
+            for (pos, (&letter, &c)) in word.iter().zip(&guess.mask).enumerate() {
+                let l = (letter - b'a') as usize;
+                match c {
+                    Correctness::Correct => {
+                        if greens[pos].is_some_and(|g| g != letter) {
+                            return Err(contradiction(format!(
+                                "two different letters are green in place {}",
+                                pos + 1
+                            )));
+                        }
+                        greens[pos] = Some(letter);
+                        seen[l] += 1;
+                    }
+                    Correctness::Misplaced => seen[l] += 1,
+                    Correctness::Wrong => gray[l] = true,
+                }
+            }
+            for l in 0..26 {
+                at_least[l] = at_least[l].max(seen[l]);
+                if gray[l] {
+                    at_most[l] = at_most[l].min(seen[l]);
+                }
+                if at_least[l] > at_most[l] {
+                    return Err(contradiction(format!(
+                        "'{}' can't be in the answer {} times and at most {}",
+                        (b'a' + l as u8) as char,
+                        at_least[l],
+                        at_most[l]
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::ops::Deref for History {
+    type Target = [Guess];
+
+    fn deref(&self) -> &[Guess] {
+        &self.0
+    }
+}
+
+impl From<Vec<Guess>> for History {
+    fn from(guesses: Vec<Guess>) -> Self {
+        Self(guesses)
+    }
+}
+
+impl From<&[Guess]> for History {
+    fn from(guesses: &[Guess]) -> Self {
+        Self(guesses.to_vec())
+    }
+}
+
+impl From<History> for Vec<Guess> {
+    fn from(history: History) -> Self {
+        history.0
+    }
+}
+
+impl FromIterator<Guess> for History {
+    fn from_iter<I: IntoIterator<Item = Guess>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Extend<Guess> for History {
+    fn extend<I: IntoIterator<Item = Guess>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl<'a> IntoIterator for &'a History {
+    type Item = &'a Guess;
+    type IntoIter = std::slice::Iter<'a, Guess>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+// the same format `History::parse` reads
+impl fmt::Display for History {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, guess) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}={}", guess.word, mask_to_cmw(&guess.mask))?;
+        }
+        Ok(())
+    }
+}
+
+/// C for green, M for yellow and W for gray, like the `mask!` shorthand in the tests
+pub fn mask_to_cmw(mask: &Mask) -> String {
+    mask.iter()
+        .map(|c| match c {
+            Correctness::Correct => 'C',
+            Correctness::Misplaced => 'M',
+            Correctness::Wrong => 'W',
+        })
+        .collect()
+}
+
+// the color one of the squares in `MaskExt::from_emoji` stands for
+fn square(c: char) -> Option<Correctness> {
+    match c {
+        '🟩' | '🟧' => Some(Correctness::Correct),
+        '🟨' | '🟦' => Some(Correctness::Misplaced),
+        '⬛' | '⬜' => Some(Correctness::Wrong),
+        _ => None,
+    }
+}
+
+/// the reverse of `mask_to_cmw`
+pub fn mask_from_cmw(s: &str) -> Option<Mask> {
+    if s.len() != 5 {
+        return None;
+    }
+    let mut mask = [Correctness::Wrong; 5];
+    for (m, c) in mask.iter_mut().zip(s.chars()) {
+        *m = match c {
+            'C' => Correctness::Correct,
+            'M' => Correctness::Misplaced,
+            'W' => Correctness::Wrong,
+            _ => return None,
+        };
+    }
+    Some(mask)
+}
+
+pub trait Guesser {
+    /// function that makes a guess; takes info of current guess progress as as arguments
+    fn guess(&mut self, history: &History) -> String;
+}
+
+impl<G: Guesser + ?Sized> Guesser for Box<G> {
+    fn guess(&mut self, history: &History) -> String {
+        (**self).guess(history)
+    }
+}
+
+impl Guesser for fn(history: &History) -> String {
+    fn guess(&mut self, history: &History) -> String {
+        (*self)(history)
+    }
+}
+
+/// turn a mask into a unique index in 0..3^N so we can bucket by it
+pub fn pattern_index<const N: usize>(mask: [Correctness; N]) -> usize {
+    mask.iter().fold(0, |acc, c| {
+        acc * 3
+            + match c {
+                Correctness::Correct => 0,
+                Correctness::Misplaced => 1,
+                Correctness::Wrong => 2,
+            }
+    })
+}
+
+/// the words that could still be the answer after the history
+pub fn candidates<'a, const N: usize>(
+    history: &'a [Guess<N>],
+    words: impl IntoIterator<Item = &'a str>,
+) -> impl Iterator<Item = &'a str> {
+    words
+        .into_iter()
+        .filter(move |word| history.iter().all(|guess| guess.matches(word)))
+}
+
+/// FNV-1a over the parts in order. Unlike std's hasher it comes out the same from one build to the
+/// next, so it's fine for hashes that get saved.
+pub fn stable_hash<'a>(parts: impl IntoIterator<Item = &'a str>) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in parts {
+        // the separator keeps ["ab", "c"] and ["a", "bc"] apart
+        for b in part.bytes().chain([0]) {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+/// the entropy of a distribution given as counts, in bits
+pub fn entropy(counts: impl IntoIterator<Item = u64> + Clone) -> f64 {
+    let total: u64 = counts.clone().into_iter().sum();
+    counts
+        .into_iter()
+        .filter(|&c| c != 0)
+        .map(|c| {
+            let p = c as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// The candidates split up by the mask `guess` would get against each of them, indexed by
+/// `pattern_index` (so the answer itself, if it's there, is in bucket 0).
+pub fn partition<'a>(guess: &str, candidates: &[&'a str]) -> [Vec<&'a str>; 243] {
+    partition_by(guess, candidates, |word| word)
+}
+
+/// `partition` for candidates that carry more than the word, like a count
+pub fn partition_by<T: Clone>(
+    guess: &str,
+    candidates: &[T],
+    word: impl Fn(&T) -> &str,
+) -> [Vec<T>; 243] {
+    let mut buckets = std::array::from_fn(|_| Vec::new());
+    for candidate in candidates {
+        buckets[pattern_index(Correctness::compute(word(candidate), guess))]
+            .push(candidate.clone());
+    }
+    buckets
+}
+
+/// how much of the candidates' weight ends up in each bucket, for when the words themselves
+/// aren't needed
+pub fn partition_counts<'a>(
+    guess: &str,
+    candidates: impl IntoIterator<Item = (&'a str, u64)>,
+) -> [u64; 243] {
+    let mut buckets = [0u64; 243];
+    for (candidate, count) in candidates {
+        buckets[pattern_index(Correctness::compute(candidate, guess))] += count;
+    }
+    buckets
+}
+
+// `partition_counts` for each of the guesses at once, worked out on the GPU when the crate was
+// built with the `gpu` feature, there's a GPU, and there are enough of them to be worth it. `None`
+// means go through them on the CPU as usual.
+pub(crate) fn partition_counts_on_gpu(
+    guesses: &[&str],
+    candidates: &[(&str, u64)],
+) -> Option<Vec<[u64; 243]>> {
+    #[cfg(feature = "gpu")]
+    return crate::gpu::partition_counts(guesses, candidates);
+    #[cfg(not(feature = "gpu"))]
+    {
+        let _ = (guesses, candidates);
+        None
+    }
+}
+
+/// expected information gained from `guess` against the weighted candidates, in bits
+pub fn bits(candidates: &[(&str, u64)], guess: &str) -> f64 {
+    entropy(partition_counts(guess, candidates.iter().copied()))
+}
+
+/// `partition_counts` for words of any length, with a bucket for each of the 3^N masks
+pub fn partition_counts_n<'a, const N: usize>(
+    guess: &str,
+    candidates: impl IntoIterator<Item = (&'a str, u64)>,
+) -> Vec<u64> {
+    let mut buckets = vec![0u64; 3usize.pow(N as u32)];
+    for (candidate, count) in candidates {
+        buckets[pattern_index(Correctness::compute_n::<N>(candidate, guess))] += count;
+    }
+    buckets
+}
+
+/// `bits` for words of any length
+pub fn bits_n<const N: usize>(candidates: &[(&str, u64)], guess: &str) -> f64 {
+    entropy(partition_counts_n::<N>(guess, candidates.iter().copied()))
+}
+
+#[cfg(test)]
+mod tests {
+    mod guess_matcher {
+        use crate::Guess;
+
+        #[test]
+        fn matches() {
+            assert!(Guess {
+                word: "abcde".parse().unwrap(),
+                mask: mask![C C C C C],
+            }
+            .matches("abcde"));
+        }
+
+        #[test]
+        fn misplaced_must_move() {
+            let g = Guess {
+                word: "abcde".parse().unwrap(),
+                mask: mask![M W W W W],
+            };
+            assert!(g.matches("fghai"));
+            assert!(!g.matches("afghi"));
+            assert!(!g.matches("fghij"));
+        }
+
+        #[test]
+        fn wrong_excludes_letter() {
+            let g = Guess {
+                word: "abcde".parse().unwrap(),
+                mask: mask![C W W W W],
+            };
+            assert!(g.matches("afghi"));
+            assert!(!g.matches("afghb"));
+        }
+
+        #[test]
+        fn repeated_letter_yellow_then_gray() {
+            // only one 'a' in the answer, and it's not in slot 0 or 1
+            let g = Guess {
+                word: "aabcd".parse().unwrap(),
+                mask: mask![M W W W W],
+            };
+            assert!(g.matches("efagh"));
+            assert!(!g.matches("efaga"));
+        }
+    }
+    mod compute {
+        use crate::Correctness;
+        #[test]
+        fn all_correct() {
+            assert_eq!(
+                Correctness::compute("abcde", "abcde"),
+                [Correctness::Correct; 5]
+            )
+        }
+
+        #[test]
+        fn all_wrong() {
+            assert_eq!(
+                Correctness::compute("abcde", "ghjkl"),
+                [Correctness::Wrong; 5]
+            )
+        }
+        #[test]
+        fn all_misplaced() {
+            assert_eq!(
+                Correctness::compute("abcde", "eabcd"),
+                [Correctness::Misplaced; 5]
+            )
+        }
+
+        #[test]
+        fn repeat_green() {
+            assert_eq!(
+                Correctness::compute("aabbb", "aaccc"),
+                [
+                    Correctness::Correct,
+                    Correctness::Correct,
+                    Correctness::Wrong,
+                    Correctness::Wrong,
+                    Correctness::Wrong,
+                ]
+            )
+        }
+
+        #[test]
+        fn repeat_yellow() {
+            assert_eq!(
+                Correctness::compute("aabbb", "ccaac"),
+                [
+                    Correctness::Wrong,
+                    Correctness::Wrong,
+                    Correctness::Misplaced,
+                    Correctness::Misplaced,
+                    Correctness::Wrong,
+                ]
+            )
+        }
+
+        #[test]
+        fn repeat_some_green() {
+            assert_eq!(
+                Correctness::compute("aabbb", "caacc"),
+                [
+                    Correctness::Wrong,
+                    Correctness::Correct,
+                    Correctness::Misplaced,
+                    Correctness::Wrong,
+                    Correctness::Wrong,
+                ]
+            )
+        }
+
+        #[test]
+        fn correct_number_of_misplaced() {
+            assert_eq!(
+                Correctness::compute("azzaz", "aaabb"),
+                [
+                    Correctness::Correct,
+                    Correctness::Misplaced,
+                    Correctness::Wrong,
+                    Correctness::Wrong,
+                    Correctness::Wrong,
+                ]
+            )
+        }
+
+        #[test]
+        fn correct_number_of_correct() {
+            assert_eq!(
+                Correctness::compute("baccc", "aaddd"),
+                [
+                    Correctness::Wrong,
+                    Correctness::Correct,
+                    Correctness::Wrong,
+                    Correctness::Wrong,
+                    Correctness::Wrong,
+                ]
+            )
+        }
+
+        #[test]
+        fn correct_number_of_correct2() {
+            assert_eq!(
+                Correctness::compute("abcde", "aacde"),
+                [
+                    Correctness::Correct,
+                    Correctness::Wrong,
+                    Correctness::Correct,
+                    Correctness::Correct,
+                    Correctness::Correct,
+                ]
+            )
+        }
+    }
+
+    mod history {
+        use crate::core::*;
+
+        #[test]
+        fn parse() {
+            let history = History::parse("crane=WWMCW tares=CWWWM").unwrap();
+            assert_eq!(history.len(), 2);
+            assert_eq!(history.words().collect::<Vec<_>>(), ["crane", "tares"]);
+            assert_eq!(history.to_string(), "crane=WWMCW tares=CWWWM");
+            assert_eq!(
+                History::parse("crane=WWMCW,tares=CWWWM"),
+                Ok(history.clone())
+            );
+            assert_eq!(
+                History::parse("crane=BBYGB tares=GBBBY"),
+                Ok(history.clone())
+            );
+            assert!(History::parse("crane=BBYGW").is_err());
+            assert!(!history.is_won());
+            // the 'a' can't be both in the answer and not
+            assert!(history.check().is_err());
+            assert!(matches!(
+                History::parse("crane=WWMC"),
+                Err(HistoryError::Malformed(_))
+            ));
+            assert_eq!(
+                History::from_answer("right", ["wrong", "right"]).to_string(),
+                "wrong=WMWWM right=CCCCC"
+            );
+        }
+
+        #[test]
+        fn from_board() {
+            let expected = History::from_answer("right", ["tares", "doily", "right"]);
+            let interleaved =
+                "Wordle 1,234 3/6\n\nTARES\n🟨⬛🟨⬛⬛\nD O I L Y\n⬛⬛🟨⬛⬛\nRIGHT\n🟩🟩🟩🟩🟩\n";
+            assert_eq!(History::from_board(interleaved), Ok(expected.clone()));
+            let blocks = "tares\ndoily\nright\n\n🟦⬜🟦⬜⬜\n⬜⬜🟦⬜⬜\n🟧🟧🟧🟧🟧\n";
+            assert_eq!(History::from_board(blocks), Ok(expected.clone()));
+            let side_by_side = "TARES 🟨⬛️🟨⬛️⬛️\nDOILY ⬛️⬛️🟨⬛️⬛️\nRIGHT 🟩🟩🟩🟩🟩";
+            assert_eq!(History::from_board(side_by_side), Ok(expected));
+            assert!(matches!(
+                History::from_board("tares\ndoily\n⬛⬛🟨⬛⬛"),
+                Err(HistoryError::Board(_))
+            ));
+            assert!(History::from_board("").is_err());
+        }
+
+        #[test]
+        fn check() {
+            assert!(History::from_answer("right", ["wrong", "light", "right"])
+                .check()
+                .is_ok());
+            let check = |s| History::parse(s).unwrap().check();
+            assert_eq!(
+                check("right=CCCCC right=CCCCC"),
+                Err(HistoryError::AfterWin { round: 2 })
+            );
+            // two different greens in the first place
+            assert!(check("crane=CWWWW tares=CWWWW").is_err());
+            // an 'a' that's both absent and present
+            assert!(check("crane=WWWWW tares=WMWWW").is_err());
+            // one 'a' is fine after a gray second 'a'
+            assert!(check("aahed=MWWWW tares=WCWWW").is_ok());
+        }
+    }
+
+    mod masks {
+        use crate::core::*;
+
+        #[test]
+        fn helpers() {
+            let mask = Correctness::compute("right", "wrong");
+            assert!(!mask.is_win());
+            assert_eq!((mask.greens(), mask.yellows()), (0, 2));
+            assert_eq!(mask.to_emoji(), "⬛🟨⬛⬛🟨");
+            assert_eq!(mask.to_compact_string(), "BYBBY");
+            assert_eq!(Mask::from_compact_str("BYBBY"), Some(mask));
+            assert_eq!(Mask::from_emoji(&mask.to_emoji()), Some(mask));
+            assert_eq!(Mask::from_emoji("⬜🟦⬜⬜🟦"), Some(mask));
+            assert_eq!(Mask::from_emoji("⬛🟨⬛⬛"), None);
+            assert!([Correctness::Correct; 5].is_win());
+        }
+
+        #[test]
+        fn bucket_order() {
+            let masks: Vec<_> = all_masks().collect();
+            assert_eq!(masks.len(), 243);
+            assert!(masks[0].is_win());
+            for (i, mask) in masks.iter().enumerate() {
+                assert_eq!(mask.index(), i);
+            }
+            // sorting masks puts them in bucket order too
+            let mut sorted = masks.clone();
+            sorted.sort();
+            assert_eq!(sorted, masks);
+            assert_eq!(Mask::from_index(243), None);
+        }
+    }
+
+    mod partitions {
+        use crate::core::*;
+
+        #[test]
+        fn buckets() {
+            let candidates = ["right", "might", "sight", "fight"];
+            let buckets = partition("masts", &candidates);
+            assert_eq!(buckets.iter().map(Vec::len).sum::<usize>(), 4);
+            let mask = Correctness::compute("right", "masts");
+            assert_eq!(buckets[mask.index()], ["right", "fight"]);
+            assert!(partition("right", &candidates)[0] == ["right"]);
+
+            let counts = partition_counts("masts", [("right", 3), ("fight", 2), ("might", 1)]);
+            assert_eq!(counts[mask.index()], 5);
+            assert_eq!(counts.iter().sum::<u64>(), 6);
+
+            let weighted = [("right", 3), ("might", 1)];
+            let buckets = partition_by("masts", &weighted, |&(word, _)| word);
+            assert_eq!(buckets[mask.index()], [("right", 3)]);
+        }
+    }
+
+    mod primitives {
+        use crate::core::*;
+
+        #[test]
+        fn words() {
+            let word: Word = "crane".parse().unwrap();
+            assert_eq!(word.as_str(), "crane");
+            assert!(Word::new("Crane").is_none());
+            assert!(Word::new("cran").is_none());
+        }
+
+        #[test]
+        fn filtering_and_scoring() {
+            let history = [Guess {
+                word: "masts".parse().unwrap(),
+                mask: Correctness::compute("right", "masts"),
+            }];
+            let words = ["right", "might", "sight", "fight"];
+            let left: Vec<_> = candidates(&history, words).collect();
+            assert_eq!(left, ["right", "fight"]);
+            assert_eq!(entropy([1, 1, 0]), 1.0);
+            let weighted: Vec<_> = words.iter().map(|&w| (w, 1)).collect();
+            assert_eq!(bits(&weighted, "masts"), 1.5);
+            assert_eq!(pattern_index([Correctness::Correct; 5]), 0);
+        }
+    }
+}
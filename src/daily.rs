@@ -0,0 +1,104 @@
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::answers;
+
+// A calendar day (in UTC), counted from 1970-01-01.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Day(pub i64);
+
+// the day the first official answer was played, 2021-06-19
+const FIRST: Day = Day(18797);
+
+impl Day {
+    pub fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Day((secs / 86400) as i64)
+    }
+
+    // days_from_civil from http://howardhinnant.github.io/date_algorithms.html
+    pub fn from_ymd(year: i64, month: u32, day: u32) -> Self {
+        let year = if month <= 2 { year - 1 } else { year };
+        let era = year.div_euclid(400);
+        let yoe = year - era * 400;
+        let month = month as i64;
+        let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        Day(era * 146097 + doe - 719468)
+    }
+
+    // civil_from_days, the reverse of `from_ymd`
+    pub fn ymd(self) -> (i64, u32, u32) {
+        let z = self.0 + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + (month <= 2) as i64;
+        (year, month, day)
+    }
+
+    // a day written as YYYY-MM-DD
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.splitn(3, '-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+        }
+        let parsed = Self::from_ymd(year, month, day);
+        // catch days past the end of the month, like February 30th
+        (parsed.ymd() == (year, month, day)).then_some(parsed)
+    }
+
+    // the puzzle number, counting the first official one as 0
+    pub fn number(self) -> Option<usize> {
+        usize::try_from(self.0 - FIRST.0).ok()
+    }
+
+    // the day of puzzle `n`, the other way from `number`
+    pub fn of_number(n: usize) -> Self {
+        Day(FIRST.0 + n as i64)
+    }
+
+    // The official answer for the day. The list only covers so many days, so after it runs out
+    // the answers start over from the beginning.
+    pub fn answer(self) -> Option<&'static str> {
+        self.number()
+            .and_then(|n| answers::by_index(n % answers::all().len()))
+    }
+}
+
+impl fmt::Display for Day {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = self.ymd();
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates() {
+        assert_eq!(Day::from_ymd(1970, 1, 1), Day(0));
+        assert_eq!(Day::from_ymd(2021, 6, 19), FIRST);
+        assert_eq!(Day::parse("2024-02-29").unwrap().to_string(), "2024-02-29");
+        assert_eq!(Day::parse("2023-02-29"), None);
+        assert_eq!(Day::parse("2023-2"), None);
+    }
+
+    #[test]
+    fn answers() {
+        assert_eq!(FIRST.answer(), Some("cigar"));
+        assert_eq!(Day(FIRST.0 + 1).answer(), Some("rebut"));
+        assert_eq!(Day(FIRST.0 - 1).answer(), None);
+    }
+}
@@ -0,0 +1,178 @@
+// A live summary of a benchmark run, redrawn in place in the terminal as games finish: how far
+// along it is and how fast it's going, the average number of guesses overall and over the last
+// few games, the answer being played, and how many games took each number of guesses.
+
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+// how many of the latest games the rolling average covers
+const RECENT: usize = 100;
+
+// redrawing takes longer than a game from a decision tree, so it's done at most this often
+const REFRESH: Duration = Duration::from_millis(50);
+
+pub struct Dashboard {
+    total: usize,
+    games: usize,
+    // guesses over all the solved games, and what the latest ones took
+    guesses: usize,
+    solved: usize,
+    recent: VecDeque<usize>,
+    // games solved in 1 to 6 guesses, then in more than that, then not at all
+    histogram: [usize; 8],
+    current: Option<String>,
+    started: Instant,
+    // when it was last drawn, and how many lines that took (to go back up over next time)
+    drawn: Option<(Instant, usize)>,
+}
+
+impl Dashboard {
+    // for a run of `total` games
+    pub fn new(total: usize) -> Self {
+        Self {
+            total,
+            games: 0,
+            guesses: 0,
+            solved: 0,
+            recent: VecDeque::with_capacity(RECENT),
+            histogram: [0; 8],
+            current: None,
+            started: Instant::now(),
+            drawn: None,
+        }
+    }
+
+    // `answer` is being played now
+    pub fn start(&mut self, answer: &str) {
+        self.current = Some(answer.to_string());
+    }
+
+    // the game being played finished, solved in `guesses` or not at all
+    pub fn record(&mut self, guesses: Option<usize>) {
+        self.games += 1;
+        self.current = None;
+        match guesses {
+            Some(n) => {
+                self.solved += 1;
+                self.guesses += n;
+                if self.recent.len() == RECENT {
+                    self.recent.pop_front();
+                }
+                self.recent.push_back(n);
+                self.histogram[n.clamp(1, 7) - 1] += 1;
+            }
+            None => self.histogram[7] += 1,
+        }
+    }
+
+    // The dashboard as lines of text at most `width` columns wide, after running for `elapsed`.
+    pub fn render(&self, elapsed: Duration, width: usize) -> Vec<String> {
+        let mean = |sum: usize, n: usize| match n {
+            0 => "-".to_string(),
+            n => format!("{:.4}", sum as f64 / n as f64),
+        };
+        let rate = match elapsed.as_secs_f64() {
+            secs if secs > 0.0 => format!("{:.1} games/s", self.games as f64 / secs),
+            _ => "-".to_string(),
+        };
+        let mut lines = vec![
+            format!(
+                "games    {}/{} ({:.0}%), {}",
+                self.games,
+                self.total,
+                100.0 * self.games as f64 / self.total.max(1) as f64,
+                rate
+            ),
+            format!(
+                "average  {} overall, {} over the last {}",
+                mean(self.guesses, self.solved),
+                mean(self.recent.iter().sum(), self.recent.len()),
+                self.recent.len()
+            ),
+            format!("playing  {}", self.current.as_deref().unwrap_or("-")),
+        ];
+        let most = self.histogram.iter().copied().max().unwrap_or(0).max(1);
+        let count_width = most.to_string().len();
+        // the label and a space, then the bar, then a space and the count
+        let room = width.saturating_sub(4 + 1 + count_width).max(1);
+        for (i, &count) in self.histogram.iter().enumerate() {
+            let label = match i {
+                0..=5 => (i + 1).to_string(),
+                6 => "7+".to_string(),
+                _ => "x".to_string(),
+            };
+            let bar = "█".repeat(count * room / most);
+            lines.push(format!(
+                "{:>3} {:<room$} {:>count_width$}",
+                label,
+                bar,
+                count,
+                room = room,
+                count_width = count_width
+            ));
+        }
+        // a line that wraps would throw off going back up over it
+        lines
+            .into_iter()
+            .map(|line| line.chars().take(width).collect())
+            .collect()
+    }
+
+    // Redraw over the last drawing, unless that was only just now. `finish` always does.
+    pub fn tick(&mut self, out: &mut impl Write, width: usize) -> io::Result<()> {
+        match self.drawn {
+            Some((at, _)) if at.elapsed() < REFRESH => Ok(()),
+            _ => self.draw(out, width),
+        }
+    }
+
+    pub fn finish(&mut self, out: &mut impl Write, width: usize) -> io::Result<()> {
+        self.draw(out, width)
+    }
+
+    fn draw(&mut self, out: &mut impl Write, width: usize) -> io::Result<()> {
+        if let Some((_, lines)) = self.drawn {
+            // back up to the first line, and clear everything from there down
+            write!(out, "\x1b[{}A\r\x1b[J", lines)?;
+        }
+        let lines = self.render(self.started.elapsed(), width);
+        for line in &lines {
+            writeln!(out, "{}", line)?;
+        }
+        out.flush()?;
+        self.drawn = Some((Instant::now(), lines.len()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render() {
+        let mut dashboard = Dashboard::new(4);
+        for guesses in [Some(3), Some(4), None] {
+            dashboard.record(guesses);
+        }
+        dashboard.start("cigar");
+        let lines = dashboard.render(Duration::from_secs(2), 60);
+        assert_eq!(lines[0], "games    3/4 (75%), 1.5 games/s");
+        assert_eq!(lines[1], "average  3.5000 overall, 3.5000 over the last 2");
+        assert_eq!(lines[2], "playing  cigar");
+        assert_eq!(lines[4], "  2 ".to_string() + &" ".repeat(54) + " 0");
+        assert_eq!(lines[5], "  3 ".to_string() + &"█".repeat(54) + " 1");
+        assert_eq!(lines[10], "  x ".to_string() + &"█".repeat(54) + " 1");
+        let narrow = dashboard.render(Duration::from_secs(2), 20);
+        assert_eq!(narrow[5], "  3 ██████████████ 1");
+        assert!(narrow.iter().all(|line| line.chars().count() <= 20));
+
+        // the second drawing goes back over the first
+        let mut out = Vec::new();
+        dashboard.finish(&mut out, 20).unwrap();
+        dashboard.finish(&mut out, 20).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.matches("\x1b[11A").count(), 1);
+    }
+}
@@ -0,0 +1,765 @@
+// Tools for maintaining word lists in the "word count" format of `dictionary.txt`.
+//
+// A list can also say more about its words, after a first line of "#wordlist v2" (which lists
+// from before there was a v2 stop at, rather than quietly misreading):
+//
+//   #wordlist v2
+//   crane 1234 role=answer tags=bird,common
+//   grupo 56 role=guess lang=es
+//
+// Every field is optional: `role` is answer or guess (only a guess, never the answer), `lang` a
+// language code and `tags` a list of labels. Any other field is a bad line, since a field this
+// version doesn't know about could change what the words mean.
+//
+// Either way, a word has to be five lowercase ASCII letters (or as many as `parse_skipping_length`
+// is told), like the games take; any other word is a bad line too.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::algorithms::Prior;
+use crate::core::stable_hash;
+use crate::i18n::Lang;
+use crate::timings::{self, Phase};
+
+#[derive(Debug, Clone)]
+pub struct BuildOptions {
+    // only keep words this long
+    pub length: usize,
+    // drop words seen fewer times than this in the corpus
+    pub min_count: u64,
+    // the count words missing from the corpus get, so they stay valid guesses
+    pub missing_count: u64,
+    // words to leave out no matter what
+    pub exclude: Vec<String>,
+}
+
+impl Default for BuildOptions {
+    fn default() -> Self {
+        Self {
+            length: 5,
+            min_count: 1,
+            missing_count: 1,
+            exclude: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub content: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: expected a word and a count, got '{}'",
+            self.line, self.content
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// Every bad line in a word list, not just the first, so they can all be fixed in one go.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseErrors(pub Vec<ParseError>);
+
+impl fmt::Display for ParseErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // a corpus in the wrong format is wrong on every line, and a few say as much as all of them
+        const SHOWN: usize = 10;
+        write!(f, "{} bad lines", self.0.len())?;
+        for error in self.0.iter().take(SHOWN) {
+            write!(f, "\n  {}", error)?;
+        }
+        if self.0.len() > SHOWN {
+            write!(f, "\n  and {} more", self.0.len() - SHOWN)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseErrors {}
+
+// whether a game can play `word`: `length` lowercase ASCII letters, like `Word::new` takes
+fn playable(word: &str, length: usize) -> bool {
+    word.len() == length && word.bytes().all(|b| b.is_ascii_lowercase())
+}
+
+// a line of a word list: a five letter word, a space, and its count
+pub(crate) fn parse_line(line: &str) -> Option<(&str, u64)> {
+    parse_line_length(line, 5)
+}
+
+fn parse_line_length(line: &str, length: usize) -> Option<(&str, u64)> {
+    let (word, count) = line.split_once(' ')?;
+    Some((word, count.parse().ok()?)).filter(|_| playable(word, length))
+}
+
+const V2: &str = "#wordlist v2";
+
+// a line of a v2 word list: a word, its count, and then any fields
+fn parse_line_v2(line: &str, length: usize) -> Option<(&str, u64, WordMeta)> {
+    let mut fields = line.split(' ');
+    let word = fields.next().filter(|word| playable(word, length))?;
+    let count = fields.next()?.parse().ok()?;
+    let mut meta = WordMeta::default();
+    for field in fields {
+        match field.split_once('=')? {
+            ("role", "answer") => meta.role = Some(Role::Answer),
+            ("role", "guess") => meta.role = Some(Role::Guess),
+            ("lang", code) => meta.lang = Some(code.parse().ok()?),
+            ("tags", tags) => meta.tags = tags.split(',').map(str::to_string).collect(),
+            _ => return None,
+        }
+    }
+    Some((word, count, meta))
+}
+
+// whether a word can be the answer or is only ever a guess
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Answer,
+    Guess,
+}
+
+// What a v2 word list says about a word besides its count, for scorers and filters that care
+// (see `Dictionary::only`). Everything is unset for words from a plain list.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WordMeta {
+    pub tags: Vec<String>,
+    pub lang: Option<Lang>,
+    pub role: Option<Role>,
+}
+
+impl WordMeta {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+// " role=answer lang=es tags=a,b", or nothing for a word with nothing to say
+impl fmt::Display for WordMeta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.role {
+            Some(Role::Answer) => write!(f, " role=answer")?,
+            Some(Role::Guess) => write!(f, " role=guess")?,
+            None => {}
+        }
+        if let Some(lang) = self.lang {
+            write!(f, " lang={}", lang.code())?;
+        }
+        if !self.tags.is_empty() {
+            write!(f, " tags={}", self.tags.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+// Read a frequency corpus with one word and its count per line (separated by any whitespace, as
+// in most unigram dumps). Words are lowercased and counts for the same word are added up.
+pub fn parse_counts(text: &str) -> Result<BTreeMap<String, u64>, ParseError> {
+    let mut counts = BTreeMap::new();
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let err = || ParseError {
+            line: i + 1,
+            content: line.to_string(),
+        };
+        let mut fields = line.split_whitespace();
+        let word = fields.next().ok_or_else(err)?;
+        let count: u64 = fields
+            .next_back()
+            .ok_or_else(err)?
+            .parse()
+            .map_err(|_| err())?;
+        *counts.entry(word.to_lowercase()).or_insert(0) += count;
+    }
+    Ok(counts)
+}
+
+// Pair every usable word in `words` (one per line) with its count from the corpus.
+pub fn build(words: &str, counts: &BTreeMap<String, u64>, options: &BuildOptions) -> Dictionary {
+    let mut dictionary = BTreeMap::new();
+    for word in words.lines().map(str::trim) {
+        let word = word.to_lowercase();
+        if !playable(&word, options.length) {
+            continue;
+        }
+        if options.exclude.contains(&word) {
+            continue;
+        }
+        let count = counts.get(&word).copied().unwrap_or(options.missing_count);
+        if count < options.min_count {
+            continue;
+        }
+        dictionary.insert(word, count);
+    }
+    Dictionary::new(dictionary)
+}
+
+#[derive(Debug, Clone)]
+pub struct PlausibleOptions {
+    // the share of words to keep, most common first
+    pub share: f64,
+    // whether words that look like plurals (ending in a single s) or past tenses (ending in ed)
+    // can be answers; Wordle's own answers hardly ever are
+    pub plurals: bool,
+    pub past_tense: bool,
+}
+
+impl Default for PlausibleOptions {
+    fn default() -> Self {
+        Self {
+            share: 0.3,
+            plurals: false,
+            past_tense: false,
+        }
+    }
+}
+
+// no -ss, -us or -is, which are mostly words of their own (glass, virus, basis)
+fn looks_plural(word: &str) -> bool {
+    word.ends_with('s') && !["ss", "us", "is"].iter().any(|end| word.ends_with(end))
+}
+
+// but not -eed (bleed, greed)
+fn looks_past_tense(word: &str) -> bool {
+    word.ends_with("ed") && !word.ends_with("eed")
+}
+
+fn keep_form(word: &str, no_plurals: bool, no_past_tense: bool) -> bool {
+    let plural = no_plurals && looks_plural(word);
+    let past_tense = no_past_tense && looks_past_tense(word);
+    !(plural || past_tense)
+}
+
+// A word list with counts, kept in alphabetical order like the bundled one, and whatever a v2
+// list says about its words (only the words it says something about are in there).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dictionary(pub BTreeMap<String, u64>, pub BTreeMap<String, WordMeta>);
+
+impl Dictionary {
+    pub fn new(words: BTreeMap<String, u64>) -> Self {
+        Self(words, BTreeMap::new())
+    }
+
+    // the dictionary that ships with the crate
+    pub fn bundled() -> Self {
+        Self::parse(include_str!("../dictionary.txt"))
+            .expect("the bundled dictionary is well-formed")
+    }
+
+    // the bundled dictionary, parsed once for the whole program
+    pub fn shared() -> &'static Self {
+        static SHARED: OnceLock<Dictionary> = OnceLock::new();
+        SHARED.get_or_init(Self::bundled)
+    }
+
+    // The words for a language's Wordle: the bundled list for English, and for the others a small
+    // hand-picked list if the crate was built with its lang-* feature. Those have their accents
+    // dropped and words with other letters (like ñ or ß) left out, and their counts go by rough
+    // rank rather than a corpus; `build` makes a fuller one from a real corpus.
+    pub fn for_lang(lang: Lang) -> Option<Self> {
+        let text = match lang {
+            Lang::English => return Some(Self::bundled()),
+            #[cfg(feature = "lang-es")]
+            Lang::Spanish => Some(include_str!("../dictionaries/es.txt")),
+            #[cfg(feature = "lang-de")]
+            Lang::German => Some(include_str!("../dictionaries/de.txt")),
+            #[cfg(feature = "lang-fr")]
+            Lang::French => Some(include_str!("../dictionaries/fr.txt")),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        };
+        text.map(|text| Self::parse(text).expect("the bundled dictionaries are well-formed"))
+    }
+
+    // stops at the first bad line; see `parse_all` for all of them
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        Self::parse_all(text).map_err(|ParseErrors(errors)| {
+            errors
+                .into_iter()
+                .next()
+                .expect("there's an error for every bad line")
+        })
+    }
+
+    pub fn parse_all(text: &str) -> Result<Self, ParseErrors> {
+        match Self::parse_skipping(text) {
+            (dictionary, errors) if errors.is_empty() => Ok(dictionary),
+            (_, errors) => Err(ParseErrors(errors)),
+        }
+    }
+
+    // the words from every good line, and what was wrong with the rest
+    pub fn parse_skipping(text: &str) -> (Self, Vec<ParseError>) {
+        Self::parse_skipping_length(text, 5)
+    }
+
+    // `parse_skipping` for a list of `length` letter words (see `dict build --length`)
+    pub fn parse_skipping_length(text: &str, length: usize) -> (Self, Vec<ParseError>) {
+        timings::time(Phase::Load, || {
+            let mut dictionary = Self::default();
+            let mut errors = Vec::new();
+            let v2 = text.lines().next() == Some(V2);
+            for (i, line) in text.lines().enumerate().skip(usize::from(v2)) {
+                let parsed = match v2 {
+                    true => parse_line_v2(line, length),
+                    false => parse_line_length(line, length)
+                        .map(|(word, count)| (word, count, Default::default())),
+                };
+                match parsed {
+                    Some((word, count, meta)) => {
+                        dictionary.0.insert(word.to_string(), count);
+                        if meta != WordMeta::default() {
+                            dictionary.1.insert(word.to_string(), meta);
+                        }
+                    }
+                    None => errors.push(ParseError {
+                        line: i + 1,
+                        content: line.to_string(),
+                    }),
+                }
+            }
+            (dictionary, errors)
+        })
+    }
+
+    // what the list says about `word`, which is nothing for most lists
+    pub fn meta(&self, word: &str) -> &WordMeta {
+        static NOTHING: WordMeta = WordMeta {
+            tags: Vec::new(),
+            lang: None,
+            role: None,
+        };
+        self.1.get(word).unwrap_or(&NOTHING)
+    }
+
+    // only the words `keep` likes, along with what the list says about them
+    pub fn only(&self, keep: impl Fn(&str, &WordMeta) -> bool) -> Dictionary {
+        self.keeping(
+            self.0
+                .iter()
+                .filter(|(word, _)| keep(word, self.meta(word)))
+                .map(|(word, &count)| (word.clone(), count))
+                .collect(),
+        )
+    }
+
+    // The words the list marks as answers, if it marks any. A list that does is taken to mark
+    // them all.
+    pub fn marked_answers(&self) -> Option<Dictionary> {
+        let answers = self.only(|_, meta| meta.role == Some(Role::Answer));
+        (!answers.0.is_empty()).then_some(answers)
+    }
+
+    // `words` (some of ours, maybe with other counts) with what the list says about them
+    fn keeping(&self, words: BTreeMap<String, u64>) -> Dictionary {
+        let meta = self
+            .1
+            .iter()
+            .filter(|(word, _)| words.contains_key(*word))
+            .map(|(word, meta)| (word.clone(), meta.clone()))
+            .collect();
+        Dictionary(words, meta)
+    }
+
+    // A guess at which words could be answers, for a list of allowed guesses that doesn't come
+    // with answers of its own: the most common words, leaving out the word forms answers are
+    // never picked from.
+    pub fn plausible_answers(&self, options: &PlausibleOptions) -> Dictionary {
+        let mut words: Vec<_> = self
+            .0
+            .iter()
+            .filter(|(word, _)| keep_form(word, !options.plurals, !options.past_tense))
+            .collect();
+        // the most common first, and alphabetical among equals so it's the same every time
+        words.sort_by(|(a, x), (b, y)| y.cmp(x).then(a.cmp(b)));
+        let keep = (words.len() as f64 * options.share.clamp(0.0, 1.0)).ceil() as usize;
+        self.keeping(
+            words
+                .into_iter()
+                .take(keep)
+                .map(|(word, &count)| (word.clone(), count))
+                .collect(),
+        )
+    }
+
+    // Only the `n` most common words, for a word list taken from a corpus, where the rare words
+    // are mostly ones no puzzle would use as the answer.
+    pub fn most_common(&self, n: usize) -> Dictionary {
+        let mut words: Vec<_> = self.0.iter().collect();
+        // alphabetical among equals, like `plausible_answers`
+        words.sort_by(|(a, x), (b, y)| y.cmp(x).then(a.cmp(b)));
+        self.keeping(
+            words
+                .into_iter()
+                .take(n)
+                .map(|(word, &count)| (word.clone(), count))
+                .collect(),
+        )
+    }
+
+    // the same words, weighed by `prior` instead of by how often they were seen
+    pub fn with_prior(&self, prior: &dyn Prior) -> Dictionary {
+        let words: Vec<_> = self
+            .0
+            .iter()
+            .map(|(word, &count)| (word.as_str(), count))
+            .collect();
+        self.keeping(
+            words
+                .iter()
+                .zip(prior.weights(&words))
+                .map(|(&(word, _), weight)| (word.to_string(), weight))
+                .collect(),
+        )
+    }
+
+    // The words that don't look like regular plurals or past tenses, for whichever of the two
+    // are left out. The official answers avoid both, so leaving them out of the candidates
+    // helps in the real game (they're still fine to guess).
+    pub fn without_forms(&self, plurals: bool, past_tense: bool) -> Dictionary {
+        self.only(|word, _| keep_form(word, plurals, past_tense))
+    }
+
+    // every word in either list, with the counts of words in both added together (and what
+    // this list says about a word over what the other one does)
+    pub fn merge(&self, other: &Dictionary) -> Dictionary {
+        let mut merged = self.clone();
+        for (word, &count) in &other.0 {
+            *merged.0.entry(word.clone()).or_insert(0) += count;
+        }
+        for (word, meta) in &other.1 {
+            merged.1.entry(word.clone()).or_insert_with(|| meta.clone());
+        }
+        merged
+    }
+
+    // what changed going from `self` to `other`
+    pub fn diff(&self, other: &Dictionary) -> Diff {
+        let mut diff = Diff::default();
+        for (word, &count) in &self.0 {
+            match other.0.get(word) {
+                None => diff.removed.push((word.clone(), count)),
+                Some(&new) if new != count => diff.changed.push((word.clone(), count, new)),
+                Some(_) => {}
+            }
+        }
+        for (word, &count) in &other.0 {
+            if !self.0.contains_key(word) {
+                diff.added.push((word.clone(), count));
+            }
+        }
+        diff
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Diff {
+    pub added: Vec<(String, u64)>,
+    pub removed: Vec<(String, u64)>,
+    // word, old count, new count
+    pub changed: Vec<(String, u64, u64)>,
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+// one line per difference: "+ word count", "- word count", or "~ word old -> new"
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (word, count) in &self.added {
+            writeln!(f, "+ {} {}", word, count)?;
+        }
+        for (word, count) in &self.removed {
+            writeln!(f, "- {} {}", word, count)?;
+        }
+        for (word, old, new) in &self.changed {
+            writeln!(f, "~ {} {} -> {}", word, old, new)?;
+        }
+        Ok(())
+    }
+}
+
+// a plain list unless there's more to say about some word, so plain lists stay as they were
+impl fmt::Display for Dictionary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.1.is_empty() {
+            writeln!(f, "{}", V2)?;
+        }
+        for (word, count) in &self.0 {
+            writeln!(f, "{} {}{}", word, count, self.meta(word))?;
+        }
+        Ok(())
+    }
+}
+
+// A hash of a dictionary and answer list, saved with anything worked out from them (see
+// `artifact`) so it can't be loaded against different ones and quietly give wrong answers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WordlistHash(pub u64);
+
+impl WordlistHash {
+    // the answers count in order, since the daily game goes by it
+    pub fn of(dictionary: &Dictionary, answers: &[&str]) -> Self {
+        let dictionary = dictionary.to_string();
+        Self(stable_hash(
+            [dictionary.as_str(), "answers"]
+                .into_iter()
+                .chain(answers.iter().copied()),
+        ))
+    }
+
+    // the bundled dictionary and answers, which is what the algorithms use
+    pub fn bundled() -> Self {
+        // hashing means writing the whole dictionary out, so it's only done once
+        static BUNDLED: OnceLock<WordlistHash> = OnceLock::new();
+        *BUNDLED.get_or_init(|| {
+            let answers: Vec<_> = crate::algorithms::answers().collect();
+            Self::of(Dictionary::shared(), &answers)
+        })
+    }
+}
+
+impl fmt::Display for WordlistHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordlistMismatch {
+    pub expected: WordlistHash,
+    // as written in the file
+    pub found: String,
+}
+
+impl fmt::Display for WordlistMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "made with a different word list (hash {}) than the one in use (hash {})",
+            self.found, self.expected
+        )
+    }
+}
+
+impl std::error::Error for WordlistMismatch {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_are_summed_and_lowercased() {
+        let counts = parse_counts("Crane\t10\ncrane 5\n\nslate 3\n").unwrap();
+        assert_eq!(counts["crane"], 15);
+        assert_eq!(counts["slate"], 3);
+        assert_eq!(
+            parse_counts("crane\nslate 3").unwrap_err(),
+            ParseError {
+                line: 1,
+                content: "crane".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn bad_lines() {
+        let text = "crane 10\nslate\nsalet three\ntares 7\n";
+        let (dictionary, errors) = Dictionary::parse_skipping(text);
+        assert_eq!(dictionary.to_string(), "crane 10\ntares 7\n");
+        assert_eq!(errors.iter().map(|e| e.line).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(Dictionary::parse(text).unwrap_err().content, "slate");
+        let all = Dictionary::parse_all(text).unwrap_err();
+        assert_eq!(all.0, errors);
+        assert!(all.to_string().starts_with("2 bad lines\n  line 2: "));
+    }
+
+    #[test]
+    fn misshapen_words() {
+        // too long, not ASCII, and not lowercase, none of which a game can play
+        for text in ["cranes 10\n", "#wordlist v2\ncranes 10\n"] {
+            let (dictionary, errors) = Dictionary::parse_skipping(&format!("{}tares 7\n", text));
+            assert_eq!(dictionary.to_string().lines().last(), Some("tares 7"));
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].content, "cranes 10");
+        }
+        for text in ["grúas 5\n", "#wordlist v2\ngrúas 5 lang=es\n", "Crane 10\n"] {
+            let errors = Dictionary::parse_all(text).unwrap_err().0;
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].line, text.lines().count());
+        }
+        assert_eq!(parse_line("cranes 10"), None);
+        // unless it's a list of longer words
+        let (dictionary, errors) = Dictionary::parse_skipping_length("cranes 10\ntares 7\n", 6);
+        assert_eq!(dictionary.to_string(), "cranes 10\n");
+        assert_eq!(errors[0].line, 2);
+    }
+
+    #[test]
+    fn word_meta() {
+        let text = "#wordlist v2\n\
+                    crane 10 role=answer tags=bird,common\n\
+                    grupo 5 role=guess lang=es\n\
+                    slate 3\n";
+        let dictionary = Dictionary::parse(text).unwrap();
+        assert_eq!(dictionary.0.len(), 3);
+        assert!(dictionary.meta("crane").has_tag("bird"));
+        assert_eq!(dictionary.meta("grupo").lang, Some(Lang::Spanish));
+        assert_eq!(dictionary.meta("slate"), &WordMeta::default());
+        assert_eq!(dictionary.to_string(), text);
+        let answers = dictionary.marked_answers().unwrap();
+        assert_eq!(
+            answers.to_string(),
+            "#wordlist v2\ncrane 10 role=answer tags=bird,common\n"
+        );
+        let common = dictionary.only(|_, meta| meta.has_tag("common"));
+        assert_eq!(common.0.keys().collect::<Vec<_>>(), ["crane"]);
+
+        // plain lists stay plain, and neither takes the other's lines
+        let plain = Dictionary::parse("crane 10\nslate 3\n").unwrap();
+        assert!(plain.1.is_empty() && plain.marked_answers().is_none());
+        assert!(Dictionary::parse("crane 10 role=answer\n").is_err());
+        assert!(Dictionary::parse("#wordlist v2\ncrane 10 colour=red\n").is_err());
+        assert!(Dictionary::parse("#wordlist v3\ncrane 10\n").is_err());
+    }
+
+    #[test]
+    fn build_filters() {
+        let counts = parse_counts("crane 10\nslate 3\nxylyl 1\n").unwrap();
+        let options = BuildOptions {
+            min_count: 2,
+            missing_count: 2,
+            exclude: vec!["slate".to_string()],
+            ..Default::default()
+        };
+        let dictionary = build(
+            "crane\nSLATE\nxylyl\nsalet\ncranes\ncan't\n",
+            &counts,
+            &options,
+        );
+        assert_eq!(dictionary.to_string(), "crane 10\nsalet 2\n");
+    }
+
+    #[test]
+    fn merge_sums_counts() {
+        let a = Dictionary::parse("crane 10\nslate 3\n").unwrap();
+        let b = Dictionary::parse("crane 5\nsalet 1\n").unwrap();
+        assert_eq!(a.merge(&b).to_string(), "crane 15\nsalet 1\nslate 3\n");
+    }
+
+    #[test]
+    fn diff() {
+        let a = Dictionary::parse("crane 10\nslate 3\ntares 7\n").unwrap();
+        let b = Dictionary::parse("crane 12\nsalet 1\ntares 7\n").unwrap();
+        let diff = a.diff(&b);
+        assert_eq!(diff.to_string(), "+ salet 1\n- slate 3\n~ crane 10 -> 12\n");
+        assert!(a.diff(&a).is_empty());
+    }
+
+    #[test]
+    fn languages() {
+        assert!(Dictionary::for_lang(Lang::English).is_some());
+        for (lang, enabled) in [
+            (Lang::Spanish, cfg!(feature = "lang-es")),
+            (Lang::German, cfg!(feature = "lang-de")),
+            (Lang::French, cfg!(feature = "lang-fr")),
+        ] {
+            let dictionary = Dictionary::for_lang(lang);
+            assert_eq!(dictionary.is_some(), enabled, "{:?}", lang);
+            // only five letter words can be played
+            for word in dictionary.iter().flat_map(|d| d.0.keys()) {
+                assert!(crate::Word::new(word).is_some(), "{}", word);
+            }
+        }
+    }
+
+    #[test]
+    fn plausible_answers() {
+        let dictionary =
+            Dictionary::parse("crane 50\ncanes 40\nglass 30\nbaked 20\ngreed 10\nxylyl 1\n")
+                .unwrap();
+        let all = PlausibleOptions {
+            share: 1.0,
+            ..Default::default()
+        };
+        let plausible = dictionary.plausible_answers(&all);
+        assert_eq!(
+            plausible.to_string(),
+            "crane 50\nglass 30\ngreed 10\nxylyl 1\n"
+        );
+        let common = PlausibleOptions { share: 0.5, ..all };
+        assert_eq!(
+            dictionary.plausible_answers(&common).to_string(),
+            "crane 50\nglass 30\n"
+        );
+        assert_eq!(
+            dictionary
+                .most_common(2)
+                .with_prior(&crate::algorithms::Uniform)
+                .to_string(),
+            "canes 1\ncrane 1\n"
+        );
+        assert_eq!(
+            dictionary.most_common(3).to_string(),
+            "canes 40\ncrane 50\nglass 30\n"
+        );
+
+        // most of the real answers make it from the bundled list
+        let bundled = Dictionary::bundled().plausible_answers(&Default::default());
+        let answers: Vec<_> = crate::algorithms::answers().collect();
+        let found = answers
+            .iter()
+            .filter(|&&a| bundled.0.contains_key(a))
+            .count();
+        assert!(
+            found * 10 > answers.len() * 8,
+            "{} of {}",
+            found,
+            answers.len()
+        );
+    }
+
+    #[test]
+    fn without_forms() {
+        let dictionary = Dictionary::parse("canes 1\nbasis 1\nbaked 1\nbleed 1\n").unwrap();
+        assert_eq!(dictionary.without_forms(false, false), dictionary);
+        assert_eq!(
+            dictionary.without_forms(true, false).to_string(),
+            "baked 1\nbasis 1\nbleed 1\n"
+        );
+        assert_eq!(
+            dictionary.without_forms(true, true).to_string(),
+            "basis 1\nbleed 1\n"
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        let text = "aahed 49650\naalii 3129\n";
+        assert_eq!(Dictionary::parse(text).unwrap().to_string(), text);
+    }
+
+    #[test]
+    fn wordlist_hash() {
+        let dictionary = Dictionary::parse("crane 10\nslate 3\n").unwrap();
+        let hash = WordlistHash::of(&dictionary, &["crane"]);
+        assert_eq!(hash, WordlistHash::of(&dictionary, &["crane"]));
+        assert_ne!(hash, WordlistHash::of(&dictionary, &["slate"]));
+        let recounted = Dictionary::parse("crane 11\nslate 3\n").unwrap();
+        let other = WordlistHash::of(&recounted, &["crane"]);
+        assert_ne!(hash, other);
+    }
+}
@@ -0,0 +1,185 @@
+use crate::game::{Game, Status};
+use crate::{Correctness, Guesser, History, PlayError, Wordle};
+
+// How the two players take their guesses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Turns {
+    // one guess each in turn, and whoever gets it first wins
+    Alternating,
+    // both guess every round; solving it in the same round is a draw
+    Simultaneous,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    // the index of the player who won
+    Won(usize),
+    // both solved it in the same round, or neither solved it at all
+    Draw,
+}
+
+// Two players racing to find the same answer, each with their own game.
+pub struct Duel<'w> {
+    games: [Game<'w>; 2],
+    turns: Turns,
+    // who still has to guess before the round is over
+    pending: [bool; 2],
+    outcome: Option<Outcome>,
+}
+
+impl<'w> Duel<'w> {
+    pub fn new(wordle: &'w Wordle, answer: &str, turns: Turns) -> Result<Self, PlayError> {
+        Ok(Self {
+            games: [wordle.start(answer)?, wordle.start(answer)?],
+            turns,
+            pending: match turns {
+                Turns::Alternating => [true, false],
+                Turns::Simultaneous => [true, true],
+            },
+            outcome: None,
+        })
+    }
+
+    // give one player a different number of guesses than the usual six, say to even out a
+    // human against a bot
+    pub fn with_max_guesses(mut self, player: usize, max_guesses: usize) -> Self {
+        let [a, b] = self.games;
+        self.games = match player {
+            0 => [a.with_max_guesses(max_guesses), b],
+            _ => [a, b.with_max_guesses(max_guesses)],
+        };
+        self
+    }
+
+    // the players who have to guess before the duel can go on
+    pub fn to_move(&self) -> Vec<usize> {
+        if self.outcome.is_some() {
+            return Vec::new();
+        }
+        (0..2).filter(|&p| self.pending[p]).collect()
+    }
+
+    pub fn guess(&mut self, player: usize, word: &str) -> Result<[Correctness; 5], PlayError> {
+        if self.outcome.is_some() {
+            return Err(PlayError::GameOver);
+        }
+        if !self.pending[player] {
+            return Err(PlayError::OutOfTurn);
+        }
+        let mask = self.games[player].guess(word)?;
+        self.pending[player] = false;
+        let playing = |game: &Game| game.status() == Status::InProgress;
+        match self.turns {
+            Turns::Alternating => {
+                let other = 1 - player;
+                if self.games[player].status() == Status::Won {
+                    self.outcome = Some(Outcome::Won(player));
+                } else if playing(&self.games[other]) {
+                    self.pending[other] = true;
+                } else if playing(&self.games[player]) {
+                    self.pending[player] = true;
+                } else {
+                    self.outcome = Some(Outcome::Draw);
+                }
+            }
+            Turns::Simultaneous => {
+                if self.pending.contains(&true) {
+                    return Ok(mask);
+                }
+                let winners: Vec<_> = (0..2)
+                    .filter(|&p| self.games[p].status() == Status::Won)
+                    .collect();
+                match winners[..] {
+                    [winner] => self.outcome = Some(Outcome::Won(winner)),
+                    [_, _] => self.outcome = Some(Outcome::Draw),
+                    _ if !self.games.iter().any(playing) => self.outcome = Some(Outcome::Draw),
+                    _ => self.pending = [playing(&self.games[0]), playing(&self.games[1])],
+                }
+            }
+        }
+        Ok(mask)
+    }
+
+    pub fn outcome(&self) -> Option<Outcome> {
+        self.outcome
+    }
+
+    pub fn history(&self, player: usize) -> &History {
+        self.games[player].history()
+    }
+
+    // let two guessers fight it out
+    pub fn play(&mut self, players: [&mut dyn Guesser; 2]) -> Result<Outcome, PlayError> {
+        loop {
+            if let Some(outcome) = self.outcome {
+                return Ok(outcome);
+            }
+            for player in self.to_move() {
+                let guess = players[player].guess(self.history(player));
+                self.guess(player, &guess)?;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a guesser that guesses the answer on the given turn
+    fn solves_on(turn: usize) -> impl Guesser {
+        struct G(usize);
+        impl Guesser for G {
+            fn guess(&mut self, history: &History) -> String {
+                if history.len() + 1 == self.0 {
+                    "right".to_string()
+                } else {
+                    "wrong".to_string()
+                }
+            }
+        }
+        G(turn)
+    }
+
+    #[test]
+    fn alternating_goes_to_whoever_is_first() {
+        let w = Wordle::new();
+        let mut duel = Duel::new(&w, "right", Turns::Alternating).unwrap();
+        let outcome = duel.play([&mut solves_on(3), &mut solves_on(3)]);
+        assert_eq!(outcome, Ok(Outcome::Won(0)));
+        assert_eq!(duel.history(0).len(), 3);
+        assert_eq!(duel.history(1).len(), 2);
+    }
+
+    #[test]
+    fn simultaneous_ties_are_draws() {
+        let w = Wordle::new();
+        let mut duel = Duel::new(&w, "right", Turns::Simultaneous).unwrap();
+        let outcome = duel.play([&mut solves_on(3), &mut solves_on(3)]);
+        assert_eq!(outcome, Ok(Outcome::Draw));
+
+        let mut duel = Duel::new(&w, "right", Turns::Simultaneous).unwrap();
+        let outcome = duel.play([&mut solves_on(4), &mut solves_on(2)]);
+        assert_eq!(outcome, Ok(Outcome::Won(1)));
+    }
+
+    #[test]
+    fn extra_guesses() {
+        let w = Wordle::new();
+        let mut duel = Duel::new(&w, "right", Turns::Simultaneous)
+            .unwrap()
+            .with_max_guesses(1, 8);
+        let outcome = duel.play([&mut solves_on(99), &mut solves_on(8)]);
+        assert_eq!(outcome, Ok(Outcome::Won(1)));
+    }
+
+    #[test]
+    fn out_of_turn() {
+        let w = Wordle::new();
+        let mut duel = Duel::new(&w, "right", Turns::Alternating).unwrap();
+        assert_eq!(duel.to_move(), [0]);
+        assert_eq!(duel.guess(1, "wrong"), Err(PlayError::OutOfTurn));
+        duel.guess(0, "wrong").unwrap();
+        assert_eq!(duel.to_move(), [1]);
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::algorithms::{answers, dictionary};
+use crate::features::Features;
+use crate::{Correctness, Guess, MaskExt, Word};
+
+// A game you drive one guess at a time, in the reset/step style reinforcement learning libraries
+// expect. Winning is worth 1, running out of guesses costs 1, and every other step is worth 0.
+pub struct WordleEnv {
+    answers: Vec<&'static str>,
+    allowed: HashSet<&'static str>,
+    max_guesses: usize,
+    answer: &'static str,
+    history: Vec<Guess>,
+    done: bool,
+    rng: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Observation {
+    // the mask the last guess got, or None right after a reset
+    pub mask: Option<[Correctness; 5]>,
+    pub features: Features,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvError {
+    // the guess isn't in the dictionary
+    InvalidGuess(String),
+    // the game is over, so reset before stepping again
+    Done,
+}
+
+impl fmt::Display for EnvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvError::InvalidGuess(word) => write!(f, "'{}' is not in the dictionary", word),
+            EnvError::Done => write!(f, "the game is over; reset to start a new one"),
+        }
+    }
+}
+
+impl std::error::Error for EnvError {}
+
+impl WordleEnv {
+    // answers are picked pseudo-randomly from the official list, starting from `seed`
+    pub fn new(seed: u64) -> Self {
+        let answers: Vec<_> = answers().collect();
+        Self {
+            allowed: dictionary().map(|(word, _)| word).collect(),
+            max_guesses: 6,
+            answer: answers[0],
+            answers,
+            history: Vec::new(),
+            done: true,
+            // xorshift gets stuck on zero
+            rng: seed.max(1),
+        }
+    }
+
+    pub fn with_max_guesses(mut self, max_guesses: usize) -> Self {
+        self.max_guesses = max_guesses;
+        self
+    }
+
+    // every word `step` accepts
+    pub fn actions(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.allowed.iter().copied()
+    }
+
+    pub fn history(&self) -> &[Guess] {
+        &self.history
+    }
+
+    // start a new game with a random answer
+    pub fn reset(&mut self) -> Observation {
+        // xorshift64
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        let answer = self.answers[(self.rng % self.answers.len() as u64) as usize];
+        self.reset_with(answer)
+    }
+
+    // start a new game with the given answer
+    pub fn reset_with(&mut self, answer: &'static str) -> Observation {
+        self.answer = answer;
+        self.history.clear();
+        self.done = false;
+        self.observe(None)
+    }
+
+    pub fn step(&mut self, guess: &str) -> Result<(Observation, f64, bool), EnvError> {
+        if self.done {
+            return Err(EnvError::Done);
+        }
+        let word = Word::new(guess)
+            .filter(|_| self.allowed.contains(guess))
+            .ok_or_else(|| EnvError::InvalidGuess(guess.to_string()))?;
+        let mask = Correctness::compute(self.answer, guess);
+        self.history.push(Guess { word, mask });
+        let reward = if mask.is_win() {
+            self.done = true;
+            1.0
+        } else if self.history.len() >= self.max_guesses {
+            self.done = true;
+            -1.0
+        } else {
+            0.0
+        };
+        Ok((self.observe(Some(mask)), reward, self.done))
+    }
+
+    fn observe(&self, mask: Option<[Correctness; 5]>) -> Observation {
+        Observation {
+            mask,
+            features: Features::new(&self.history, &self.answers),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn win() {
+        let mut env = WordleEnv::new(1);
+        let obs = env.reset_with("right");
+        assert_eq!(obs.mask, None);
+        assert_eq!(obs.features.round, 0);
+
+        let (obs, reward, done) = env.step("wrong").unwrap();
+        assert_eq!(obs.mask, Some(mask![W M W W M]));
+        assert_eq!((reward, done), (0.0, false));
+
+        let (_, reward, done) = env.step("right").unwrap();
+        assert_eq!((reward, done), (1.0, true));
+        assert_eq!(env.step("right"), Err(EnvError::Done));
+    }
+
+    #[test]
+    fn loss() {
+        let mut env = WordleEnv::new(1).with_max_guesses(2);
+        env.reset_with("right");
+        assert_eq!(env.step("wrong").unwrap().1, 0.0);
+        assert_eq!(env.step("wrong").unwrap().1, -1.0);
+        assert_eq!(env.step("wrong"), Err(EnvError::Done));
+    }
+
+    #[test]
+    fn invalid_guess() {
+        let mut env = WordleEnv::new(1);
+        env.reset_with("right");
+        assert!(matches!(env.step("zzzzz"), Err(EnvError::InvalidGuess(_))));
+        assert!(env.history().is_empty());
+    }
+
+    #[test]
+    fn reset_picks_answers() {
+        let mut env = WordleEnv::new(42);
+        let mut seen = HashSet::new();
+        for _ in 0..10 {
+            env.reset();
+            seen.insert(env.answer);
+        }
+        assert!(seen.len() > 1);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{Correctness, Guess};
+
+// how many numbers `Features::encode` produces
+pub const ENCODED_LEN: usize = 5 * 26 + 26 + 26 + 5 * 26 + 2;
+
+// Everything a history tells us about the answer, flattened into something that's easy to write
+// out as a table row or feed to a model. Letter sets are bitmasks with bit 0 for 'a'.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Features {
+    // how many guesses have been made
+    pub round: usize,
+    // letters known to be at each position
+    pub greens: [Option<u8>; 5],
+    // letters known to be somewhere in the answer
+    pub present: u32,
+    // letters known not to be anywhere in the answer
+    pub absent: u32,
+    // letters known not to be at each position
+    pub not_here: [u32; 5],
+    // how many of the given candidates are still consistent with the history
+    pub remaining: usize,
+}
+
+impl Features {
+    pub fn new(history: &[Guess], candidates: &[&str]) -> Self {
+        let mut features = Self {
+            round: history.len(),
+            greens: [None; 5],
+            present: 0,
+            absent: 0,
+            not_here: [0; 5],
+            remaining: candidates
+                .iter()
+                .filter(|c| history.iter().all(|g| g.matches(c)))
+                .count(),
+        };
+        for guess in history {
+            let word = guess.word.bytes();
+            for (i, (&letter, &m)) in word.iter().zip(&guess.mask).enumerate() {
+                let bit = 1 << (letter - b'a');
+                match m {
+                    Correctness::Correct => {
+                        features.greens[i] = Some(letter);
+                        features.present |= bit;
+                    }
+                    Correctness::Misplaced => {
+                        features.not_here[i] |= bit;
+                        features.present |= bit;
+                    }
+                    Correctness::Wrong => {
+                        features.not_here[i] |= bit;
+                        // a gray letter can still be in the answer if the same guess got it
+                        // green or yellow somewhere else
+                        let elsewhere = word
+                            .iter()
+                            .zip(&guess.mask)
+                            .any(|(&l, &m)| l == letter && m != Correctness::Wrong);
+                        if !elsewhere {
+                            features.absent |= bit;
+                        }
+                    }
+                }
+            }
+        }
+        features
+    }
+
+    // Flatten into a fixed-length vector for models, in this order: one-hot greens for each
+    // position (5 x 26), present letters (26), absent letters (26), letters ruled out at each
+    // position (5 x 26), the round, and ln(1 + remaining).
+    pub fn encode(&self) -> Vec<f32> {
+        let mut out = Vec::with_capacity(ENCODED_LEN);
+        let bits = |out: &mut Vec<f32>, set: u32| {
+            out.extend((0..26).map(|i| if set & (1 << i) != 0 { 1.0 } else { 0.0 }));
+        };
+        for green in self.greens {
+            bits(&mut out, green.map_or(0, |letter| 1 << (letter - b'a')));
+        }
+        bits(&mut out, self.present);
+        bits(&mut out, self.absent);
+        for set in self.not_here {
+            bits(&mut out, set);
+        }
+        out.push(self.round as f32);
+        out.push((1.0 + self.remaining as f32).ln());
+        out
+    }
+}
+
+// the letters in a letter set, in alphabetical order
+pub fn letters(set: u32) -> String {
+    (0..26)
+        .filter(|i| set & (1 << i) != 0)
+        .map(|i| (b'a' + i as u8) as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_history() {
+        let history = [Guess {
+            word: "sassy".parse().unwrap(),
+            mask: mask![C M W W W],
+        }];
+        let f = Features::new(&history, &["sassy", "scabs", "stabs"]);
+        assert_eq!(f.round, 1);
+        assert_eq!(f.greens, [Some(b's'), None, None, None, None]);
+        assert_eq!(letters(f.present), "as");
+        // 's' is gray twice but green once, so only 'y' is known to be missing
+        assert_eq!(letters(f.absent), "y");
+        assert_eq!(letters(f.not_here[1]), "a");
+        assert_eq!(letters(f.not_here[2]), "s");
+        assert_eq!(f.remaining, 0);
+    }
+
+    #[test]
+    fn encode() {
+        let history = [Guess {
+            word: "baker".parse().unwrap(),
+            mask: mask![W C C C C],
+        }];
+        let encoded = Features::new(&history, &["caker"]).encode();
+        assert_eq!(encoded.len(), ENCODED_LEN);
+        // 'a' is green in the second position
+        assert_eq!(encoded[26], 1.0);
+        // 'b' is absent
+        assert_eq!(encoded[5 * 26 + 26 + 1], 1.0);
+        assert_eq!(encoded[ENCODED_LEN - 2], 1.0);
+        assert_eq!(encoded[ENCODED_LEN - 1], 2f32.ln());
+    }
+
+    #[test]
+    fn counts_remaining() {
+        let history = [Guess {
+            word: "baker".parse().unwrap(),
+            mask: mask![W C C C C],
+        }];
+        let f = Features::new(&history, &["baker", "caker", "daker", "eaten"]);
+        assert_eq!(f.remaining, 2);
+    }
+}
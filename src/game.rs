@@ -0,0 +1,134 @@
+use crate::hard_mode::is_legal_hard_mode;
+use crate::{Correctness, Guess, History, MaskExt, PlayError, Word, Wordle};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    InProgress,
+    Won,
+    Lost,
+}
+
+// One game being played by someone else, a guess at a time, for hosting a Wordle of your own.
+// Guesses are checked against the `Wordle`'s guess policy; start one with `Wordle::start`.
+pub struct Game<'w> {
+    wordle: &'w Wordle,
+    answer: String,
+    history: History,
+    max_guesses: usize,
+}
+
+impl<'w> Game<'w> {
+    pub(crate) fn new(wordle: &'w Wordle, answer: &str) -> Self {
+        Self {
+            wordle,
+            answer: answer.to_string(),
+            history: History::new(),
+            max_guesses: 6,
+        }
+    }
+
+    pub fn with_max_guesses(mut self, max_guesses: usize) -> Self {
+        self.max_guesses = max_guesses;
+        self
+    }
+
+    // play a guess and get back its mask
+    pub fn guess(&mut self, word: &str) -> Result<[Correctness; 5], PlayError> {
+        if self.status() != Status::InProgress {
+            return Err(PlayError::GameOver);
+        }
+        // the answer is always a fine guess, even when it's not one the policy allows
+        let allowed = word == self.answer || self.wordle.allows(word);
+        let illegal = || PlayError::IllegalGuess {
+            round: self.history.len() + 1,
+            guess: word.to_string(),
+        };
+        // a word list can have words of other lengths in it, but they can't be played
+        let guess = Word::new(word).filter(|_| allowed).ok_or_else(illegal)?;
+        if self.wordle.hard_mode && !is_legal_hard_mode(&self.history, word) {
+            return Err(illegal());
+        }
+        let mask = Correctness::compute(&self.answer, word);
+        self.history.push(Guess { word: guess, mask });
+        Ok(mask)
+    }
+
+    pub fn status(&self) -> Status {
+        match self.history.last() {
+            Some(last) if last.mask.is_win() => Status::Won,
+            _ if self.history.len() >= self.max_guesses => Status::Lost,
+            _ => Status::InProgress,
+        }
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    // how many more guesses the player gets
+    pub fn remaining(&self) -> usize {
+        match self.status() {
+            Status::InProgress => self.max_guesses - self.history.len(),
+            _ => 0,
+        }
+    }
+
+    pub fn answer(&self) -> &str {
+        &self.answer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn win() {
+        let w = Wordle::new();
+        let mut game = w.start("right").unwrap();
+        assert_eq!(game.guess("wrong"), Ok(mask![W M W W M]));
+        assert_eq!((game.status(), game.remaining()), (Status::InProgress, 5));
+        assert_eq!(game.guess("right"), Ok(mask![C C C C C]));
+        assert_eq!((game.status(), game.remaining()), (Status::Won, 0));
+        assert_eq!(game.guess("right"), Err(PlayError::GameOver));
+    }
+
+    #[test]
+    fn loss() {
+        let w = Wordle::new();
+        let mut game = w.start("right").unwrap().with_max_guesses(2);
+        game.guess("wrong").unwrap();
+        game.guess("wrong").unwrap();
+        assert_eq!(game.status(), Status::Lost);
+        assert_eq!(game.history().len(), 2);
+    }
+
+    #[test]
+    fn illegal_guesses_are_not_counted() {
+        let w = Wordle::new();
+        let mut game = w.start("right").unwrap();
+        assert!(matches!(
+            game.guess("zzzzz"),
+            Err(PlayError::IllegalGuess { round: 1, .. })
+        ));
+        assert_eq!(game.remaining(), 6);
+        // in the word list, but not something that can be played
+        let w = Wordle::new().with_dictionary(["right", "rights"]);
+        let mut game = w.start("right").unwrap();
+        assert!(game.guess("rights").is_err());
+        assert_eq!(game.remaining(), 6);
+    }
+
+    #[test]
+    fn hard_mode() {
+        let w = Wordle::new().with_hard_mode();
+        let mut game = w.start("right").unwrap();
+        game.guess("wrong").unwrap();
+        // doesn't use the r or the g it found
+        assert!(matches!(
+            game.guess("tares"),
+            Err(PlayError::IllegalGuess { round: 2, .. })
+        ));
+        assert!(game.guess("girth").is_ok());
+    }
+}
@@ -0,0 +1,228 @@
+// Bucketing every guess against every candidate on the GPU, for word lists big enough that doing
+// it on the CPU takes a while. Each invocation of the shader (gpu.wgsl) takes one guess and works
+// through every candidate, just like `partition_counts`. This is experimental: anything that
+// goes wrong, from there being no GPU at all to a failed dispatch, gives `None`, and the callers
+// go back to the CPU.
+
+use std::sync::{mpsc, OnceLock};
+
+use wgpu::util::DeviceExt;
+
+const SHADER: &str = include_str!("gpu.wgsl");
+
+// Below this many guess and candidate pairs, copying everything over and back costs more than it
+// saves.
+pub const MIN_PAIRS: usize = 1 << 22;
+
+// guesses per dispatch, which keeps the buffers well under the smallest storage buffer limit
+const CHUNK: usize = 4096;
+
+pub struct Gpu {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+}
+
+impl Gpu {
+    // the first adapter wgpu can find, if any
+    pub fn new() -> Option<Self> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = pollster::block_on(instance.request_adapter(&Default::default())).ok()?;
+        let (device, queue) =
+            pollster::block_on(adapter.request_device(&Default::default())).ok()?;
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("buckets"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("buckets"),
+            layout: None,
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        if pollster::block_on(device.pop_error_scope()).is_some() {
+            return None;
+        }
+        Some(Self {
+            device,
+            queue,
+            pipeline,
+        })
+    }
+
+    // one for the whole program, set up the first time it's asked for
+    pub fn shared() -> Option<&'static Self> {
+        static SHARED: OnceLock<Option<Gpu>> = OnceLock::new();
+        SHARED.get_or_init(Self::new).as_ref()
+    }
+
+    // `partition_counts` for every guess, in order
+    pub fn partition_counts(
+        &self,
+        guesses: &[&str],
+        candidates: &[(&str, u64)],
+    ) -> Option<Vec<[u64; 243]>> {
+        if candidates.is_empty() {
+            return Some(vec![[0; 243]; guesses.len()]);
+        }
+        let packed: Vec<u32> = candidates
+            .iter()
+            .flat_map(|&(word, count)| [pack(word), count as u32, (count >> 32) as u32, 0])
+            .collect();
+        let candidate_buffer = self.storage(&packed);
+        let mut counts = Vec::with_capacity(guesses.len());
+        for chunk in guesses.chunks(CHUNK) {
+            counts.extend(self.dispatch(chunk, candidates.len(), &candidate_buffer)?);
+        }
+        Some(counts)
+    }
+
+    fn storage(&self, data: &[u32]) -> wgpu::Buffer {
+        self.device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: &bytes(data),
+                usage: wgpu::BufferUsages::STORAGE,
+            })
+    }
+
+    fn dispatch(
+        &self,
+        guesses: &[&str],
+        candidates: usize,
+        candidate_buffer: &wgpu::Buffer,
+    ) -> Option<Vec<[u64; 243]>> {
+        let device = &self.device;
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let packed: Vec<u32> = guesses.iter().map(|guess| pack(guess)).collect();
+        let guess_buffer = self.storage(&packed);
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &bytes(&[guesses.len() as u32, candidates as u32]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        // new buffers start out zeroed
+        let size = (guesses.len() * 243 * 2 * 4) as u64;
+        let buckets = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                entry(0, &guess_buffer),
+                entry(1, candidate_buffer),
+                entry(2, &buckets),
+                entry(3, &params),
+            ],
+        });
+        let mut encoder = device.create_command_encoder(&Default::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&Default::default());
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(guesses.len().div_ceil(64) as u32, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&buckets, 0, &readback, 0, size);
+        self.queue.submit([encoder.finish()]);
+        if pollster::block_on(device.pop_error_scope()).is_some() {
+            return None;
+        }
+
+        let (send, receive) = mpsc::channel();
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = send.send(result);
+        });
+        device.poll(wgpu::PollType::Wait).ok()?;
+        receive.recv().ok()?.ok()?;
+        let data = slice.get_mapped_range();
+        let words: Vec<u32> = data
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        let counts = words
+            .chunks_exact(243 * 2)
+            .map(|guess| {
+                std::array::from_fn(|pattern| {
+                    guess[2 * pattern] as u64 | (guess[2 * pattern + 1] as u64) << 32
+                })
+            })
+            .collect();
+        Some(counts)
+    }
+}
+
+// `partition_counts` for every guess, on the GPU if there's one and the job is big enough to be
+// worth it
+pub fn partition_counts(guesses: &[&str], candidates: &[(&str, u64)]) -> Option<Vec<[u64; 243]>> {
+    if guesses.len().saturating_mul(candidates.len()) < MIN_PAIRS {
+        return None;
+    }
+    Gpu::shared()?.partition_counts(guesses, candidates)
+}
+
+// five bits a letter, first letter lowest, as the shader reads them
+fn pack(word: &str) -> u32 {
+    word.bytes()
+        .enumerate()
+        .fold(0, |acc, (i, b)| acc | ((b - b'a') as u32) << (5 * i))
+}
+
+fn bytes(data: &[u32]) -> Vec<u8> {
+    data.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+fn entry(binding: u32, buffer: &wgpu::Buffer) -> wgpu::BindGroupEntry<'_> {
+    wgpu::BindGroupEntry {
+        binding,
+        resource: buffer.as_entire_binding(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::partition_counts;
+
+    #[test]
+    fn shader_is_valid() {
+        let module = wgpu::naga::front::wgsl::parse_str(SHADER).unwrap();
+        wgpu::naga::valid::Validator::new(Default::default(), Default::default())
+            .validate(&module)
+            .unwrap();
+    }
+
+    #[test]
+    fn agrees_with_the_cpu() {
+        // there may well be no GPU to test on, in which case there's nothing to compare
+        let Some(gpu) = Gpu::shared() else {
+            return;
+        };
+        let guesses = ["tares", "fjord", "right", "aabbb", "eerie"];
+        let candidates = [
+            ("right", 3),
+            ("sight", 1 << 33),
+            ("wrong", 7),
+            ("ccaac", 1),
+            ("eerie", u32::MAX as u64),
+            ("ridge", u32::MAX as u64),
+        ];
+        let counts = gpu.partition_counts(&guesses, &candidates).unwrap();
+        for (guess, counts) in guesses.iter().zip(counts) {
+            assert_eq!(counts, partition_counts(guess, candidates.iter().copied()));
+        }
+    }
+}
@@ -0,0 +1,66 @@
+// The weight of the candidates in each of a guess's buckets, for one guess per invocation (see
+// gpu.rs). Words come packed five bits a letter, first letter lowest. WGSL has no 64 bit
+// integers, so weights and totals are split into low and high halves.
+
+struct Params {
+    guesses: u32,
+    candidates: u32,
+}
+
+@group(0) @binding(0) var<storage, read> guesses: array<u32>;
+// the word, then the weight's low and high halves (and one unused, to keep them 16 bytes)
+@group(0) @binding(1) var<storage, read> candidates: array<vec4<u32>>;
+// 243 buckets per guess, each a low half then a high half
+@group(0) @binding(2) var<storage, read_write> buckets: array<u32>;
+@group(0) @binding(3) var<uniform> params: Params;
+
+fn letter(word: u32, i: u32) -> u32 {
+    return (word >> (5u * i)) & 31u;
+}
+
+// same as Correctness::compute then pattern_index: 0 is correct, 1 misplaced and 2 wrong
+fn pattern(answer: u32, guess: u32) -> u32 {
+    var marks = array<u32, 5>(2u, 2u, 2u, 2u, 2u);
+    var used = array<bool, 5>(false, false, false, false, false);
+    for (var i = 0u; i < 5u; i++) {
+        if letter(answer, i) == letter(guess, i) {
+            marks[i] = 0u;
+            used[i] = true;
+        }
+    }
+    for (var i = 0u; i < 5u; i++) {
+        if marks[i] == 0u {
+            continue;
+        }
+        let g = letter(guess, i);
+        for (var j = 0u; j < 5u; j++) {
+            if !used[j] && letter(answer, j) == g {
+                used[j] = true;
+                marks[i] = 1u;
+                break;
+            }
+        }
+    }
+    var index = 0u;
+    for (var i = 0u; i < 5u; i++) {
+        index = index * 3u + marks[i];
+    }
+    return index;
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let g = id.x;
+    if g >= params.guesses {
+        return;
+    }
+    let guess = guesses[g];
+    for (var c = 0u; c < params.candidates; c++) {
+        let candidate = candidates[c];
+        let slot = (g * 243u + pattern(candidate.x, guess)) * 2u;
+        let low = buckets[slot] + candidate.y;
+        let carry = select(0u, 1u, low < candidate.y);
+        buckets[slot] = low;
+        buckets[slot + 1u] = buckets[slot + 1u] + candidate.z + carry;
+    }
+}
@@ -0,0 +1,197 @@
+// The server's operations over gRPC (see proto/wordle.proto), for services that would rather
+// talk to the solver through generated clients than JSON.
+
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::task;
+use tonic::{Request, Response, Status};
+
+use crate::dict::Dictionary;
+use crate::session::{parse_guess, MAX_HISTORY};
+use crate::session::{Algorithm, SessionError, SessionOptions, SessionStore};
+use crate::suggest::{suggest_from_history, SuggestOptions};
+
+pub mod proto {
+    tonic::include_proto!("wordle");
+}
+
+use proto::solver_server::{Solver, SolverServer};
+
+pub struct GrpcSolver {
+    dictionary: &'static Dictionary,
+    sessions: Mutex<SessionStore>,
+}
+
+impl GrpcSolver {
+    pub fn new(session_timeout: Duration) -> Self {
+        Self {
+            dictionary: Dictionary::shared(),
+            sessions: Mutex::new(SessionStore::new(session_timeout)),
+        }
+    }
+}
+
+// answer requests on `addr` until the runtime shuts down
+pub async fn serve(addr: SocketAddr, solver: GrpcSolver) -> Result<(), tonic::transport::Error> {
+    crate::Wordle::warm_up();
+    tonic::transport::Server::builder()
+        .add_service(SolverServer::new(solver))
+        .serve(addr)
+        .await
+}
+
+#[tonic::async_trait]
+impl Solver for GrpcSolver {
+    async fn suggest(
+        &self,
+        request: Request<proto::SuggestRequest>,
+    ) -> Result<Response<proto::Suggestion>, Status> {
+        let request = request.into_inner();
+        if request.history.len() > MAX_HISTORY {
+            return Err(Status::invalid_argument(format!(
+                "at most {} guesses",
+                MAX_HISTORY
+            )));
+        }
+        let history = request
+            .history
+            .into_iter()
+            .map(|guess| parse_guess(guess.word, &guess.mask))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Status::invalid_argument)?;
+        let candidates_only = request.candidates_only;
+        // scoring takes a while, and would hold up everything else on this thread
+        let dictionary = self.dictionary;
+        let suggestion = task::spawn_blocking(move || {
+            let options = SuggestOptions {
+                candidates_only,
+                ..Default::default()
+            };
+            suggest_from_history(dictionary, &history, &options)
+        })
+        .await
+        .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(proto::Suggestion {
+            guess: suggestion.guess.unwrap_or_default(),
+            remaining: suggestion.remaining as u64,
+            bits: suggestion.bits,
+            p_answer: suggestion.p_answer,
+            p_solved: suggestion.p_solved,
+        }))
+    }
+
+    async fn new_game(
+        &self,
+        request: Request<proto::NewGameRequest>,
+    ) -> Result<Response<proto::NewGameResponse>, Status> {
+        let name = request.into_inner().algorithm;
+        let algorithm = Algorithm::parse(&name)
+            .ok_or_else(|| Status::invalid_argument(format!("unknown algorithm '{}'", name)))?;
+        let id = self
+            .sessions
+            .lock()
+            .unwrap()
+            .create(SessionOptions::new(algorithm))
+            .map_err(|e| Status::resource_exhausted(e.to_string()))?;
+        Ok(Response::new(proto::NewGameResponse { id }))
+    }
+
+    async fn submit_feedback(
+        &self,
+        request: Request<proto::Feedback>,
+    ) -> Result<Response<proto::Suggestion>, Status> {
+        let request = request.into_inner();
+        let guess = request
+            .guess
+            .ok_or_else(|| Status::invalid_argument("missing guess"))?;
+        let guess = parse_guess(guess.word, &guess.mask).map_err(Status::invalid_argument)?;
+        let not_found = |e: SessionError| Status::not_found(e.to_string());
+
+        // the guesser is taken out of the store to work out the next guess, so other games
+        // aren't held up by this one
+        let (mut guesser, history) = {
+            let mut sessions = self.sessions.lock().unwrap();
+            sessions.expire();
+            if let Some(session) = sessions.get(request.id) {
+                if session.history.len() >= MAX_HISTORY {
+                    return Err(Status::invalid_argument(format!(
+                        "at most {} guesses",
+                        MAX_HISTORY
+                    )));
+                }
+            }
+            sessions.record(request.id, guess).map_err(not_found)?;
+            sessions.check_out(request.id).map_err(not_found)?
+        };
+        let dictionary = self.dictionary;
+        let (guesser, next, remaining) = task::spawn_blocking(move || {
+            let next = guesser.guess(&history);
+            let remaining = dictionary
+                .0
+                .keys()
+                .filter(|word| history.iter().all(|guess| guess.matches(word)))
+                .count();
+            (guesser, next, remaining)
+        })
+        .await
+        .map_err(|e| Status::internal(e.to_string()))?;
+        self.sessions.lock().unwrap().check_in(request.id, guesser);
+        Ok(Response::new(proto::Suggestion {
+            guess: next,
+            remaining: remaining as u64,
+            bits: 0.0,
+            p_answer: 0.0,
+            p_solved: 0.0,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guess(word: &str, mask: &str) -> proto::Guess {
+        proto::Guess {
+            word: word.to_string(),
+            mask: mask.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn suggest() {
+        let solver = GrpcSolver::new(Duration::from_secs(60));
+        let request = proto::SuggestRequest {
+            history: Vec::new(),
+            candidates_only: false,
+        };
+        let suggestion = solver.suggest(Request::new(request)).await.unwrap();
+        assert_eq!(suggestion.get_ref().guess, "tares");
+
+        let request = proto::SuggestRequest {
+            history: vec![guess("tares", "BYBBX")],
+            candidates_only: false,
+        };
+        let status = solver.suggest(Request::new(request)).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn games() {
+        let solver = GrpcSolver::new(Duration::from_secs(60));
+        let request = proto::NewGameRequest {
+            algorithm: "naive".to_string(),
+        };
+        let id = solver.new_game(Request::new(request)).await.unwrap();
+        let feedback = proto::Feedback {
+            id: id.get_ref().id + 1,
+            guess: Some(guess("tares", "BBBBB")),
+        };
+        let status = solver
+            .submit_feedback(Request::new(feedback))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
+}
@@ -0,0 +1,89 @@
+// Hard mode's rule, in the one place everything that enforces it looks: the game host when it
+// takes a guess, the algorithms when they narrow down what they'll guess, and the prompt people
+// type their guesses into.
+
+use crate::{Correctness, Guess};
+
+// Whether hard mode lets `guess` be played after `history`: every green has to stay where it is,
+// and every letter found so far has to be used (as many times as it was found in one guess).
+// Yellows may stay where they were, like in the original game. Anything but five lowercase
+// letters is never legal.
+pub fn is_legal_hard_mode(history: &[Guess], guess: &str) -> bool {
+    let guess = guess.as_bytes();
+    if guess.len() != 5 || !guess.iter().all(u8::is_ascii_lowercase) {
+        return false;
+    }
+    history.iter().all(|past| {
+        let mut found = [0u8; 26];
+        for ((&c, &letter), &g) in past.mask.iter().zip(&past.word.bytes()).zip(guess) {
+            if c == Correctness::Correct && letter != g {
+                return false;
+            }
+            if c != Correctness::Wrong {
+                found[(letter - b'a') as usize] += 1;
+            }
+        }
+        for &g in guess {
+            let n = &mut found[(g - b'a') as usize];
+            *n = n.saturating_sub(1);
+        }
+        found.iter().all(|&n| n == 0)
+    })
+}
+
+// the guesses from `guesses` that hard mode allows after `history`, in the same order
+pub fn legal_guesses<'h, I>(history: &'h [Guess], guesses: I) -> LegalGuesses<'h, I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    LegalGuesses {
+        history,
+        guesses: guesses.into_iter(),
+    }
+}
+
+pub struct LegalGuesses<'h, I> {
+    history: &'h [Guess],
+    guesses: I,
+}
+
+impl<I> Iterator for LegalGuesses<'_, I>
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let history = self.history;
+        self.guesses
+            .find(|guess| is_legal_hard_mode(history, guess.as_ref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::History;
+
+    #[test]
+    fn legality() {
+        let history = History::parse("tares=WMWWC").unwrap();
+        assert!(is_legal_hard_mode(&history, "amiss"));
+        assert!(is_legal_hard_mode(&history, "basis"));
+        // the s has to stay put, and the a has to be used
+        assert!(!is_legal_hard_mode(&history, "salsa"));
+        assert!(!is_legal_hard_mode(&history, "boots"));
+        // both e's have to be used
+        let history = History::parse("eerie=MMWWW").unwrap();
+        assert!(is_legal_hard_mode(&history, "theme"));
+        assert!(!is_legal_hard_mode(&history, "ethos"));
+        assert!(is_legal_hard_mode(&History::new(), "zzzzz"));
+        assert!(!is_legal_hard_mode(&History::new(), "Theme"));
+
+        let words = ["theme", "ethos", "eerie", "there"];
+        let legal: Vec<_> = legal_guesses(&history, words).collect();
+        assert_eq!(legal, ["theme", "eerie", "there"]);
+    }
+}
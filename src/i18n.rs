@@ -0,0 +1,160 @@
+// The words the interactive commands use, looked up by key so they can be shown in other
+// languages. Catalogs are in a small part of Fluent's syntax, one message per line with named
+// placeholders:
+//
+//   solved-timed = solved in { $guesses } guesses and { $seconds }s
+//
+// Lines starting with # are comments. A language that's missing a message falls back to
+// English, so a translation can be added a bit at a time.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+const ENGLISH: &str = include_str!("../locales/en.ftl");
+const SPANISH: &str = include_str!("../locales/es.ftl");
+const GERMAN: &str = include_str!("../locales/de.ftl");
+const FRENCH: &str = include_str!("../locales/fr.ftl");
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Lang {
+    #[default]
+    English,
+    Spanish,
+    German,
+    French,
+}
+
+impl FromStr for Lang {
+    type Err = String;
+
+    // a language code, with or without a region and encoding, like "es" or "es_MX.UTF-8"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.split(['_', '-', '.']).next().unwrap_or_default();
+        match code.to_lowercase().as_str() {
+            "en" | "c" | "posix" => Ok(Self::English),
+            "es" => Ok(Self::Spanish),
+            "de" => Ok(Self::German),
+            "fr" => Ok(Self::French),
+            _ => Err(format!("no messages for the language '{}'", s)),
+        }
+    }
+}
+
+impl Lang {
+    // going by the locale in the environment, and English if there's none or it isn't known
+    pub fn detect() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default()
+    }
+
+    // the two letter code, as in the lang-* features
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::Spanish => "es",
+            Self::German => "de",
+            Self::French => "fr",
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Self::English => ENGLISH,
+            Self::Spanish => SPANISH,
+            Self::German => GERMAN,
+            Self::French => FRENCH,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Catalog {
+    messages: HashMap<String, String>,
+    english: HashMap<String, String>,
+}
+
+impl Catalog {
+    pub fn new(lang: Lang) -> Self {
+        let english = parse(ENGLISH).expect("the English catalog parses");
+        let messages = parse(lang.source()).expect("the bundled catalogs parse");
+        Self { messages, english }
+    }
+
+    // The message for `key`, with each { $name } filled in from `args`. A key no language has
+    // comes back as itself, which is easier to spot and fix than nothing at all.
+    pub fn get(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let Some(message) = self.messages.get(key).or_else(|| self.english.get(key)) else {
+            return key.to_string();
+        };
+        let mut out = message.clone();
+        for (name, value) in args {
+            out = out.replace(&format!("{{ ${} }}", name), &value.to_string());
+        }
+        out
+    }
+}
+
+// the messages in a catalog, or the line that isn't one
+fn parse(text: &str) -> Result<HashMap<String, String>, usize> {
+    let mut messages = HashMap::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line.split_once('=').ok_or(i + 1)?;
+        messages.insert(key.trim().to_string(), value.trim().to_string());
+    }
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        let english = Catalog::new(Lang::English);
+        assert_eq!(
+            english.get("solved", &[("guesses", &3)]),
+            "solved in 3 guesses"
+        );
+        let spanish = Catalog::new(Lang::Spanish);
+        assert_eq!(
+            spanish.get("duel-wins", &[("player", &"naive (1)")]),
+            "¡gana naive (1)!"
+        );
+        assert_eq!(spanish.get("nonsense", &[]), "nonsense");
+        assert_eq!("es_MX.UTF-8".parse(), Ok(Lang::Spanish));
+        assert!("tlh".parse::<Lang>().is_err());
+    }
+
+    #[test]
+    fn complete() {
+        // every message has a translation, with the same placeholders
+        let english = parse(ENGLISH).unwrap();
+        let placeholders = |message: &str| {
+            let mut names: Vec<_> = message
+                .split("{ $")
+                .skip(1)
+                .filter_map(|rest| rest.split_once(" }").map(|(name, _)| name.to_string()))
+                .collect();
+            names.sort();
+            names
+        };
+        for lang in [Lang::Spanish, Lang::German, Lang::French] {
+            let translation = parse(lang.source()).unwrap();
+            for (key, message) in &english {
+                let translated = translation
+                    .get(key)
+                    .unwrap_or_else(|| panic!("no '{}' in {:?}", key, lang));
+                assert_eq!(placeholders(message), placeholders(translated), "{}", key);
+            }
+        }
+    }
+}
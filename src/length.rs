@@ -0,0 +1,143 @@
+// Suggestions for words that aren't five letters long. The core is generic over the length (see
+// `Word<N>`), but the rest of the crate plays five letters, so this picks the length at runtime
+// and hands over to `suggest` with it. There are no bundled lists for other lengths; `dict build
+// --length` makes one out of a word list and a corpus.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::core::MaskExt;
+use crate::dict::Dictionary;
+use crate::suggest::{suggest_n, SuggestOptions, Suggestion};
+use crate::{Correctness, Guess, Word};
+
+// the lengths there's a suggestion for
+pub const LENGTHS: RangeInclusive<usize> = 4..=8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LengthError {
+    Unsupported(usize),
+    // a word of the dictionary or the answers that isn't the length asked for
+    Word(String, usize),
+    // a guess and its mask that aren't
+    Guess(String, String, usize),
+}
+
+impl fmt::Display for LengthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported(length) => write!(
+                f,
+                "can't suggest {} letter words, only {} to {}",
+                length,
+                LENGTHS.start(),
+                LENGTHS.end()
+            ),
+            Self::Word(word, length) => write!(f, "'{}' isn't {} letters long", word, length),
+            Self::Guess(word, mask, length) => write!(
+                f,
+                "'{} {}' isn't a {} letter guess and its mask in B/Y/G",
+                word, mask, length
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LengthError {}
+
+// `suggest_from_history` for a game of `length` letter words. The history is each guess and its
+// mask in B/Y/G, and every word of the dictionary (and the answers) has to be `length` letters.
+pub fn suggest(
+    length: usize,
+    dictionary: &Dictionary,
+    history: &[(&str, &str)],
+    options: &SuggestOptions,
+) -> Result<Suggestion, LengthError> {
+    match length {
+        4 => suggest_with::<4>(dictionary, history, options),
+        5 => suggest_with::<5>(dictionary, history, options),
+        6 => suggest_with::<6>(dictionary, history, options),
+        7 => suggest_with::<7>(dictionary, history, options),
+        8 => suggest_with::<8>(dictionary, history, options),
+        _ => Err(LengthError::Unsupported(length)),
+    }
+}
+
+fn suggest_with<const N: usize>(
+    dictionary: &Dictionary,
+    history: &[(&str, &str)],
+    options: &SuggestOptions,
+) -> Result<Suggestion, LengthError> {
+    let words = dictionary
+        .0
+        .keys()
+        .chain(options.answers.iter().flat_map(|a| a.0.keys()));
+    if let Some(word) = words.into_iter().find(|word| word.len() != N) {
+        return Err(LengthError::Word(word.clone(), N));
+    }
+    let history = history
+        .iter()
+        .map(|&(word, mask)| {
+            let err = || LengthError::Guess(word.to_string(), mask.to_string(), N);
+            Ok(Guess {
+                word: Word::<N>::new_n(word).ok_or_else(err)?,
+                mask: <[Correctness; N]>::from_compact_str(mask).ok_or_else(err)?,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(suggest_n(dictionary, &history, options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str, length: usize) -> Dictionary {
+        let (dictionary, errors) = Dictionary::parse_skipping_length(text, length);
+        assert_eq!(errors, []);
+        dictionary
+    }
+
+    #[test]
+    fn six_letters() {
+        let dictionary = parse("banker 3\ncanker 1\ndanker 1\nhanker 1\neatery 1\n", 6);
+        let options = SuggestOptions::default();
+        let suggestion = suggest(6, &dictionary, &[("eatery", "YGBBYB")], &options).unwrap();
+        assert_eq!(suggestion.remaining, 4);
+        assert!(suggestion.bits > 0.0);
+        let suggestion = suggest(
+            6,
+            &dictionary,
+            &[("eatery", "YGBBYB"), ("banker", "BGGGGG")],
+            &options,
+        )
+        .unwrap();
+        assert_eq!(suggestion.remaining, 3);
+
+        assert_eq!(
+            suggest(6, &dictionary, &[("eaten", "BYBYB")], &options),
+            Err(LengthError::Guess("eaten".into(), "BYBYB".into(), 6))
+        );
+        assert_eq!(
+            suggest(5, &dictionary, &[], &options),
+            Err(LengthError::Word("banker".into(), 5))
+        );
+        assert_eq!(
+            suggest(9, &dictionary, &[], &options),
+            Err(LengthError::Unsupported(9))
+        );
+    }
+
+    #[test]
+    fn four_letters() {
+        let dictionary = parse("bake 1\ncake 1\nlake 1\nlace 1\n", 4);
+        let options = SuggestOptions {
+            candidates_only: true,
+            ..Default::default()
+        };
+        let suggestion = suggest(4, &dictionary, &[("bake", "BGGG")], &options).unwrap();
+        assert_eq!(suggestion.remaining, 2);
+        let suggestion = suggest(4, &dictionary, &[("lace", "GGBG")], &options).unwrap();
+        assert_eq!(suggestion.guess.as_deref(), Some("lake"));
+    }
+}
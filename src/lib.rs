@@ -1,239 +1,274 @@
 use std::collections::HashSet;
+use std::fmt;
+
+#[cfg(test)]
+macro_rules! guesser {
+    (|$history:ident| $impl:block) => {{
+        struct G;
+        impl $crate::Guesser for G {
+            fn guess(&mut self, $history: &$crate::History) -> String {
+                $impl
+            }
+        }
+        G
+    }};
+}
+
+#[cfg(test)]
+macro_rules! mask {
+    (C) => {$crate::Correctness::Correct};
+    (M) => {$crate::Correctness::Misplaced};
+    (W) => {$crate::Correctness::Wrong};
+    ($($c:tt)+) => {[
+        $(mask!($c)),+
+    ]}
+}
 
 pub mod algorithms;
+#[cfg(all(test, feature = "alloc-audit"))]
+mod alloc_audit;
+pub mod analysis;
+pub mod answers;
+pub mod artifact;
+pub mod blocklist;
+pub mod cancel;
+#[cfg(feature = "cli")]
+pub mod config;
+pub mod core;
+pub mod daily;
+pub mod dashboard;
+pub mod dict;
+pub mod duel;
+pub mod env;
+pub mod features;
+pub mod game;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod hard_mode;
+pub mod i18n;
+pub mod length;
+pub mod noise;
+pub mod openers;
+pub mod platform;
+pub mod query;
+#[cfg(feature = "serde")]
+pub mod results;
+pub mod selfcheck;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod session;
+#[cfg(feature = "serde")]
+pub mod simulate;
+pub mod stats;
+pub mod style;
+pub mod suggest;
+pub mod tags;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+pub mod timings;
+pub mod transcript;
+pub mod tree;
+pub mod variants;
+
+pub use crate::core::{Correctness, Guess, Guesser, History, Mask, MaskExt, Word};
+#[cfg(feature = "serde")]
+pub use crate::simulate::{simulate, SimulateOptions};
 
 const DICTIONARY: &str = include_str!("../dictionary.txt");
 
+// Which guesses a game accepts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GuessPolicy {
+    // only words in the dictionary
+    #[default]
+    Strict,
+    // any five lowercase letters
+    Permissive,
+    // only words that could be an answer
+    AnswersOnly,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayError {
+    // the answer isn't five lowercase letters
+    MalformedAnswer(String),
+    // the answer isn't in the dictionary (see `Wordle::allow_unknown_answers`)
+    UnknownAnswer(String),
+    // the guesser made a guess the policy doesn't allow
+    IllegalGuess { round: usize, guess: String },
+    // the game has already been won or lost
+    GameOver,
+    // in a duel, it's the other player's turn
+    OutOfTurn,
+}
+
+impl fmt::Display for PlayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlayError::MalformedAnswer(answer) => {
+                write!(f, "answer '{}' is not five lowercase letters", answer)
+            }
+            PlayError::UnknownAnswer(answer) => {
+                write!(f, "answer '{}' is not in the dictionary", answer)
+            }
+            PlayError::IllegalGuess { round, guess } => {
+                write!(f, "guess {} ('{}') is not allowed", round, guess)
+            }
+            PlayError::GameOver => write!(f, "the game is already over"),
+            PlayError::OutOfTurn => write!(f, "it's the other player's turn"),
+        }
+    }
+}
+
+impl std::error::Error for PlayError {}
+
 pub struct Wordle {
     dictionary: HashSet<&'static str>,
+    answers: HashSet<&'static str>,
+    policy: GuessPolicy,
+    unknown_answers: bool,
+    hard_mode: bool,
+}
+
+impl Default for Wordle {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Wordle {
     pub fn new() -> Self {
+        let (dictionary, answers) = timings::time(timings::Phase::Load, || {
+            (
+                // the words without their counts (like `algorithms::dictionary`, this leaves
+                // out bad lines)
+                HashSet::from_iter(
+                    DICTIONARY
+                        .lines()
+                        .filter_map(dict::parse_line)
+                        .map(|(word, _)| word),
+                ),
+                algorithms::answers().collect(),
+            )
+        });
         Self {
-            // we want every other element because we want to omit the word count
-            dictionary: HashSet::from_iter(DICTIONARY.lines().map(|line| {
-                line.split_once(' ')
-                    .expect("every word is a word + space + word count")
-                    .0
-            })),
+            dictionary,
+            answers,
+            policy: GuessPolicy::Strict,
+            unknown_answers: false,
+            hard_mode: false,
         }
     }
 
-    pub fn play<G: Guesser>(&self, answer: &'static str, mut guesser: G) -> Option<usize> {
-        // play six rounds where it invokes guesser each round
-        let mut history = Vec::new();
-        // while wordle only allows for six guesses, we will limit
-        // our guesses so we do not cause stack overflow
-        for i in 1..=32 {
-            let guess = guesser.guess(&history);
-            if guess == answer {
-                return Some(i);
-            }
+    // Build what's otherwise built the first time something needs it (the bundled dictionary,
+    // its hash and the table of openers) on another thread, so whatever comes first doesn't wait
+    // on it. Join the handle to wait for it to finish.
+    pub fn warm_up() -> std::thread::JoinHandle<()> {
+        std::thread::spawn(|| {
+            dict::Dictionary::shared();
+            openers::bundled(openers::CONFIGURATIONS[0]);
+        })
+    }
 
-            // not sure why we need to deref and ref 'guess' again
-            assert!(self.dictionary.contains(&*guess));
+    pub fn with_policy(mut self, policy: GuessPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
 
-            let correctness = Correctness::compute(answer, &guess);
-            history.push(Guess {
-                word: guess,
-                mask: correctness,
-            });
-        }
-        None
+    // play answers that aren't in the dictionary instead of rejecting them (they still have to be
+    // five lowercase letters)
+    pub fn allow_unknown_answers(mut self) -> Self {
+        self.unknown_answers = true;
+        self
     }
-}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Correctness {
-    // Green
-    Correct,
-    // Yellow,
-    Misplaced,
-    // Gray,
-    Wrong,
-}
+    // only accept guesses that use every hint so far (see `hard_mode::is_legal_hard_mode`)
+    pub fn with_hard_mode(mut self) -> Self {
+        self.hard_mode = true;
+        self
+    }
 
-impl Correctness {
-    fn compute(answer: &str, guess: &str) -> [Self; 5] {
-        assert_eq!(answer.len(), 5);
-        assert_eq!(guess.len(), 5);
-        // initialise c as an array of five Wrong guesses
-        let mut c = [Correctness::Wrong; 5];
-
-        // Mark guesses correct
-        for (i, (a, g)) in answer.chars().zip(guess.chars()).enumerate() {
-            if a == g {
-                c[i] = Correctness::Correct;
-            }
+    pub(crate) fn allows(&self, guess: &str) -> bool {
+        match self.policy {
+            GuessPolicy::Strict => self.dictionary.contains(guess),
+            GuessPolicy::Permissive => well_formed(guess),
+            GuessPolicy::AnswersOnly => self.answers.contains(guess),
         }
-        // Mark guesses misplaced
-        let mut used = [false; 5];
-        for (i, &c) in c.iter().enumerate() {
-            if c == Correctness::Correct {
-                used[i] = true;
-            }
-        }
-        for (i, g) in guess.chars().enumerate() {
-            if c[i] == Correctness::Correct {
-                continue; // already marked as correct
-            }
-            // if the current guess letter matches any letters inside the answer
-            // true => mark as Misplaced
-            // false => remains Wrong
-            if answer.chars().enumerate().any(|(i, a)| {
-                if a == g && !used[i] {
-                    used[i] = true;
-                    return true;
-                }
-                false
-            }) {
-                c[i] = Correctness::Misplaced
-            }
-        }
-        c
     }
-}
 
-pub struct Guess {
-    pub word: String,
-    pub mask: [Correctness; 5],
-}
+    // accept (and take answers from) this word list instead of the bundled one
+    pub fn with_dictionary(mut self, words: impl IntoIterator<Item = &'static str>) -> Self {
+        self.dictionary = words.into_iter().collect();
+        self
+    }
 
-impl Guess {
-    pub fn matches(&self, word: &str) -> bool {
-        assert_eq!(self.word.len(), 5);
-        assert_eq!(word.len(), 5);
-
-        // first check greens
-        let mut used = [false; 5];
-        for (i, ((g, &m), w)) in self
-            .word
-            .chars()
-            .zip(&self.mask)
-            .zip(word.chars())
-            .enumerate()
-        {
-            if m == Correctness::Correct {
-                if g != w {
-                    return false;
-                } else {
-                    used[i] = true;
-                    continue;
-                }
-            }
+    // accept every spelling of words with regional variants as guesses
+    pub fn with_variants(mut self, variants: &variants::Variants<'static>) -> Self {
+        self.dictionary.extend(variants.words());
+        self
+    }
+
+    // start a game for someone else to play, checking the answer first
+    pub fn start(&self, answer: &str) -> Result<game::Game<'_>, PlayError> {
+        if !well_formed(answer) {
+            return Err(PlayError::MalformedAnswer(answer.to_string()));
         }
-        for (_, ((_, _), w)) in self
-            .word
-            .chars()
-            .zip(&self.mask)
-            .zip(word.chars())
-            .enumerate()
-        {
-            let mut plausible = true;
-            if self
-                .word
-                .chars()
-                .zip(&self.mask)
-                .enumerate()
-                .any(|(j, (g, m))| {
-                    if g != w {
-                        return false;
-                    }
-                    if used[j] {
-                        return false;
-                    }
-                    // we are looking at a 'w' in 'word' and have found a 'w' in the previous guess.
-                    // the colour of that previous 'w' will tell us whether this 'w' might be ok.
-                    match m {
-                        Correctness::Correct => unreachable!(
-                            "all correct guesses should have resulted in return or be used"
-                        ),
-                        Correctness::Misplaced if j == 1 => {
-                            // 'w'
-                            used[j] = true;
-                            return true;
-                        }
-                        Correctness::Misplaced => {
-                            used[j] = true;
-                            return true;
-                        }
-                        Correctness::Wrong => {
-                            plausible = false;
-                            return false;
-                        }
-                    }
-                })
-                && plausible
-            {
-                // the char 'w' was yellow in the previous guess
-            } else if !plausible {
-                return false;
-            } else {
-                // we have no info about char 'w', so word might still match
-            }
+        if !self.unknown_answers && !self.dictionary.contains(answer) {
+            return Err(PlayError::UnknownAnswer(answer.to_string()));
         }
-        true
+        Ok(game::Game::new(self, answer))
     }
-}
 
-pub trait Guesser {
-    // function that makes a guess; takes info of current guess progress as as arguments
-    fn guess(&mut self, history: &[Guess]) -> String;
-}
-
-impl Guesser for fn(history: &[Guess]) -> String {
-    fn guess(&mut self, history: &[Guess]) -> String {
-        (*self)(history)
+    pub fn play<G: Guesser>(
+        &self,
+        answer: &'static str,
+        guesser: G,
+    ) -> Result<Option<usize>, PlayError> {
+        let game = self.play_out(answer, guesser)?;
+        Ok((game.status() == game::Status::Won).then(|| game.history().len()))
     }
-}
 
-#[cfg(test)]
-macro_rules! guesser {
-    (|$history:ident| $impl:block) => {{
-        struct G;
-        impl $crate::Guesser for G {
-            fn guess(&mut self, $history: &[Guess]) -> String {
-                $impl
-            }
+    // like `play`, but hands back the finished game so the guesses can be looked at
+    pub fn play_out<G: Guesser>(
+        &self,
+        answer: &str,
+        mut guesser: G,
+    ) -> Result<game::Game<'_>, PlayError> {
+        // while wordle only allows for six guesses, we will limit
+        // our guesses so we do not cause stack overflow
+        let mut game = self.start(answer)?.with_max_guesses(32);
+        while game.status() == game::Status::InProgress {
+            let round = timings::Phase::Scoring(game.history().len() + 1);
+            let guess = timings::time(round, || guesser.guess(game.history()));
+            game.guess(&guess)?;
         }
-        G
-    }};
+        Ok(game)
+    }
 }
 
-#[cfg(test)]
-macro_rules! mask {
-    (C) => {$crate::Correctness::Correct};
-    (M) => {$crate::Correctness::Misplaced};
-    (W) => {$crate::Correctness::Wrong};
-    ($($c:tt)+) => {[
-        $(mask!($c)),+
-    ]}
+fn well_formed(word: &str) -> bool {
+    word.len() == 5 && word.bytes().all(|b| b.is_ascii_lowercase())
 }
 
 #[cfg(test)]
 mod tests {
-    mod guess_matcher {
-        use crate::Guess;
-
-        #[test]
-        fn matches() {
-            assert!(Guess {
-                word: "abcde".to_string(),
-                mask: mask![C C C C C],
-            }
-            .matches("abcde"));
-        }
+    #[test]
+    fn warm_up() {
+        crate::Wordle::warm_up().join().unwrap();
+        assert!(crate::openers::bundled("greedy:entropy").is_some());
     }
+
     mod game {
-        use crate::{Guess, Wordle};
+        use crate::Wordle;
 
         // make sure the code is playing the game correctly
         #[test]
         fn genius() {
             let w = Wordle::new();
             let guesser = guesser!(|_history| { "right".to_string() });
-            assert_eq!(w.play("right", guesser), Some(1));
+            assert_eq!(w.play("right", guesser), Ok(Some(1)));
         }
 
         #[test]
@@ -245,7 +280,7 @@ mod tests {
                 }
                 "wrong".to_string()
             });
-            assert_eq!(w.play("right", guesser), Some(2));
+            assert_eq!(w.play("right", guesser), Ok(Some(2)));
         }
 
         #[test]
@@ -257,7 +292,7 @@ mod tests {
                 }
                 "wrong".to_string()
             });
-            assert_eq!(w.play("right", guesser), Some(3));
+            assert_eq!(w.play("right", guesser), Ok(Some(3)));
         }
 
         #[test]
@@ -269,7 +304,7 @@ mod tests {
                 }
                 "wrong".to_string()
             });
-            assert_eq!(w.play("right", guesser), Some(4));
+            assert_eq!(w.play("right", guesser), Ok(Some(4)));
         }
 
         #[test]
@@ -281,7 +316,7 @@ mod tests {
                 }
                 "wrong".to_string()
             });
-            assert_eq!(w.play("right", guesser), Some(5));
+            assert_eq!(w.play("right", guesser), Ok(Some(5)));
         }
 
         #[test]
@@ -293,124 +328,80 @@ mod tests {
                 }
                 "wrong".to_string()
             });
-            assert_eq!(w.play("right", guesser), Some(6));
+            assert_eq!(w.play("right", guesser), Ok(Some(6)));
         }
 
         #[test]
         fn ooops() {
             let w = Wordle::new();
             let guesser = guesser!(|_history| { "wrong".to_string() });
-            assert_eq!(w.play("right", guesser), None);
+            assert_eq!(w.play("right", guesser), Ok(None));
         }
     }
 
-    mod compute {
-        use crate::Correctness;
-        #[test]
-        fn all_correct() {
-            assert_eq!(
-                Correctness::compute("abcde", "abcde"),
-                [Correctness::Correct; 5]
-            )
-        }
+    mod policy {
+        use crate::{GuessPolicy, PlayError, Wordle};
 
         #[test]
-        fn all_wrong() {
-            assert_eq!(
-                Correctness::compute("abcde", "ghjkl"),
-                [Correctness::Wrong; 5]
-            )
-        }
-        #[test]
-        fn all_misplaced() {
-            assert_eq!(
-                Correctness::compute("abcde", "eabcd"),
-                [Correctness::Misplaced; 5]
-            )
-        }
-
-        #[test]
-        fn repeat_green() {
-            assert_eq!(
-                Correctness::compute("aabbb", "aaccc"),
-                [
-                    Correctness::Correct,
-                    Correctness::Correct,
-                    Correctness::Wrong,
-                    Correctness::Wrong,
-                    Correctness::Wrong,
-                ]
-            )
-        }
-
-        #[test]
-        fn repeat_yellow() {
+        fn strict_rejects_unknown_words() {
+            let w = Wordle::new();
+            let guesser = guesser!(|_history| { "zzzzz".to_string() });
             assert_eq!(
-                Correctness::compute("aabbb", "ccaac"),
-                [
-                    Correctness::Wrong,
-                    Correctness::Wrong,
-                    Correctness::Misplaced,
-                    Correctness::Misplaced,
-                    Correctness::Wrong,
-                ]
-            )
+                w.play("right", guesser),
+                Err(PlayError::IllegalGuess {
+                    round: 1,
+                    guess: "zzzzz".to_string()
+                })
+            );
         }
 
         #[test]
-        fn repeat_some_green() {
+        fn answer_is_validated() {
+            let w = Wordle::new();
+            let guesser = guesser!(|_history| { "right".to_string() });
             assert_eq!(
-                Correctness::compute("aabbb", "caacc"),
-                [
-                    Correctness::Wrong,
-                    Correctness::Correct,
-                    Correctness::Misplaced,
-                    Correctness::Wrong,
-                    Correctness::Wrong,
-                ]
-            )
-        }
-
-        #[test]
-        fn correct_number_of_misplaced() {
+                w.play("rite", guesser),
+                Err(PlayError::MalformedAnswer("rite".to_string()))
+            );
+            let guesser = guesser!(|_history| { "qxzzy".to_string() });
             assert_eq!(
-                Correctness::compute("azzaz", "aaabb"),
-                [
-                    Correctness::Correct,
-                    Correctness::Misplaced,
-                    Correctness::Wrong,
-                    Correctness::Wrong,
-                    Correctness::Wrong,
-                ]
-            )
+                w.play("qxzzy", guesser),
+                Err(PlayError::UnknownAnswer("qxzzy".to_string()))
+            );
+            let w = Wordle::new()
+                .with_policy(GuessPolicy::Permissive)
+                .allow_unknown_answers();
+            let guesser = guesser!(|_history| { "qxzzy".to_string() });
+            assert_eq!(w.play("qxzzy", guesser), Ok(Some(1)));
         }
 
         #[test]
-        fn correct_number_of_correct() {
-            assert_eq!(
-                Correctness::compute("baccc", "aaddd"),
-                [
-                    Correctness::Wrong,
-                    Correctness::Correct,
-                    Correctness::Wrong,
-                    Correctness::Wrong,
-                    Correctness::Wrong,
-                ]
-            )
+        fn permissive_takes_any_letters() {
+            let w = Wordle::new().with_policy(GuessPolicy::Permissive);
+            let guesser = guesser!(|history| {
+                if history.is_empty() {
+                    return "zzzzz".to_string();
+                }
+                "right".to_string()
+            });
+            assert_eq!(w.play("right", guesser), Ok(Some(2)));
+            let guesser = guesser!(|_history| { "zzzz".to_string() });
+            assert!(w.play("right", guesser).is_err());
         }
 
         #[test]
-        fn correct_number_of_correct2() {
-            assert_eq!(
-                Correctness::compute("abcde", "aacde"),
-                [
-                    Correctness::Correct,
-                    Correctness::Wrong,
-                    Correctness::Correct,
-                    Correctness::Correct,
-                    Correctness::Correct,
-                ]
-            )
+        fn answers_only() {
+            let w = Wordle::new().with_policy(GuessPolicy::AnswersOnly);
+            // "tares" is in the dictionary but was never an answer
+            let guesser = guesser!(|_history| { "tares".to_string() });
+            assert!(w.play("right", guesser).is_err());
+            let guesser = guesser!(|history| {
+                if history.is_empty() {
+                    return "wrong".to_string();
+                }
+                "right".to_string()
+            });
+            assert_eq!(w.play("right", guesser), Ok(Some(2)));
         }
     }
 }
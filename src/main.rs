@@ -1,9 +1,3762 @@
+use std::collections::HashSet;
+use std::io::{BufWriter, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use wordle_solver::artifact::{Header, HeaderError, Kind, Rules};
+use wordle_solver::blocklist::Blocklist;
+use wordle_solver::config::Config;
+use wordle_solver::core::mask_to_cmw;
+use wordle_solver::daily::Day;
+use wordle_solver::dashboard::Dashboard;
+use wordle_solver::dict::{Dictionary, WordlistHash};
+use wordle_solver::duel::{Duel, Outcome, Turns};
+use wordle_solver::features::{letters, Features};
+use wordle_solver::game::{Game, Status};
+use wordle_solver::hard_mode::is_legal_hard_mode;
+use wordle_solver::i18n::{Catalog, Lang};
+use wordle_solver::openers;
+use wordle_solver::query::{self, Constraint};
+use wordle_solver::server::{Server, ServerConfig};
+use wordle_solver::session::Algorithm;
+use wordle_solver::stats::{Leaderboard, Run};
+use wordle_solver::style::{Palette, Style};
+use wordle_solver::suggest::{self, suggest_from_history, Constraints, SuggestOptions, Suggestion};
+use wordle_solver::tags::Tags;
+use wordle_solver::transcript::Transcript;
+use wordle_solver::tree::Tree;
+use wordle_solver::variants::{Spelling, Variants};
+use wordle_solver::{
+    algorithms, analysis, dict, length, platform, results, timings, Guess, GuessPolicy, Guesser,
+    History, Mask, MaskExt, PlayError, Word, Wordle,
+};
+
 const GAMES: &str = include_str!("../answers.txt");
+// the answers the best algorithm so far finds hardest, from `report hardest`
+const HARD: &str = include_str!("../hard.txt");
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Cli {
+    #[clap(subcommand)]
+    command: Command,
+
+    /// Read defaults from this file instead of ~/.config/wordle_solver/config.toml (or %APPDATA%\wordle_solver\config.toml on Windows)
+    #[clap(long, global = true, env = "WORDLE_SOLVER_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// Never color the output (colors are also off when it isn't a terminal, or NO_COLOR is set)
+    #[clap(long, global = true)]
+    no_color: bool,
+
+    /// The colors for masks, in the terminal and in share grids
+    #[clap(long, global = true, value_enum, env = "WORDLE_SOLVER_PALETTE")]
+    palette: Option<PaletteArg>,
+
+    /// The language for messages, and for the words suggest, grade and solve go by (which needs
+    /// its lang-* feature), like "es". Messages go by the locale when omitted, and the words stay
+    /// English
+    #[clap(long, global = true, env = "WORDLE_SOLVER_LANG")]
+    lang: Option<String>,
+
+    /// When done, print how long went on loading words, precomputing (trees, tables and
+    /// openers), filtering candidates and scoring guesses in each round, on stderr
+    #[clap(long, global = true)]
+    timings: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Play every official answer and report how many guesses each took
+    Bench(Box<Bench>),
+    /// Build the optimal decision tree and print it in the community tree format
+    ExportTree {
+        /// How many guesses to try per state; wider finds a better tree, but takes longer
+        #[clap(long, default_value_t = algorithms::Optimal::DEFAULT_WIDTH)]
+        width: usize,
+
+        /// Write the tree here instead of to stdout
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+
+        #[clap(flatten)]
+        memory: Memory,
+    },
+    /// Replay a decision tree against every official answer and check its claimed score
+    VerifyTree {
+        /// The tree, in the community tree format
+        path: PathBuf,
+
+        /// Fail unless the average number of guesses is this (to four decimal places)
+        #[clap(long)]
+        average: Option<f64>,
+
+        /// Fail unless the worst answer takes exactly this many guesses
+        #[clap(long)]
+        max: Option<usize>,
+    },
+    /// Write out game states along with the guess the optimal tree makes in each of them
+    Dataset {
+        #[clap(short, long, value_enum, default_value_t = Format::Csv)]
+        format: Format,
+
+        /// How many guesses the optimal tree tries per state; wider finds a better tree, but takes
+        /// longer
+        #[clap(long, default_value_t = algorithms::Optimal::DEFAULT_WIDTH)]
+        width: usize,
+
+        /// Write the dataset here instead of to stdout
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+
+        #[clap(flatten)]
+        memory: Memory,
+    },
+    /// Suggest the next guess for a game in progress
+    #[clap(after_help = "\
+Each guess is followed by the colors it got, one letter per square: G for green (right letter, \
+right place), Y for yellow (in the word, elsewhere) and B for black or grey (not in the word).
+
+Examples:
+  wordle_solver suggest
+  wordle_solver suggest tares BYBBG
+  wordle_solver suggest tares BBGBY lorry BBGBB --candidates-only
+  printf 'tares=BBGBY\\ntares=BBGBY,lorry=BBGBB\\n' | wordle_solver suggest --batch")]
+    Suggest {
+        /// The game so far as guesses each followed by their mask in B/Y/G, like "tares BYBBG"
+        history: Vec<String>,
+
+        /// Read a game per line from stdin instead, like "tares=BBGBY,lorry=BBGBB" (masks in B/Y/G
+        /// like the rest of suggest, though C/M/W works too), and print a guess per line for each; a line that
+        /// can't be answered gets an empty one, with the reason on stderr
+        #[clap(long, conflicts_with = "history")]
+        batch: bool,
+
+        /// Read the game from a board copied out of a client (a row of letters and a row of
+        /// colored squares per guess) in this file, or stdin with "-"
+        #[clap(long, conflicts_with_all = ["history", "batch"])]
+        board: Option<PathBuf>,
+
+        /// Only suggest words that could still be the answer
+        #[clap(long)]
+        candidates_only: bool,
+
+        /// Never suggest the words in this file (one per line), unless they're the only way to win
+        #[clap(long, env = "WORDLE_SOLVER_BLOCKLIST")]
+        blocklist: Option<PathBuf>,
+
+        /// Guess from this word list (one "word count" per line, like the bundled one) instead
+        #[clap(long)]
+        dictionary: Option<PathBuf>,
+
+        /// Leave out lines of --dictionary that aren't a word and a count (saying which) instead
+        /// of stopping
+        #[clap(long, requires = "dictionary")]
+        skip_bad_lines: bool,
+
+        /// The words that can be the answer (one per line); with --dictionary but without this,
+        /// the answers are the ones it marks as answers, or else guessed at from how common the
+        /// words are
+        #[clap(long)]
+        answers: Option<PathBuf>,
+
+        /// The share of the most common words of --dictionary that could be answers
+        #[clap(long, default_value_t = 0.3)]
+        answer_share: f64,
+
+        /// Words that look like plurals can be answers too (when guessing at the answers)
+        #[clap(long)]
+        plural_answers: bool,
+
+        /// Words that look like past tenses can be answers too (when guessing at the answers)
+        #[clap(long)]
+        past_tense_answers: bool,
+
+        /// Words ending in a single s can't be the answer, like in the official answers
+        #[clap(long)]
+        exclude_plurals: bool,
+
+        /// Words ending in -ed can't be the answer, like in the official answers
+        #[clap(long)]
+        exclude_past_tense: bool,
+
+        /// Only this many of the most common words (of the answers, or of the dictionary) can be
+        /// the answer; every word can still be guessed
+        #[clap(long)]
+        top_answers: Option<usize>,
+
+        /// How likely each word is taken to be the answer [default: counts]
+        #[clap(long, value_enum, env = "WORDLE_SOLVER_PRIOR")]
+        prior: Option<Smoothing>,
+
+        /// What to suggest for the sixth guess
+        #[clap(long, value_enum, default_value_t = Risk::Score)]
+        last_guess: Risk,
+
+        /// Never suggest these words, even when one of them is the only way to win (say they've
+        /// already been played on another board)
+        #[clap(long, value_delimiter = ',')]
+        ban: Vec<String>,
+
+        /// Only suggest words with all of these letters
+        #[clap(long)]
+        with_letters: Option<String>,
+
+        /// Only suggest words with none of these letters
+        #[clap(long)]
+        without_letters: Option<String>,
+
+        /// Play words of this many letters (4 to 8) instead of five, from --dictionary, which has
+        /// to be all that long (`dict build --length` makes one)
+        #[clap(long, requires = "dictionary", conflicts_with_all = ["batch", "board"])]
+        length: Option<usize>,
+    },
+    /// Grade each guess of a game from 0 to 100 against the best guess there was
+    #[clap(after_help = "\
+Examples:
+  wordle_solver grade tares BBGBY lorry BBGBB sprig GGGGG
+  wordle_solver grade tares lorry sprig --answer sprig")]
+    Grade {
+        /// The game as guesses each followed by their mask, like "tares BYBBG", or just the
+        /// guesses when the answer is given
+        history: Vec<String>,
+
+        /// The answer, to work out the masks from
+        #[clap(long)]
+        answer: Option<String>,
+    },
+    /// Step back and forth through a saved game, with the words left and how each guess
+    /// measured up at every point
+    #[clap(after_help = "\
+The file holds the game like \"tares=WMWWC doily=WWCWW\" (C for green, M for yellow and W for
+gray), or a board copied out of a client.
+
+Examples:
+  wordle_solver replay game.txt")]
+    Replay { path: PathBuf },
+    /// Grade every saved game in a directory, writing each one's guesses out as JSON with their
+    /// grade, the information they were expected to gain and did, and the luck in between
+    #[clap(after_help = "\
+Games are read like for replay. Each file's JSON goes next to it, with .json for its extension,
+unless --out says somewhere else; .json files already there are skipped.
+
+Examples:
+  wordle_solver annotate games/
+  wordle_solver annotate games/ --out annotated/")]
+    Annotate {
+        dir: PathBuf,
+
+        /// Write the JSON files here instead
+        #[clap(long)]
+        out: Option<PathBuf>,
+    },
+    /// List the words that fit what you already know, like a crossword pattern
+    #[clap(after_help = "\
+Examples:
+  wordle_solver solve s_a_e --absent rtl
+  wordle_solver solve --letters ertsa --use-all --rank bits")]
+    Solve {
+        /// Known letters and blanks, like s_a_e ('_', '.' and '?' are all blanks)
+        #[clap(default_value = "_____")]
+        pattern: String,
+
+        /// Letters that aren't in the word
+        #[clap(long)]
+        absent: Option<String>,
+
+        /// Only words made from these letters, each used at most as often as it's given
+        #[clap(long)]
+        letters: Option<String>,
+
+        /// With --letters, only words that use up every one of them
+        #[clap(long, requires = "letters")]
+        use_all: bool,
+
+        /// How to order the words
+        #[clap(long, value_enum, default_value_t = Rank::Frequency)]
+        rank: Rank,
+
+        /// How many words to show
+        #[clap(long, default_value_t = 20)]
+        top: usize,
+    },
+    /// Serve suggestions over HTTP
+    Serve {
+        /// The address to listen on
+        #[clap(long, default_value = "127.0.0.1:8080")]
+        addr: String,
+
+        /// How many requests each client may make per rate window
+        #[clap(long, default_value_t = 60)]
+        rate_limit: usize,
+
+        /// The length of the rate window, in seconds
+        #[clap(long, default_value_t = 60)]
+        rate_window: u64,
+
+        /// How long a session may sit idle before it's dropped, in seconds
+        #[clap(long, default_value_t = 1800)]
+        session_timeout: u64,
+
+        /// How many sessions there may be at once; asking for another gets a 503 until some
+        /// expire
+        #[clap(long, default_value_t = 10000)]
+        max_sessions: usize,
+
+        /// The largest request body accepted, in bytes
+        #[clap(long, default_value_t = 16384)]
+        max_body: usize,
+
+        /// How many suggestions to remember for when someone else is in the same spot; 0 turns
+        /// it off
+        #[clap(long, default_value_t = 1024)]
+        cache_size: usize,
+
+        /// How long a suggestion may take, in milliseconds, before the best guess found so far
+        /// is sent back instead (marked as truncated); 0 lets it take as long as it needs
+        #[clap(long, default_value_t = 5000)]
+        time_budget: u64,
+
+        /// The algorithms sessions may ask for, like "naive,greedy,beam:4,optimal" (a beam width
+        /// allows every width up to it)
+        #[clap(long, value_delimiter = ',', default_value = "naive,greedy,beam:4")]
+        algorithms: Vec<String>,
+
+        /// The languages sessions may play in, like "en,es" (the others need their lang-*
+        /// features)
+        #[clap(long, value_delimiter = ',', default_value = "en")]
+        languages: Vec<String>,
+
+        /// Append a line of JSON here for every suggestion served: the session, the history,
+        /// the guess and its score
+        #[clap(long)]
+        audit_log: Option<PathBuf>,
+    },
+    /// Serve suggestions over gRPC (see proto/wordle.proto)
+    #[cfg(feature = "grpc")]
+    ServeGrpc {
+        /// The address to listen on
+        #[clap(long, default_value = "127.0.0.1:50051")]
+        addr: std::net::SocketAddr,
+
+        /// How long a game may sit idle before it's dropped, in seconds
+        #[clap(long, default_value_t = 1800)]
+        session_timeout: u64,
+    },
+    /// Race two players (algorithms, or you at the terminal) to the same answer
+    Duel {
+        /// The two players, like "human,naive"
+        #[clap(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            default_values_t = [Player::Human, Player::Naive]
+        )]
+        players: Vec<Player>,
+
+        #[clap(long, value_enum, default_value_t = DuelTurns::Alternating)]
+        turns: DuelTurns,
+
+        /// The answer; a random official answer when omitted
+        #[clap(long)]
+        answer: Option<String>,
+
+        /// How many guesses the beam players keep at each step [default: 4]
+        #[clap(long, env = "WORDLE_SOLVER_BEAM_WIDTH")]
+        beam_width: Option<usize>,
+
+        /// Handicap: naive players pick at random from this many of their best guesses
+        #[clap(long)]
+        bot_top: Option<usize>,
+
+        /// Handicap: algorithm players have to open with this word
+        #[clap(long, env = "WORDLE_SOLVER_OPENER")]
+        bot_opener: Option<String>,
+
+        /// Assist: human players get this many guesses on top of the usual six
+        #[clap(long, default_value_t = 0)]
+        extra_guesses: usize,
+    },
+    /// Play today's word against the clock, and keep the result on a leaderboard
+    Speedrun {
+        /// Where the leaderboard is kept
+        #[clap(long, default_value = "leaderboard.txt")]
+        leaderboard: PathBuf,
+
+        /// Play in hard mode, where every hint has to be used in later guesses
+        #[clap(long)]
+        hard: bool,
+    },
+    /// Play today's word at your own pace, and keep the result on the leaderboard
+    Daily {
+        /// Where the leaderboard is kept
+        #[clap(long, default_value = "leaderboard.txt")]
+        leaderboard: PathBuf,
+
+        /// Play in hard mode, where every hint has to be used in later guesses
+        #[clap(long)]
+        hard: bool,
+    },
+    /// Play a past day's word, which doesn't go on the leaderboard
+    Archive {
+        /// The day, as YYYY-MM-DD, or the puzzle's number (the first being 0)
+        puzzle: String,
+
+        /// Play in hard mode, where every hint has to be used in later guesses
+        #[clap(long)]
+        hard: bool,
+    },
+    /// Print a completion script for a shell, to source from its startup file
+    #[clap(after_help = "\
+Examples:
+  wordle_solver completions bash > ~/.local/share/bash-completion/completions/wordle_solver
+  wordle_solver completions zsh > ~/.zfunc/_wordle_solver
+  wordle_solver completions fish > ~/.config/fish/completions/wordle_solver.fish")]
+    Completions {
+        #[clap(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// Show games played, wins, streaks and personal bests from the leaderboard
+    Stats {
+        /// Where the leaderboard is kept
+        #[clap(long, default_value = "leaderboard.txt")]
+        leaderboard: PathBuf,
+    },
+    /// Check the bundled word lists and tables agree with each other and with this build
+    Selfcheck,
+    /// Maintain word lists in the "word count" format of dictionary.txt
+    Dict {
+        #[clap(subcommand)]
+        command: DictCommand,
+    },
+    /// Look into how the algorithms play
+    Analyze {
+        #[clap(subcommand)]
+        command: AnalyzeCommand,
+    },
+    /// Play one algorithm under every combination of some rule settings, and show how each
+    /// setting changes how it does
+    #[clap(after_help = "\
+Examples:
+  wordle_solver sweep -i greedy --max 200
+  wordle_solver sweep --hard off,on --rounds 6,7,8 --guesses strict")]
+    Sweep(Sweep),
+    /// Write a two level strategy to print out and play by hand: an opener, what to play after
+    /// each mask it gets back, and what that leaves, as markdown
+    #[clap(after_help = "\
+Examples:
+  wordle_solver cheatsheet > cheatsheet.md
+  wordle_solver cheatsheet --opener salet --min-answers 20 --palette high-contrast")]
+    Cheatsheet(Cheatsheet),
+    /// Play with some of the masks the algorithms are shown changed, as if someone misread
+    /// them, and show how well each algorithm copes
+    #[clap(after_help = "\
+Examples:
+  wordle_solver noise --max 200
+  wordle_solver noise -i naive,greedy,beam --rate 0,0.1,0.3 --seed 7")]
+    Noise(Noise),
+    /// Look into saved benchmark results
+    Report {
+        #[clap(subcommand)]
+        command: ReportCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum AnalyzeCommand {
+    /// Find where two algorithms would guess differently, and what each choice costs
+    Disagreements {
+        #[clap(value_enum)]
+        a: Implementation,
+        #[clap(value_enum)]
+        b: Implementation,
+
+        /// Only play this many answers
+        #[clap(short, long)]
+        max: Option<usize>,
+
+        /// How many guesses the beam implementation keeps at each step [default: 4]
+        #[clap(long, env = "WORDLE_SOLVER_BEAM_WIDTH")]
+        beam_width: Option<usize>,
+
+        #[clap(flatten)]
+        scorer: Scoring,
+
+        /// How many of the biggest disagreements to show
+        #[clap(long, default_value_t = 10)]
+        top: usize,
+    },
+    /// Rank fixed sequences of two or three openers, played whatever the masks, by how well
+    /// they split up the answers
+    Openings {
+        /// Each sequence as comma-separated words, like "salet,crony"
+        #[clap(required = true)]
+        sequences: Vec<String>,
+    },
+    /// Show every mask an opener can get back, how likely it is, how many answers it leaves
+    /// and what to play next
+    Patterns {
+        opener: String,
+
+        /// What picks the follow-ups [default: naive]
+        #[clap(short, long, value_enum, env = "WORDLE_SOLVER_ALGORITHM")]
+        implementation: Option<Implementation>,
+
+        /// How many guesses the beam implementation keeps at each step [default: 4]
+        #[clap(long, env = "WORDLE_SOLVER_BEAM_WIDTH")]
+        beam_width: Option<usize>,
+
+        #[clap(flatten)]
+        scorer: Scoring,
+
+        /// Leave out the masks no answer gives
+        #[clap(long)]
+        possible_only: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ReportCommand {
+    /// Compare two saved runs answer by answer (saved by bench --output, as csv, ndjson or json)
+    Compare {
+        before: PathBuf,
+        after: PathBuf,
+
+        /// Only compare this algorithm's games, for runs of several algorithms
+        #[clap(long)]
+        algorithm: Option<String>,
+
+        /// How many of the biggest changes to show
+        #[clap(long, default_value_t = 10)]
+        top: usize,
+    },
+    /// Break a saved run down by the kind of answer, hardest first: ones with repeated letters,
+    /// ones ending in a vowel, and each quarter of the answers by how common they are
+    Tags {
+        path: PathBuf,
+
+        /// Only count this algorithm's games, for runs of several algorithms
+        #[clap(long)]
+        algorithm: Option<String>,
+
+        /// More tags from this file, a word and then its tags on each line (like "llama animal")
+        #[clap(long)]
+        tags: Option<PathBuf>,
+
+        /// Leave out the built-in tags, and only go by the ones from --tags
+        #[clap(long, requires = "tags")]
+        no_builtin: bool,
+    },
+    /// List the answers a saved run found hardest, in the format of the bundled list bench
+    /// --hard-only plays (hard.txt); for regenerating it when a better algorithm comes along
+    Hardest {
+        path: PathBuf,
+
+        /// Only go by this algorithm's games, for runs of several algorithms
+        #[clap(long)]
+        algorithm: Option<String>,
+
+        /// How many answers to list
+        #[clap(short = 'n', long, default_value_t = 100)]
+        count: usize,
+
+        /// Write the list here instead of to stdout
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum DictCommand {
+    /// Pair a word list with counts from a frequency corpus
+    Build {
+        /// The words to include, one per line
+        #[clap(long)]
+        words: PathBuf,
+
+        /// The corpus: a word and its count on each line
+        #[clap(long)]
+        counts: PathBuf,
+
+        /// Only keep words this long
+        #[clap(long, default_value_t = 5)]
+        length: usize,
+
+        /// Drop words seen fewer times than this
+        #[clap(long, default_value_t = 1)]
+        min_count: u64,
+
+        /// The count for words that aren't in the corpus at all
+        #[clap(long, default_value_t = 1)]
+        missing_count: u64,
+
+        /// Leave out the words in this file (one per line)
+        #[clap(long)]
+        exclude: Option<PathBuf>,
+
+        /// Write the dictionary here instead of to stdout
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Combine two dictionaries, adding up the counts of words in both
+    Merge {
+        a: PathBuf,
+        b: PathBuf,
+
+        /// How long the words are
+        #[clap(long, default_value_t = 5)]
+        length: usize,
+
+        /// Write the dictionary here instead of to stdout
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Show the words added, removed, and recounted going from one dictionary to another
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+
+        /// How long the words are
+        #[clap(long, default_value_t = 5)]
+        length: usize,
+    },
+    /// Work out the first guess for every configuration the bundled table (openers.txt) has,
+    /// which takes a while; for after changing the bundled word lists
+    Openers {
+        /// Write the table here instead of to stdout
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum Format {
+    Csv,
+    Ndjson,
+}
+
+#[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
+struct Bench {
+    #[clap(subcommand)]
+    command: Option<BenchCommand>,
+
+    /// [default: naive]
+    #[clap(short, long, value_enum, env = "WORDLE_SOLVER_ALGORITHM")]
+    implementation: Option<Implementation>,
+
+    /// Only play this many games
+    #[clap(short, long)]
+    max: Option<usize>,
+
+    /// How many guesses the beam implementation keeps at each step [default: 4]
+    #[clap(long, env = "WORDLE_SOLVER_BEAM_WIDTH")]
+    beam_width: Option<usize>,
+
+    /// Keep the states the beam and greedy implementations work out in this directory, and
+    /// reuse them in later runs with the same words and settings
+    #[clap(long, env = "WORDLE_SOLVER_CACHE_DIR")]
+    cache_dir: Option<PathBuf>,
+
+    /// How many guesses the optimal implementation tries per state; wider finds a better tree,
+    /// but takes longer
+    #[clap(long, default_value_t = algorithms::Optimal::DEFAULT_WIDTH)]
+    optimal_width: usize,
+
+    #[clap(flatten)]
+    memory: Memory,
+
+    #[clap(flatten)]
+    scorer: Scoring,
+
+    /// Play the greedy implementation with this word list (one "word count" per line, like the
+    /// bundled one) instead of the bundled dictionary. It only ever keeps totals per mask, so
+    /// even very long lists fit in memory
+    #[clap(long)]
+    dictionary: Option<PathBuf>,
+
+    /// Leave out lines of --dictionary that aren't a word and a count (saying which) instead of
+    /// stopping
+    #[clap(long, requires = "dictionary")]
+    skip_bad_lines: bool,
+
+    /// Play a decision tree from this file (in the community tree format) instead of building
+    /// one with the optimal implementation
+    #[clap(long)]
+    tree: Option<PathBuf>,
+
+    /// Learn which of these openers works best over the run, instead of the usual opener
+    #[clap(long, value_delimiter = ',', env = "WORDLE_SOLVER_OPENERS")]
+    openers: Vec<String>,
+
+    /// What the openers are compared on
+    #[clap(long, value_enum, default_value_t = Goal::Average)]
+    objective: Goal,
+
+    /// Never suggest the words in this file (one per line), unless they're the only way to win
+    #[clap(long, env = "WORDLE_SOLVER_BLOCKLIST")]
+    blocklist: Option<PathBuf>,
+
+    /// Only use this spelling of words with regional variants as answers (both are still valid
+    /// guesses)
+    #[clap(long, value_enum)]
+    spelling: Option<Dialect>,
+
+    /// Pairs of british and american spellings, one pair per line, instead of the bundled ones
+    #[clap(long, env = "WORDLE_SOLVER_VARIANTS")]
+    variants: Option<PathBuf>,
+
+    /// Which guesses the game accepts; with answers-only the solver only considers answers too
+    #[clap(long, value_enum, default_value_t = Policy::Strict)]
+    guesses: Policy,
+
+    /// Play the answers in this file (one per line) instead of the official ones
+    #[clap(long)]
+    answers: Option<PathBuf>,
+
+    /// Only play the answers the best algorithm so far found hardest (the bundled hard.txt), as
+    /// a quick check while working on an algorithm
+    #[clap(long, conflicts_with = "answers")]
+    hard_only: bool,
+
+    /// Save how every game went here, as csv, ndjson, json or parquet (with the arrow feature)
+    /// going by the extension
+    #[clap(short, long)]
+    output: Vec<PathBuf>,
+
+    /// Run a benchmark profile from the config; flags given as well override it
+    #[clap(long)]
+    profile: Option<String>,
+
+    /// What to do about answers that were failed or took six guesses
+    #[clap(long, value_enum)]
+    on_failure: Option<OnFailure>,
+
+    /// What to play for the sixth guess
+    #[clap(long, value_enum, default_value_t = Risk::Score)]
+    last_guess: Risk,
+
+    /// The naive and greedy implementations only take this many of the most common words to be
+    /// possible answers (every word can still be guessed), so answers outside them can be lost
+    #[clap(long)]
+    top_answers: Option<usize>,
+
+    /// Show a live summary of the run (how fast it's going, the averages, the game being played
+    /// and how many guesses the games took) instead of a line per game; needs a terminal
+    #[clap(long)]
+    dashboard: bool,
+
+    // the saved game being played again, by `bench replay`
+    #[clap(skip)]
+    replaying: Option<results::GameRecord>,
+}
+
+#[derive(Subcommand, Debug)]
+enum BenchCommand {
+    /// Play one game from a saved run again, exactly as it was played, and show every guess with
+    /// what it left
+    Replay {
+        /// The game's answer
+        answer: String,
+
+        /// The run the game was saved in, with --output as ndjson or json
+        #[clap(long)]
+        from: PathBuf,
+
+        /// Which algorithm's game, when the run played more than one
+        #[clap(long)]
+        algorithm: Option<String>,
+    },
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum OnFailure {
+    /// Print an annotated transcript of each one after the run
+    Trace,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum PaletteArg {
+    /// Green and yellow
+    Standard,
+    /// Orange and blue, which are easier to tell apart for colorblind players
+    HighContrast,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum Rank {
+    /// Most common first
+    Frequency,
+    /// The words that best tell the rest apart first
+    Bits,
+}
+
+#[derive(Args, Debug)]
+struct Sweep {
+    /// Only the naive and greedy implementations can play hard mode [default: naive]
+    #[clap(short, long, value_enum, env = "WORDLE_SOLVER_ALGORITHM")]
+    implementation: Option<Implementation>,
+
+    /// Only play this many answers
+    #[clap(short, long)]
+    max: Option<usize>,
+
+    #[clap(flatten)]
+    scorer: Scoring,
+
+    /// Whether to play in hard mode, where every hint has to be used in later guesses
+    #[clap(long, value_enum, value_delimiter = ',', default_values_t = [Toggle::Off, Toggle::On])]
+    hard: Vec<Toggle>,
+
+    /// How many guesses a game allows; the games are only played once, and cut off at each
+    #[clap(long, value_delimiter = ',', default_values_t = [6])]
+    rounds: Vec<usize>,
+
+    /// Which guesses the game accepts (and the algorithm considers)
+    #[clap(long, value_enum, value_delimiter = ',', default_values_t = [Policy::Strict, Policy::AnswersOnly])]
+    guesses: Vec<Policy>,
+}
+
+#[derive(Args, Debug)]
+struct Cheatsheet {
+    /// Open with this instead of whatever the implementation would
+    #[clap(long)]
+    opener: Option<String>,
+
+    /// What picks the guesses [default: naive]
+    #[clap(short, long, value_enum, env = "WORDLE_SOLVER_ALGORITHM")]
+    implementation: Option<Implementation>,
+
+    /// How many guesses the beam implementation keeps at each step [default: 4]
+    #[clap(long, env = "WORDLE_SOLVER_BEAM_WIDTH")]
+    beam_width: Option<usize>,
+
+    #[clap(flatten)]
+    scorer: Scoring,
+
+    /// Masks that leave fewer answers than this just list them, rather than getting a section
+    /// of their own
+    #[clap(long, default_value_t = 10)]
+    min_answers: usize,
+}
+
+#[derive(Args, Debug)]
+struct Noise {
+    /// Which implementations to play; optimal only follows its own tree, so it can't
+    #[clap(short, long, value_enum, value_delimiter = ',', default_values_t = [Implementation::Naive, Implementation::Greedy])]
+    implementation: Vec<Implementation>,
+
+    /// Only play this many answers
+    #[clap(short, long)]
+    max: Option<usize>,
+
+    /// How many guesses the beam implementation keeps at each step [default: 4]
+    #[clap(long, env = "WORDLE_SOLVER_BEAM_WIDTH")]
+    beam_width: Option<usize>,
+
+    #[clap(flatten)]
+    scorer: Scoring,
+
+    /// The chance of each mask being wrong, as a fraction
+    #[clap(long, value_delimiter = ',', default_values_t = [0.0, 0.05, 0.1, 0.2])]
+    rate: Vec<f64>,
+
+    /// How many guesses a game allows; more than six gives room to recover from a bad mask
+    #[clap(long, default_value_t = 6)]
+    rounds: usize,
+
+    /// Where the random changes start from, so a run can be repeated
+    #[clap(long, default_value_t = 1)]
+    seed: u64,
+
+    /// Give every implementation its own random changes, rather than the same ones for the same
+    /// answer; the comparisons get noisier, which is only worth it to see by how much
+    #[clap(long)]
+    independent: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum Toggle {
+    Off,
+    On,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum Policy {
+    /// Only dictionary words
+    Strict,
+    /// Any five lowercase letters
+    Permissive,
+    /// Only words from the answer list
+    AnswersOnly,
+}
+
+impl From<Policy> for GuessPolicy {
+    fn from(policy: Policy) -> Self {
+        match policy {
+            Policy::Strict => Self::Strict,
+            Policy::Permissive => Self::Permissive,
+            Policy::AnswersOnly => Self::AnswersOnly,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum Dialect {
+    British,
+    American,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum Goal {
+    Average,
+    WithinSix,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum Risk {
+    /// Whatever the algorithm scores best, like any other guess
+    Score,
+    /// The likeliest word that could still be the answer, since there's nothing after it
+    Win,
+}
+
+impl From<Risk> for algorithms::RiskMode {
+    fn from(risk: Risk) -> Self {
+        match risk {
+            Risk::Score => algorithms::RiskMode::Score,
+            Risk::Win => algorithms::RiskMode::Win,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum Player {
+    Human,
+    Naive,
+    Beam,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum DuelTurns {
+    /// One guess each in turn; whoever gets it first wins
+    Alternating,
+    /// Both guess every round; solving it in the same round is a draw
+    Simultaneous,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum Implementation {
+    Naive,
+    Beam,
+    Optimal,
+    Greedy,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum Heuristic {
+    /// Most information gained
+    Entropy,
+    /// Fewest candidates left on average
+    ExpectedSize,
+    /// Fewest candidates left at worst
+    WorstCase,
+    /// Fewest guesses expected to finish, going by how many a set of candidates usually takes
+    ExpectedLength,
+    /// Most likely to be the answer
+    Frequency,
+    /// A weighted sum of entropy, the chance of being the answer, and the worst case, weighted
+    /// by the --*-weight options
+    Composite,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum Smoothing {
+    /// As often as they were seen
+    Counts,
+    /// The log of how often they were seen
+    Log,
+    /// Going by how common they are next to the rest, with the few thousand most common words
+    /// about as likely as each other
+    Sigmoid,
+    /// All as likely
+    Uniform,
+}
+
+impl Smoothing {
+    // the one given, or else the one in the config
+    fn or_config(given: Option<Self>, config: &Config) -> Result<Option<Self>, String> {
+        match (given, &config.prior) {
+            (None, Some(name)) => Smoothing::from_str(name, true)
+                .map(Some)
+                .map_err(|_| format!("unknown prior '{}' in the config", name)),
+            _ => Ok(given),
+        }
+    }
+
+    fn prior(self) -> Box<dyn algorithms::Prior> {
+        match self {
+            Smoothing::Counts => Box::new(algorithms::Counts),
+            Smoothing::Log => Box::new(algorithms::LogCounts),
+            Smoothing::Sigmoid => Box::new(algorithms::SigmoidRank::default()),
+            Smoothing::Uniform => Box::new(algorithms::Uniform),
+        }
+    }
+}
+
+#[derive(Args, Debug, Clone, Copy)]
+struct Scoring {
+    /// What the greedy implementation picks its guesses by
+    #[clap(long, value_enum, default_value_t = Heuristic::Entropy)]
+    scorer: Heuristic,
+
+    /// How much each bit of information counts for in the composite scorer
+    #[clap(long, default_value_t = 1.0)]
+    entropy_weight: f64,
+
+    /// How much the chance of the guess being the answer (from 0 to 1) counts for in the
+    /// composite scorer
+    #[clap(long, default_value_t = 0.0)]
+    prior_weight: f64,
+
+    /// How much each candidate that can be left at worst counts against a guess in the composite
+    /// scorer
+    #[clap(long, default_value_t = 0.0)]
+    worst_case_weight: f64,
+
+    /// Give guesses that might be the answer credit for maybe ending the game right away, worth
+    /// all the information still unknown (so it's in bits, like entropy)
+    #[clap(long)]
+    answer_bonus: bool,
+
+    /// How likely the naive and greedy implementations take each word to be the answer
+    /// [default: counts]
+    #[clap(long, value_enum, env = "WORDLE_SOLVER_PRIOR")]
+    prior: Option<Smoothing>,
+}
+
+impl Scoring {
+    // The opener from the bundled table (see `openers`), for greedy on the bundled words. There's
+    // none for the composite scorer or the answer bonus, since the weights could be anything.
+    fn bundled_opener(self, answers_only: bool) -> Option<&'static str> {
+        if self.answer_bonus
+            || matches!(self.scorer, Heuristic::Composite)
+            || self.prior.is_some_and(|prior| prior != Smoothing::Counts)
+        {
+            return None;
+        }
+        let name = self.scorer.to_possible_value()?.get_name().to_string();
+        let configuration = match answers_only {
+            true => format!("greedy:{}:answers-only", name),
+            false => format!("greedy:{}", name),
+        };
+        openers::bundled(&configuration)
+    }
+
+    fn scorer(self) -> Box<dyn algorithms::Scorer> {
+        let scorer: Box<dyn algorithms::Scorer> = match self.scorer {
+            Heuristic::Entropy => Box::new(algorithms::Entropy),
+            Heuristic::ExpectedSize => Box::new(algorithms::ExpectedSize),
+            Heuristic::WorstCase => Box::new(algorithms::WorstCase),
+            Heuristic::ExpectedLength => Box::new(algorithms::ExpectedLength),
+            Heuristic::Frequency => Box::new(algorithms::Frequency),
+            Heuristic::Composite => Box::new(
+                algorithms::Composite::new()
+                    .with(self.entropy_weight, algorithms::Entropy)
+                    .with(self.prior_weight, algorithms::Frequency)
+                    .with(self.worst_case_weight, algorithms::WorstCase),
+            ),
+        };
+        if self.answer_bonus {
+            Box::new(algorithms::AnswerBonus::new(scorer))
+        } else {
+            scorer
+        }
+    }
+
+    // the prior from the config, unless one was given
+    fn with_config(mut self, config: &Config) -> Result<Self, String> {
+        self.prior = Smoothing::or_config(self.prior, config)?;
+        Ok(self)
+    }
+
+    fn prior(self) -> Box<dyn algorithms::Prior> {
+        self.prior.unwrap_or(Smoothing::Counts).prior()
+    }
+
+    fn naive(self) -> algorithms::Naive {
+        algorithms::Naive::new().with_prior(&*self.prior())
+    }
+
+    fn greedy(self) -> algorithms::Greedy<Box<dyn algorithms::Scorer>> {
+        algorithms::Greedy::new(self.scorer()).with_prior(&*self.prior())
+    }
+}
+
+#[derive(Args, Debug, Clone, Copy)]
+struct Memory {
+    /// Only build the optimal implementation's table of masks if it fits in this many megabytes,
+    /// and work each mask out when it comes up otherwise (slower, but fine on a small machine)
+    #[clap(long, env = "WORDLE_SOLVER_TABLE_MEMORY")]
+    table_memory: Option<usize>,
+
+    /// Print how much memory the precomputed tables take to stderr
+    #[clap(long)]
+    report_memory: bool,
+}
+
+impl Memory {
+    // the limit in bytes
+    fn table_limit(self) -> Option<usize> {
+        self.table_memory.map(|mb| mb.saturating_mul(1 << 20))
+    }
+
+    fn report(self, blocklist: &Blocklist, policy: GuessPolicy) {
+        if !self.report_memory {
+            return;
+        }
+        let size = algorithms::Optimal::official_table_size(blocklist, policy);
+        let mb = size as f64 / (1 << 20) as f64;
+        match self.table_limit() {
+            Some(limit) if size > limit => eprintln!(
+                "the mask table would take {:.1} MB, over the {} MB limit, so masks were worked \
+                 out as they came up",
+                mb,
+                self.table_memory.unwrap_or_default()
+            ),
+            _ => eprintln!("the mask table took {:.1} MB", mb),
+        }
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    let started = Instant::now();
+    if cli.timings {
+        timings::enable();
+    }
+    let config = match cli.config.or_else(Config::default_path) {
+        Some(path) => Config::load(&path)?,
+        None => Config::default(),
+    };
+    let palette = match cli.palette {
+        Some(PaletteArg::Standard) => Palette::Standard,
+        Some(PaletteArg::HighContrast) => Palette::HighContrast,
+        None => match &config.palette {
+            Some(name) => name.parse()?,
+            None => Palette::default(),
+        },
+    };
+    let style = match cli.no_color || config.color == Some(false) {
+        true => Style {
+            color: false,
+            palette,
+        },
+        false => Style::detect(palette),
+    };
+    // the words only change when asked for, so a locale doesn't change what's being solved
+    let words_lang: Option<Lang> = cli
+        .lang
+        .or_else(|| config.lang.clone())
+        .map(|lang| lang.parse())
+        .transpose()?;
+    let messages = Catalog::new(words_lang.unwrap_or_else(Lang::detect));
+    let report_timings = cli.timings;
+    match cli.command {
+        Command::Bench(args) => bench(*args, &config, style)?,
+        Command::ExportTree {
+            width,
+            output,
+            memory,
+        } => {
+            let blocklist = Blocklist::default();
+            let tree = algorithms::Optimal::official(
+                Some(width),
+                &blocklist,
+                GuessPolicy::Strict,
+                memory.table_limit(),
+            );
+            memory.report(&blocklist, GuessPolicy::Strict);
+            let answers: Vec<_> = GAMES.split_whitespace().collect();
+            let header = Header::new(Kind::Tree, Rules::default(), WordlistHash::bundled());
+            let text = format!("{}\n{}", header, tree.export(&answers));
+            match output {
+                Some(path) => std::fs::write(path, text)?,
+                None => print!("{}", text),
+            }
+        }
+        Command::VerifyTree { path, average, max } => verify_tree(&path, average, max)?,
+        Command::Selfcheck => selfcheck()?,
+        Command::Dataset {
+            format,
+            width,
+            output,
+            memory,
+        } => {
+            let out: Box<dyn Write> = match output {
+                Some(path) => Box::new(std::fs::File::create(path)?),
+                None => Box::new(std::io::stdout().lock()),
+            };
+            dataset(format, width, memory, BufWriter::new(out))?;
+        }
+        Command::Suggest {
+            history,
+            batch,
+            board,
+            candidates_only,
+            blocklist,
+            dictionary,
+            skip_bad_lines,
+            answers,
+            answer_share,
+            plural_answers,
+            past_tense_answers,
+            exclude_plurals,
+            exclude_past_tense,
+            top_answers,
+            prior,
+            last_guess,
+            ban,
+            with_letters,
+            without_letters,
+            length,
+        } => {
+            let prior = Smoothing::or_config(prior, &config)?;
+            let blocklist = blocklist.or(config.blocklist);
+            let given = dictionary.is_some();
+            let mut dictionary = match dictionary {
+                Some(path) => read_dictionary(&path, skip_bad_lines, length.unwrap_or(5))?,
+                None => word_list(words_lang)?,
+            };
+            let answers = match answers {
+                Some(path) => Some(Dictionary::new(
+                    std::fs::read_to_string(path)?
+                        .split_whitespace()
+                        .map(|word| (word.to_lowercase(), 1))
+                        .collect(),
+                )),
+                // a list of guesses on its own says nothing about which could be answers, unless
+                // it marks them
+                None if given => Some(dictionary.marked_answers().unwrap_or_else(|| {
+                    dictionary.plausible_answers(&dict::PlausibleOptions {
+                        share: answer_share,
+                        plurals: plural_answers,
+                        past_tense: past_tense_answers,
+                    })
+                })),
+                None => None,
+            };
+            let answers = if exclude_plurals || exclude_past_tense {
+                let from = answers.as_ref().unwrap_or(&dictionary);
+                Some(from.without_forms(exclude_plurals, exclude_past_tense))
+            } else {
+                answers
+            };
+            let mut answers = match top_answers {
+                Some(n) => {
+                    // a list of answers doesn't say how common they are, but the dictionary does
+                    let from = dictionary
+                        .only(|word, _| answers.as_ref().is_none_or(|a| a.0.contains_key(word)));
+                    Some(from.most_common(n))
+                }
+                None => answers,
+            };
+            // only now, since which words are common enough to be answers goes by the counts
+            if let Some(prior) = prior {
+                let prior = prior.prior();
+                dictionary = dictionary.with_prior(&*prior);
+                answers = answers.map(|answers| answers.with_prior(&*prior));
+            }
+            let constraints = Constraints::new()
+                .ban(&ban)
+                .with_letters(with_letters.as_deref().unwrap_or_default())
+                .without_letters(without_letters.as_deref().unwrap_or_default());
+            if let Some(length) = length {
+                let blocklist = read_blocklist(blocklist.as_deref())?;
+                let options = SuggestOptions {
+                    blocklist: Some(&blocklist),
+                    candidates_only,
+                    answers: answers.as_ref(),
+                    risk: last_guess.into(),
+                    constraints: Some(&constraints),
+                    ..Default::default()
+                };
+                suggest_length(length, &dictionary, &history, &options)?
+            } else if batch {
+                suggest_batch(
+                    &dictionary,
+                    answers.as_ref(),
+                    candidates_only,
+                    blocklist.as_deref(),
+                    last_guess.into(),
+                    &constraints,
+                )?
+            } else {
+                let history = match board {
+                    Some(path) => {
+                        let text = if path.as_os_str() == "-" {
+                            std::io::read_to_string(std::io::stdin())?
+                        } else {
+                            std::fs::read_to_string(&path)?
+                        };
+                        History::from_board(&text).map_err(|e| e.to_string())?
+                    }
+                    None => parse_history(&history)?,
+                };
+                suggest(
+                    &dictionary,
+                    answers.as_ref(),
+                    &history,
+                    candidates_only,
+                    blocklist.as_deref(),
+                    last_guess.into(),
+                    &constraints,
+                )?
+            }
+        }
+        Command::Grade { history, answer } => {
+            grade(&word_list(words_lang)?, &history, answer.as_deref())?
+        }
+        Command::Replay { path } => replay(&word_list(words_lang)?, &path, style)?,
+        Command::Annotate { dir, out } => annotate(&word_list(words_lang)?, &dir, out.as_deref())?,
+        Command::Solve {
+            pattern,
+            absent,
+            letters,
+            use_all,
+            rank,
+            top,
+        } => {
+            let mut constraints = vec![Constraint::pattern(&pattern)?];
+            constraints.extend(absent.as_deref().map(Constraint::absent));
+            constraints.extend(
+                letters
+                    .as_deref()
+                    .map(|letters| Constraint::letter_bank(letters, use_all)),
+            );
+            solve(&word_list(words_lang)?, &constraints, rank, top)
+        }
+        Command::Serve {
+            addr,
+            rate_limit,
+            rate_window,
+            session_timeout,
+            max_sessions,
+            max_body,
+            cache_size,
+            time_budget,
+            algorithms,
+            languages,
+            audit_log,
+        } => {
+            let algorithms = algorithms
+                .iter()
+                .map(|name| Algorithm::parse(name).ok_or(format!("unknown algorithm '{}'", name)))
+                .collect::<Result<_, _>>()?;
+            let languages = languages
+                .iter()
+                .map(|code| code.parse())
+                .collect::<Result<_, _>>()?;
+            let config = ServerConfig {
+                rate_limit,
+                rate_window: Duration::from_secs(rate_window),
+                session_timeout: Duration::from_secs(session_timeout),
+                max_sessions,
+                max_body,
+                cache_size,
+                time_budget: (time_budget > 0).then(|| Duration::from_millis(time_budget)),
+                algorithms,
+                languages,
+            };
+            let mut server = Server::new(config);
+            if let Some(path) = audit_log {
+                let log = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .map_err(|e| format!("can't open {}: {}", path.display(), e))?;
+                server = server.with_audit_log(log);
+            }
+            eprintln!("listening on {}", addr);
+            server.run(&addr)?;
+        }
+        #[cfg(feature = "grpc")]
+        Command::ServeGrpc {
+            addr,
+            session_timeout,
+        } => {
+            let solver = wordle_solver::grpc::GrpcSolver::new(Duration::from_secs(session_timeout));
+            eprintln!("listening on {}", addr);
+            tokio::runtime::Runtime::new()?.block_on(wordle_solver::grpc::serve(addr, solver))?;
+        }
+        Command::Duel {
+            players,
+            turns,
+            answer,
+            beam_width,
+            bot_top,
+            bot_opener,
+            extra_guesses,
+        } => {
+            let handicaps = Handicaps {
+                bot_top,
+                bot_opener: bot_opener.or(config.opener),
+                extra_guesses,
+            };
+            let beam_width = beam_width.or(config.beam_width).unwrap_or(4);
+            duel(
+                &players, turns, answer, beam_width, handicaps, style, &messages,
+            )?
+        }
+        Command::Speedrun { leaderboard, hard } => {
+            daily(&leaderboard, true, hard, style, &messages)?
+        }
+        Command::Daily { leaderboard, hard } => daily(&leaderboard, false, hard, style, &messages)?,
+        Command::Archive { puzzle, hard } => archive(&puzzle, hard, style, &messages)?,
+        Command::Completions { shell } => {
+            let mut command = Cli::command();
+            let name = command.get_name().to_string();
+            clap_complete::generate(shell, &mut command, name, &mut std::io::stdout());
+        }
+        Command::Stats { leaderboard } => stats(&leaderboard, &messages)?,
+        Command::Dict { command } => dict(command)?,
+        Command::Analyze { command } => analyze(command, &config, style)?,
+        Command::Sweep(args) => sweep(args, &config)?,
+        Command::Cheatsheet(args) => cheatsheet(args, &config, style)?,
+        Command::Noise(args) => noise(args, &config)?,
+        Command::Report { command } => report(command)?,
+    }
+    if report_timings {
+        print_timings(started.elapsed());
+    }
+    Ok(())
+}
+
+// what --timings prints, with whatever no phase covers (like writing output) as the rest
+fn print_timings(total: Duration) {
+    let phases = timings::report();
+    let covered: Duration = phases.iter().map(|&(_, time, _)| time).sum();
+    eprintln!(
+        "{:<18} {:>10} {:>6} {:>8}",
+        "phase", "time", "share", "calls"
+    );
+    let row = |name: &str, time: Duration, calls: Option<u64>| {
+        let line = format!(
+            "{:<18} {:>9.3}s {:>5.1}% {:>8}",
+            name,
+            time.as_secs_f64(),
+            100.0 * time.as_secs_f64() / total.as_secs_f64().max(f64::EPSILON),
+            calls.map_or_else(String::new, |calls| calls.to_string())
+        );
+        eprintln!("{}", line.trim_end());
+    };
+    for (phase, time, calls) in phases {
+        row(&phase.to_string(), time, Some(calls));
+    }
+    row("everything else", total.saturating_sub(covered), None);
+    row("total", total, None);
+}
+
+// an empty leaderboard if there's no file yet
+fn read_leaderboard(path: &Path) -> Result<Leaderboard, Box<dyn std::error::Error>> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => Ok(Leaderboard::parse(&text)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Leaderboard::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn daily(
+    path: &Path,
+    timed: bool,
+    hard: bool,
+    style: Style,
+    messages: &Catalog,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut leaderboard = read_leaderboard(path)?;
+    let day = Day::today();
+    if leaderboard.played(day) {
+        return Err(messages.get("daily-played", &[("day", &day)]).into());
+    }
+    let answer = day
+        .answer()
+        .ok_or_else(|| messages.get("daily-no-answer", &[]))?;
+    let (guesses, time) = play_day(day, answer, timed, hard, style, messages)?;
+    leaderboard.runs.push(Run {
+        day,
+        answer: answer.to_string(),
+        guesses,
+        time: timed.then_some(time),
+    });
+    std::fs::write(path, leaderboard.to_string())?;
+    Ok(())
+}
+
+// A past day's puzzle, to catch up on or play again. It's kept off the leaderboard, since the
+// answer may well be known by now.
+fn archive(
+    puzzle: &str,
+    hard: bool,
+    style: Style,
+    messages: &Catalog,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let day = match puzzle.parse() {
+        Ok(number) => Day::of_number(number),
+        Err(_) => Day::parse(puzzle)
+            .ok_or_else(|| format!("'{}' is neither a day (YYYY-MM-DD) nor a number", puzzle))?,
+    };
+    let answer = day
+        .answer()
+        .filter(|_| day <= Day::today())
+        .ok_or_else(|| messages.get("archive-no-answer", &[("day", &day)]))?;
+    play_day(day, answer, false, hard, style, messages)?;
+    Ok(())
+}
+
+// play one day's puzzle through to the end, and say how many guesses it took and how long
+fn play_day(
+    day: Day,
+    answer: &str,
+    timed: bool,
+    hard: bool,
+    style: Style,
+    messages: &Catalog,
+) -> Result<(Option<usize>, Duration), Box<dyn std::error::Error>> {
+    let w = if hard {
+        Wordle::new().with_hard_mode()
+    } else {
+        Wordle::new()
+    };
+    let mut game = w.start(answer)?;
+    let number = day.number().expect("days with answers have numbers");
+    let key = if timed { "daily-timed" } else { "daily" };
+    println!("{}", messages.get(key, &[("number", &number)]));
+    let start = Instant::now();
+    let mut human = Human::new(messages);
+    if hard {
+        human = human.hard_mode(messages);
+    }
+    while game.status() == Status::InProgress {
+        let guess = human.guess(game.history());
+        if guess.is_empty() {
+            return Err(messages.get("no-more-guesses", &[]).into());
+        }
+        match game.guess(&guess) {
+            Ok(mask) => println!("{}", style.mask(&guess, &mask)),
+            Err(e @ PlayError::IllegalGuess { .. }) => println!("{}", e),
+            Err(e) => return Err(e.into()),
+        }
+    }
+    let time = start.elapsed();
+    let guesses = (game.status() == Status::Won).then(|| game.history().len());
+    let seconds = format!("{:.1}", time.as_secs_f64());
+    let result = match guesses {
+        Some(n) if timed => messages.get("solved-timed", &[("guesses", &n), ("seconds", &seconds)]),
+        Some(n) => messages.get("solved", &[("guesses", &n)]),
+        None => messages.get("out-of-guesses", &[("answer", &answer)]),
+    };
+    println!("{}", result);
+    let score = guesses.map_or("X".to_string(), |n| n.to_string());
+    // the game's own share text marks hard mode with an asterisk
+    let mark = if hard { "*" } else { "" };
+    println!("\nwordle {} {}/6{}", number, score, mark);
+    print!("{}", style.grid(game.history()));
+    Ok((guesses, time))
+}
+
+fn stats(path: &Path, messages: &Catalog) -> Result<(), Box<dyn std::error::Error>> {
+    let leaderboard = read_leaderboard(path)?;
+    let summary = leaderboard.summary(Day::today());
+    let win_percent = match summary.played {
+        0 => 0,
+        played => (summary.won * 100 + played / 2) / played,
+    };
+    let line =
+        |key: &str, n: &dyn std::fmt::Display| println!("{}", messages.get(key, &[("n", n)]));
+    line("stats-played", &summary.played);
+    line("stats-win-percent", &win_percent);
+    line("stats-current-streak", &summary.current_streak);
+    line("stats-max-streak", &summary.max_streak);
+    println!("{}", messages.get("stats-distribution", &[]));
+    let most = summary
+        .distribution
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    for (i, &count) in summary.distribution.iter().enumerate() {
+        let bar = "#".repeat((count * 20).div_ceil(most));
+        println!("  {} | {} {}", i + 1, bar, count);
+    }
+    if let Some(run) = summary.fastest {
+        let seconds = format!(
+            "{:.1}",
+            run.time.expect("only timed games count").as_secs_f64()
+        );
+        let args: [(&str, &dyn std::fmt::Display); 3] = [
+            ("seconds", &seconds),
+            ("answer", &run.answer),
+            ("day", &run.day),
+        ];
+        println!("{}", messages.get("stats-fastest", &args));
+    }
+    if let Some(run) = summary.fewest_guesses {
+        let guesses = run.guesses.expect("only wins count");
+        let args: [(&str, &dyn std::fmt::Display); 3] = [
+            ("guesses", &guesses),
+            ("answer", &run.answer),
+            ("day", &run.day),
+        ];
+        println!("{}", messages.get("stats-fewest", &args));
+    }
+    Ok(())
+}
+
+// Reads guesses typed in at the terminal.
+struct Human {
+    prompt: String,
+    // what to say when a guess breaks hard mode, if it's being played
+    hard_mode: Option<String>,
+}
+
+impl Human {
+    fn new(messages: &Catalog) -> Self {
+        Self {
+            prompt: messages.get("your-guess", &[]),
+            hard_mode: None,
+        }
+    }
+
+    fn hard_mode(mut self, messages: &Catalog) -> Self {
+        self.hard_mode = Some(messages.get("hard-mode-illegal", &[]));
+        self
+    }
+}
+
+impl Guesser for Human {
+    fn guess(&mut self, history: &History) -> String {
+        loop {
+            print!("{} ", self.prompt);
+            let _ = std::io::stdout().flush();
+            let mut line = String::new();
+            // an empty guess means there's nothing more to read
+            let _ = std::io::stdin().read_line(&mut line);
+            let guess = line.trim().to_lowercase();
+            match &self.hard_mode {
+                // ask again rather than hand the game a guess it'll only turn down
+                Some(illegal) if !guess.is_empty() && !is_legal_hard_mode(history, &guess) => {
+                    println!("{}", illegal)
+                }
+                _ => return guess,
+            }
+        }
+    }
+}
+
+// ways to even out a duel between people and algorithms
+struct Handicaps {
+    bot_top: Option<usize>,
+    bot_opener: Option<String>,
+    extra_guesses: usize,
+}
+
+fn duel(
+    players: &[Player],
+    turns: DuelTurns,
+    answer: Option<String>,
+    beam_width: usize,
+    handicaps: Handicaps,
+    style: Style,
+    messages: &Catalog,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if players.len() != 2 {
+        return Err("a duel needs exactly two players".into());
+    }
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.subsec_nanos());
+    let answer = answer.unwrap_or_else(|| {
+        let answers: Vec<_> = GAMES.split_whitespace().collect();
+        answers[nanos as usize % answers.len()].to_string()
+    });
+    let turns = match turns {
+        DuelTurns::Alternating => Turns::Alternating,
+        DuelTurns::Simultaneous => Turns::Simultaneous,
+    };
+    let w = Wordle::new();
+    let mut duel = Duel::new(&w, &answer, turns)?;
+    for (p, &player) in players.iter().enumerate() {
+        if player == Player::Human {
+            duel = duel.with_max_guesses(p, 6 + handicaps.extra_guesses);
+        }
+    }
+    // the opener has to outlive the guessers
+    let opener: Option<&'static str> = handicaps
+        .bot_opener
+        .map(|opener| &*Box::leak(opener.into_boxed_str()));
+    let mut guessers: Vec<Box<dyn Guesser>> = Vec::new();
+    for (p, &player) in players.iter().enumerate() {
+        let bot: Box<dyn Guesser> = match player {
+            Player::Human => {
+                guessers.push(Box::new(Human::new(messages)));
+                continue;
+            }
+            Player::Naive => {
+                let naive = algorithms::Naive::new();
+                match handicaps.bot_top {
+                    Some(top) => Box::new(naive.with_handicap(top, nanos as u64 + p as u64)),
+                    None => Box::new(naive),
+                }
+            }
+            Player::Beam => Box::new(algorithms::Beam::new(beam_width)),
+        };
+        guessers.push(match opener {
+            Some(opener) => Box::new(algorithms::Opener::new(opener, bot)),
+            None => bot,
+        });
+    }
+    let name = |p: usize| format!("{:?} ({})", players[p], p + 1).to_lowercase();
+    // don't give the game away to someone playing along
+    let human = players.contains(&Player::Human);
+
+    while duel.outcome().is_none() {
+        for p in duel.to_move() {
+            loop {
+                let guess = guessers[p].guess(duel.history(p));
+                if guess.is_empty() {
+                    return Err(messages.get("no-more-guesses", &[]).into());
+                }
+                match duel.guess(p, &guess) {
+                    Ok(mask) => {
+                        let shown = if human && players[p] != Player::Human {
+                            "?????"
+                        } else {
+                            &guess
+                        };
+                        println!("{}: {}", name(p), style.played(shown, &mask));
+                        break;
+                    }
+                    Err(e @ PlayError::IllegalGuess { .. }) if players[p] == Player::Human => {
+                        println!("{}", e);
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+    }
+    match duel.outcome() {
+        Some(Outcome::Won(p)) => println!("{}", messages.get("duel-wins", &[("player", &name(p))])),
+        _ => println!("{}", messages.get("duel-draw", &[])),
+    }
+    println!("{}", messages.get("duel-answer", &[("answer", &answer)]));
+    Ok(())
+}
+
+// guesses each followed by their mask, like "tares BYBBG"
+fn parse_history(history: &[String]) -> Result<History, String> {
+    if !history.len().is_multiple_of(2) {
+        return Err("every guess needs a mask".into());
+    }
+    history
+        .chunks(2)
+        .map(|pair| {
+            let word = Word::new(&pair[0].to_lowercase())
+                .ok_or_else(|| format!("'{}' isn't a five letter word", pair[0]))?;
+            let mask = Mask::from_compact_str(&pair[1])
+                .ok_or_else(|| format!("'{}' is not a mask like BYBBG", pair[1]))?;
+            Ok(Guess { word, mask })
+        })
+        .collect()
+}
+
+fn read_blocklist(path: Option<&Path>) -> std::io::Result<Blocklist> {
+    match path {
+        Some(path) => Ok(Blocklist::parse(&std::fs::read_to_string(path)?)),
+        None => Ok(Blocklist::default()),
+    }
+}
+
+// A word list in the "word count" format, of `length` letter words. Every bad line is reported,
+// rather than only the first, and with `skip_bad_lines` they're left out (with a warning) instead.
+fn read_dictionary(path: &Path, skip_bad_lines: bool, length: usize) -> Result<Dictionary, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let (dictionary, errors) = Dictionary::parse_skipping_length(&text, length);
+    if !skip_bad_lines && !errors.is_empty() {
+        return Err(format!("{}: {}", path.display(), dict::ParseErrors(errors)));
+    }
+    for error in &errors {
+        eprintln!("{}: skipping {}", path.display(), error);
+    }
+    Ok(dictionary)
+}
+
+// the bundled words, or another language's if one was asked for
+fn word_list(lang: Option<Lang>) -> Result<Dictionary, String> {
+    let lang = lang.unwrap_or_default();
+    Dictionary::for_lang(lang).ok_or_else(|| {
+        format!(
+            "no {:?} words in this build; it needs the lang-{} feature",
+            lang,
+            lang.code()
+        )
+    })
+}
+
+fn suggest(
+    dictionary: &Dictionary,
+    answers: Option<&Dictionary>,
+    history: &History,
+    candidates_only: bool,
+    blocklist: Option<&Path>,
+    risk: algorithms::RiskMode,
+    constraints: &Constraints,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let blocklist = read_blocklist(blocklist)?;
+    let options = SuggestOptions {
+        blocklist: Some(&blocklist),
+        candidates_only,
+        answers,
+        max_guesses: None,
+        risk,
+        constraints: Some(constraints),
+        cancel: None,
+        deadline: None,
+    };
+    let round = timings::Phase::Scoring(history.len() + 1);
+    let suggestion = timings::time(round, || {
+        suggest_from_history(dictionary, history, &options)
+    });
+    print_suggestion(suggestion, constraints)
+}
+
+// `suggest` for words that aren't five letters, with the history as it was typed
+fn suggest_length(
+    length: usize,
+    dictionary: &Dictionary,
+    history: &[String],
+    options: &SuggestOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !history.len().is_multiple_of(2) {
+        return Err("every guess needs a mask".into());
+    }
+    let words: Vec<_> = history
+        .iter()
+        .step_by(2)
+        .map(|w| w.to_lowercase())
+        .collect();
+    let masks = history.iter().skip(1).step_by(2).map(String::as_str);
+    let history: Vec<_> = words.iter().map(String::as_str).zip(masks).collect();
+    let round = timings::Phase::Scoring(history.len() + 1);
+    let suggestion = timings::time(round, || {
+        length::suggest(length, dictionary, &history, options)
+    })
+    .map_err(|e| e.to_string())?;
+    print_suggestion(
+        suggestion,
+        options.constraints.unwrap_or(&Constraints::default()),
+    )
+}
+
+fn print_suggestion(
+    suggestion: Suggestion,
+    constraints: &Constraints,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match suggestion.guess {
+        Some(guess) => println!(
+            "{} ({:.2} bits, {} words left, {:.0}% it's the answer, {:.0}% solved in time)",
+            guess,
+            suggestion.bits,
+            suggestion.remaining,
+            suggestion.p_answer * 100.0,
+            suggestion.p_solved * 100.0
+        ),
+        None => return Err(nothing_to_suggest(constraints).into()),
+    }
+    Ok(())
+}
+
+fn nothing_to_suggest(constraints: &Constraints) -> &'static str {
+    if *constraints == Constraints::default() {
+        "no word fits that history"
+    } else {
+        "no word fits that history without breaking --ban, --with-letters or --without-letters"
+    }
+}
+
+// One line out for every line in, so the answers line up with the games even when some of them
+// are nonsense. stdout is line buffered, so this can also be driven a line at a time.
+fn suggest_batch(
+    dictionary: &Dictionary,
+    answers: Option<&Dictionary>,
+    candidates_only: bool,
+    blocklist: Option<&Path>,
+    risk: algorithms::RiskMode,
+    constraints: &Constraints,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let blocklist = read_blocklist(blocklist)?;
+    let options = SuggestOptions {
+        blocklist: Some(&blocklist),
+        candidates_only,
+        answers,
+        max_guesses: None,
+        risk,
+        constraints: Some(constraints),
+        cancel: None,
+        deadline: None,
+    };
+    let mut out = std::io::stdout().lock();
+    for (i, line) in std::io::stdin().lines().enumerate() {
+        let line = line?;
+        let suggestion = History::parse(&line)
+            .map_err(|e| e.to_string())
+            .and_then(|history| {
+                let round = timings::Phase::Scoring(history.len() + 1);
+                timings::time(round, || {
+                    suggest_from_history(dictionary, &history, &options)
+                })
+                .guess
+                .ok_or_else(|| nothing_to_suggest(constraints).to_string())
+            });
+        match suggestion {
+            Ok(guess) => writeln!(out, "{}", guess)?,
+            Err(e) => {
+                eprintln!("line {}: {}", i + 1, e);
+                writeln!(out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn solve(dictionary: &Dictionary, constraints: &[Constraint], rank: Rank, top: usize) {
+    let mut completions = query::complete(dictionary, constraints);
+    if let Rank::Bits = rank {
+        completions.sort_by(|a, b| b.bits.total_cmp(&a.bits));
+    }
+    println!("{} words fit", completions.len());
+    for completion in completions.iter().take(top) {
+        println!(
+            "  {} (seen {} times, {:.2} bits)",
+            completion.word, completion.count, completion.bits
+        );
+    }
+}
+
+fn grade(
+    dictionary: &Dictionary,
+    history: &[String],
+    answer: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let history = match answer {
+        Some(answer) => {
+            let words: Vec<_> = history.iter().map(|word| word.to_lowercase()).collect();
+            if let Some(word) = words.iter().find(|word| Word::new(word).is_none()) {
+                return Err(format!("'{}' isn't a five letter word", word).into());
+            }
+            History::from_answer(answer, words.iter().map(String::as_str))
+        }
+        None => parse_history(history)?,
+    };
+    for (i, grade) in suggest::grade(dictionary, &history).iter().enumerate() {
+        let best = match &grade.best.guess {
+            Some(best) if grade.score < 100 => {
+                format!("; {} would have been {:.2} bits", best, grade.best.bits)
+            }
+            _ => String::new(),
+        };
+        println!(
+            "{}. {} {:>3}/100  {:.2} bits with {} words left{}",
+            i + 1,
+            grade.guess,
+            grade.score,
+            grade.bits,
+            grade.best.remaining,
+            best
+        );
+    }
+    Ok(())
+}
+
+// a saved game, in `History::parse`'s format or as a board copied out of a client
+fn read_game(path: &Path) -> Result<History, Box<dyn std::error::Error>> {
+    let text = std::fs::read_to_string(path)?;
+    Ok(History::parse(&text)
+        .or_else(|_| History::from_board(&text))
+        .map_err(|e| format!("{}: {}", path.display(), e))?)
+}
+
+// One JSON file per game. A file that can't be read is reported and skipped, so one bad game
+// doesn't stop the rest.
+fn annotate(
+    dictionary: &Dictionary,
+    dir: &Path,
+    out: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut paths = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.retain(|path| path.is_file() && path.extension().is_none_or(|ext| ext != "json"));
+    paths.sort();
+    let out = out.unwrap_or(dir);
+    std::fs::create_dir_all(out)?;
+    let mut written = 0;
+    for path in &paths {
+        let history = match read_game(path) {
+            Ok(history) => history,
+            Err(e) => {
+                eprintln!("skipping {}", e);
+                continue;
+            }
+        };
+        let guesses: Vec<_> = suggest::grade(dictionary, &history)
+            .into_iter()
+            .zip(history.iter())
+            .map(|(grade, guess)| {
+                serde_json::json!({
+                    "guess": grade.guess,
+                    "mask": guess.mask.to_compact_string(),
+                    "score": grade.score,
+                    "expected_bits": grade.bits,
+                    "gained_bits": grade.gained,
+                    "luck": grade.luck,
+                    "before": grade.best.remaining,
+                    "left": grade.left,
+                    "best": grade.best.guess,
+                    "best_bits": grade.best.bits,
+                })
+            })
+            .collect();
+        let luck: f64 = guesses.iter().filter_map(|g| g["luck"].as_f64()).sum();
+        let game = serde_json::json!({
+            "file": path.file_name().map(|name| name.to_string_lossy()),
+            "solved": history.is_won(),
+            "luck": luck,
+            "guesses": guesses,
+        });
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        let to = out.join(format!("{}.json", name));
+        let mut file = BufWriter::new(std::fs::File::create(&to)?);
+        serde_json::to_writer_pretty(&mut file, &game)?;
+        writeln!(file)?;
+        written += 1;
+    }
+    println!(
+        "annotated {} of {} games into {}",
+        written,
+        paths.len(),
+        out.display()
+    );
+    Ok(())
+}
+
+// A prompt for moving through the game, which starts before the first guess. Each position
+// shows the board so far, what's left, and how the next guess compares with the best one there.
+fn replay(
+    dictionary: &Dictionary,
+    path: &Path,
+    style: Style,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let history = read_game(path)?;
+    let words: Vec<_> = dictionary.0.keys().map(String::as_str).collect();
+    let transcript = Transcript::annotate(&history, &words);
+    let grades = suggest::grade(dictionary, &history);
+    let mut at = 0;
+    loop {
+        println!();
+        println!("after {} of {} guesses", at, history.len());
+        for guess in &history[..at] {
+            println!("  {}", style.guess(guess));
+        }
+        let left = match at {
+            0 => words.clone(),
+            _ => transcript.steps[at - 1].left.clone(),
+        };
+        let shown: Vec<_> = left.iter().take(12).copied().collect();
+        let more = left.len().saturating_sub(shown.len());
+        print!("{} words left: {}", left.len(), shown.join(" "));
+        if more > 0 {
+            print!(" and {} more", more);
+        }
+        println!();
+        if let Some(grade) = grades.get(at) {
+            let best = &grade.best;
+            println!(
+                "next: {} {:>3}/100  {:.2} bits{}",
+                grade.guess,
+                grade.score,
+                grade.bits,
+                if transcript.steps[at].candidate {
+                    ""
+                } else {
+                    " (couldn't have been the answer)"
+                }
+            );
+            if let Some(guess) = &best.guess {
+                println!(
+                    "best: {} {:.2} bits, {:.0}% it's the answer, {:.0}% solved in time",
+                    guess,
+                    best.bits,
+                    best.p_answer * 100.0,
+                    best.p_solved * 100.0
+                );
+            }
+        }
+
+        print!("[n]ext, [p]revious, a guess number, or [q]uit: ");
+        std::io::stdout().flush()?;
+        let mut line = String::new();
+        if std::io::stdin().read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        at = match line.trim() {
+            "" | "n" => (at + 1).min(history.len()),
+            "p" => at.saturating_sub(1),
+            "q" => return Ok(()),
+            other => match other.parse::<usize>() {
+                Ok(n) if n <= history.len() => n,
+                _ => {
+                    println!(
+                        "'{}' isn't n, p, q or a guess from 0 to {}",
+                        other,
+                        history.len()
+                    );
+                    at
+                }
+            },
+        };
+    }
+}
+
+// a fresh guesser for the analyses, which hand guessers histories they didn't play themselves
+fn analysis_guesser(
+    implementation: Implementation,
+    beam_width: usize,
+    scorer: Scoring,
+) -> Result<Box<dyn Guesser>, Box<dyn std::error::Error>> {
+    Ok(match implementation {
+        Implementation::Naive => Box::new(scorer.naive()),
+        Implementation::Beam => Box::new(algorithms::Beam::new(beam_width)),
+        Implementation::Greedy => {
+            let greedy = scorer.greedy();
+            match scorer.bundled_opener(false) {
+                Some(opener) => Box::new(greedy.with_opener(opener)),
+                None => Box::new(greedy),
+            }
+        }
+        Implementation::Optimal => {
+            return Err("the optimal implementation can only follow its own tree".into())
+        }
+    })
+}
+
+fn analyze(
+    command: AnalyzeCommand,
+    config: &Config,
+    style: Style,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        AnalyzeCommand::Disagreements {
+            a,
+            b,
+            max,
+            beam_width,
+            scorer,
+            top,
+        } => {
+            let scorer = scorer.with_config(config)?;
+            let beam_width = beam_width.or(config.beam_width).unwrap_or(4);
+            // the openers are the same every game and can be slow to work out, so get them once
+            // (which checks both guessers can be used here too)
+            let opener = |implementation| -> Result<&'static str, Box<dyn std::error::Error>> {
+                let opener =
+                    analysis_guesser(implementation, beam_width, scorer)?.guess(&History::new());
+                Ok(Box::leak(opener.into_boxed_str()))
+            };
+            let (opener_a, opener_b) = (opener(a)?, opener(b)?);
+            let mut answers: Vec<&'static str> = GAMES.split_whitespace().collect();
+            answers.truncate(max.unwrap_or(usize::MAX));
+            let found = analysis::disagreements(
+                || {
+                    let inner = analysis_guesser(a, beam_width, scorer).unwrap();
+                    algorithms::Opener::new(opener_a, inner)
+                },
+                || {
+                    let inner = analysis_guesser(b, beam_width, scorer).unwrap();
+                    algorithms::Opener::new(opener_b, inner)
+                },
+                &answers,
+            );
+            let total: i64 = found
+                .iter()
+                .map(|d| d.costs[1] as i64 - d.costs[0] as i64)
+                .sum();
+            println!(
+                "{} states where they disagree; going with b's choice costs {:+} guesses over {} answers",
+                found.len(),
+                total,
+                answers.len()
+            );
+            for d in found.iter().take(top) {
+                let history: Vec<_> = d.history.iter().map(|g| style.guess(g)).collect();
+                let history = if history.is_empty() {
+                    "to open".to_string()
+                } else {
+                    format!("after {}", history.join(", "))
+                };
+                println!(
+                    "{}: a plays {}, b plays {} ({} answers, {:+.2} guesses each for b)",
+                    history,
+                    d.choices[0],
+                    d.choices[1],
+                    d.answers.len(),
+                    d.mean_delta()
+                );
+            }
+        }
+        AnalyzeCommand::Openings { sequences } => {
+            let answers: Vec<_> = GAMES.split_whitespace().collect();
+            let mut ranked = Vec::new();
+            for sequence in &sequences {
+                let openers: Vec<_> = sequence.split(',').map(str::trim).collect();
+                if let Some(bad) = openers.iter().find(|w| w.len() != 5) {
+                    return Err(format!("'{}' isn't a five letter word", bad).into());
+                }
+                ranked.push((sequence, analysis::Opening::evaluate(&openers, &answers)));
+            }
+            ranked.sort_by(|(_, a), (_, b)| a.expected_remaining.total_cmp(&b.expected_remaining));
+            for (sequence, opening) in ranked {
+                println!(
+                    "{}: {:.3} left on average, {} at most, {} groups, {:.1}% known, {:.2} bits",
+                    sequence,
+                    opening.expected_remaining,
+                    opening.largest,
+                    opening.groups,
+                    100.0 * opening.solved,
+                    opening.bits
+                );
+            }
+        }
+        AnalyzeCommand::Patterns {
+            opener,
+            implementation,
+            beam_width,
+            scorer,
+            possible_only,
+        } => {
+            let scorer = scorer.with_config(config)?;
+            let Some(word) = Word::new(&opener) else {
+                return Err(format!("'{}' isn't a five letter word", opener).into());
+            };
+            let implementation = match (implementation, &config.algorithm) {
+                (Some(implementation), _) => implementation,
+                (None, Some(name)) => Implementation::from_str(name, true)
+                    .map_err(|_| format!("unknown algorithm '{}' in the config", name))?,
+                (None, None) => Implementation::Naive,
+            };
+            let beam_width = beam_width.or(config.beam_width).unwrap_or(4);
+            let mut guesser = analysis_guesser(implementation, beam_width, scorer)?;
+            let answers: Vec<_> = GAMES.split_whitespace().collect();
+            for outcome in analysis::breakdown(&opener, &answers) {
+                if possible_only && outcome.answers.is_empty() {
+                    continue;
+                }
+                let next = match outcome.answers[..] {
+                    [] => "-".to_string(),
+                    _ if outcome.mask.is_win() => "solved".to_string(),
+                    [answer] => answer.to_string(),
+                    _ => {
+                        let history = History::from(vec![Guess {
+                            word,
+                            mask: outcome.mask,
+                        }]);
+                        guesser.guess(&history)
+                    }
+                };
+                // a few answers are worth listing, since any of them could just be played
+                let which = match outcome.answers.len() {
+                    2..=3 => format!(" ({})", outcome.answers.join(", ")),
+                    _ => String::new(),
+                };
+                println!(
+                    "{} {:>6.2}% {:>4}  {}{}",
+                    style.mask(&opener, &outcome.mask),
+                    100.0 * outcome.probability,
+                    outcome.answers.len(),
+                    next,
+                    which
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_results(
+    path: &Path,
+    algorithm: Option<&str>,
+) -> Result<Vec<results::GameRecord>, Box<dyn std::error::Error>> {
+    let format = match results::Format::from_path(path) {
+        Some(results::Format::Parquet) => {
+            return Err(format!("{}: parquet files can only be written", path.display()).into())
+        }
+        Some(format) => format,
+        None => {
+            return Err(format!(
+                "can't tell what format {} is in; use .csv, .ndjson or .json",
+                path.display()
+            )
+            .into())
+        }
+    };
+    // runs under different rules can still be compared
+    let text = read_artifact(path, Kind::Results, None)?;
+    let in_file = |e: &dyn std::fmt::Display| format!("{}: {}", path.display(), e);
+    let mut records = if format == results::Format::Json {
+        // the header is in the report, which `read_artifact` didn't look in
+        let report = results::read_report(&text).map_err(|e| in_file(&e))?;
+        let bundled = WordlistHash::bundled();
+        if let Some(found) = report
+            .wordlist
+            .filter(|found| *found != bundled.to_string())
+        {
+            let mismatch = dict::WordlistMismatch {
+                expected: bundled,
+                found,
+            };
+            return Err(in_file(&mismatch).into());
+        }
+        report.games
+    } else {
+        results::read(&text, format).map_err(|e| in_file(&e))?
+    };
+    if let Some(algorithm) = algorithm {
+        records.retain(|record| record.algorithm == algorithm);
+    }
+    Ok(records)
+}
+
+fn report(command: ReportCommand) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        ReportCommand::Compare {
+            before,
+            after,
+            algorithm,
+            top,
+        } => {
+            let a = read_results(&before, algorithm.as_deref())?;
+            let b = read_results(&after, algorithm.as_deref())?;
+            let comparison = results::compare(&a, &b)?;
+            let mean = |records: &[results::GameRecord]| {
+                let scores: Vec<_> = records.iter().filter_map(|r| r.score()).collect();
+                scores.iter().sum::<usize>() as f64 / scores.len().max(1) as f64
+            };
+            let solved =
+                |records: &[results::GameRecord]| records.iter().filter(|r| r.solved).count();
+            println!(
+                "before: {} games, {} solved, average {:.4}",
+                a.len(),
+                solved(&a),
+                mean(&a)
+            );
+            println!(
+                "after:  {} games, {} solved, average {:.4}",
+                b.len(),
+                solved(&b),
+                mean(&b)
+            );
+            println!(
+                "change over {} answers solved by both: {:+.4} guesses (95% CI {:+.4} to {:+.4})",
+                comparison
+                    .changes
+                    .iter()
+                    .filter(|c| c.delta().is_some())
+                    .count(),
+                comparison.mean_delta,
+                comparison.mean_delta - comparison.confidence,
+                comparison.mean_delta + comparison.confidence
+            );
+            println!(
+                "{} improved, {} regressed, {} played differently",
+                comparison.improved().count(),
+                comparison.regressed().count(),
+                comparison
+                    .changes
+                    .iter()
+                    .filter(|c| c.diverged_at().is_some())
+                    .count()
+            );
+            if !comparison.only_before.is_empty() || !comparison.only_after.is_empty() {
+                println!(
+                    "only in before: {}; only in after: {}",
+                    comparison.only_before.len(),
+                    comparison.only_after.len()
+                );
+            }
+            let score =
+                |r: &results::GameRecord| r.score().map_or("X".to_string(), |n| n.to_string());
+            let biggest: Vec<_> = comparison
+                .changes
+                .iter()
+                .filter(|c| c.diverged_at().is_some())
+                .take(top)
+                .collect();
+            if !biggest.is_empty() {
+                println!("biggest changes:");
+            }
+            for change in biggest {
+                println!(
+                    "  {}: {} -> {}, from guess {}",
+                    change.after.answer,
+                    score(change.before),
+                    score(change.after),
+                    change.diverged_at().unwrap_or_default()
+                );
+                println!("    before: {}", change.before.guesses.join(" "));
+                println!("    after:  {}", change.after.guesses.join(" "));
+            }
+        }
+        ReportCommand::Tags {
+            path,
+            algorithm,
+            tags,
+            no_builtin,
+        } => {
+            let records = read_results(&path, algorithm.as_deref())?;
+            let answers: Vec<_> = records.iter().map(|r| r.answer.as_str()).collect();
+            let mut all = match no_builtin {
+                true => Tags::default(),
+                false => Tags::builtin(&answers, Dictionary::shared()),
+            };
+            if let Some(path) = tags {
+                all = all.merge(Tags::parse(&std::fs::read_to_string(path)?));
+            }
+            let scores: Vec<_> = records.iter().filter_map(|r| r.score()).collect();
+            let mean = scores.iter().sum::<usize>() as f64 / scores.len().max(1) as f64;
+            println!(
+                "all answers: {} games, {} solved, average {:.4}",
+                records.len(),
+                scores.len(),
+                mean
+            );
+            println!(
+                "{:<20} {:>6} {:>6} {:>8} {:>8} {:>6}",
+                "tag", "games", "solved", "mean", "vs all", "6 or X"
+            );
+            for stats in results::by_tag(&records, &all) {
+                println!(
+                    "{:<20} {:>6} {:>6} {:>8.4} {:>+8.4} {:>6}",
+                    stats.tag,
+                    stats.games,
+                    stats.solved,
+                    stats.mean,
+                    stats.mean - mean,
+                    stats.close_calls
+                );
+            }
+        }
+        ReportCommand::Hardest {
+            path,
+            algorithm,
+            count,
+            output,
+        } => {
+            let records = read_results(&path, algorithm.as_deref())?;
+            let mut algorithms: Vec<_> = records.iter().map(|r| r.algorithm.as_str()).collect();
+            algorithms.sort_unstable();
+            algorithms.dedup();
+            let hardest = results::hardest(&records, count);
+            let mut text = format!(
+                "# The {} answers {} found hardest out of {} games, hardest first, from\n\
+                 # `wordle_solver report hardest`. bench --hard-only plays just these, as a quick\n\
+                 # check; regenerate it from a full run when a better algorithm comes along.\n",
+                hardest.len(),
+                algorithms.join(" and "),
+                records.len()
+            );
+            for record in hardest {
+                text.push_str(&format!("{}\n", record.answer));
+            }
+            match output {
+                Some(path) => std::fs::write(path, text)?,
+                None => print!("{}", text),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn dict(command: DictCommand) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        DictCommand::Build {
+            words,
+            counts,
+            length,
+            min_count,
+            missing_count,
+            exclude,
+            output,
+        } => {
+            let counts = dict::parse_counts(&std::fs::read_to_string(counts)?)?;
+            let exclude = match exclude {
+                Some(path) => std::fs::read_to_string(path)?
+                    .lines()
+                    .map(|word| word.trim().to_lowercase())
+                    .collect(),
+                None => Vec::new(),
+            };
+            let options = dict::BuildOptions {
+                length,
+                min_count,
+                missing_count,
+                exclude,
+            };
+            let dictionary = dict::build(&std::fs::read_to_string(words)?, &counts, &options);
+            match output {
+                Some(path) => std::fs::write(path, dictionary.to_string())?,
+                None => print!("{}", dictionary),
+            }
+        }
+        DictCommand::Merge {
+            a,
+            b,
+            length,
+            output,
+        } => {
+            let a = read_dictionary(&a, false, length)?;
+            let b = read_dictionary(&b, false, length)?;
+            let merged = a.merge(&b);
+            match output {
+                Some(path) => std::fs::write(path, merged.to_string())?,
+                None => print!("{}", merged),
+            }
+        }
+        DictCommand::Diff { old, new, length } => {
+            let old = read_dictionary(&old, false, length)?;
+            let new = read_dictionary(&new, false, length)?;
+            let diff = old.diff(&new);
+            print!("{}", diff);
+            eprintln!(
+                "{} added, {} removed, {} changed",
+                diff.added.len(),
+                diff.removed.len(),
+                diff.changed.len()
+            );
+        }
+        DictCommand::Openers { output } => {
+            let mut openers = Vec::new();
+            for configuration in openers::CONFIGURATIONS {
+                let opener = openers::compute(configuration).expect("every configuration computes");
+                eprintln!("{}: {}", configuration, opener);
+                openers.push((configuration, opener));
+            }
+            let table = openers::write(&openers);
+            match output {
+                Some(path) => std::fs::write(path, table)?,
+                None => print!("{}", table),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn dataset(
+    format: Format,
+    width: usize,
+    memory: Memory,
+    mut out: impl Write,
+) -> std::io::Result<()> {
+    let blocklist = Blocklist::default();
+    let tree = algorithms::Optimal::official(
+        Some(width),
+        &blocklist,
+        GuessPolicy::Strict,
+        memory.table_limit(),
+    );
+    memory.report(&blocklist, GuessPolicy::Strict);
+    let answers: Vec<_> = GAMES.split_whitespace().collect();
+    if let Format::Csv = format {
+        writeln!(
+            out,
+            "round,remaining,greens,present,absent,not_here1,not_here2,not_here3,not_here4,not_here5,history,guess"
+        )?;
+    }
+    // every answer walks through the same early states, so only write each state once
+    let mut seen = HashSet::new();
+    for &answer in &answers {
+        let (history, _) = tree.replay(answer);
+        for round in 0..history.len() {
+            let state = &history[..round];
+            let key: Vec<_> = state
+                .iter()
+                .map(|g| format!("{}={}", g.word, mask_to_cmw(&g.mask)))
+                .collect();
+            let key = key.join(" ");
+            if !seen.insert(key.clone()) {
+                continue;
+            }
+            let f = Features::new(state, &answers);
+            let greens: String = f.greens.iter().map(|g| g.map_or('.', char::from)).collect();
+            let not_here: Vec<_> = f.not_here.iter().map(|&set| letters(set)).collect();
+            let guess = &history[round].word;
+            match format {
+                Format::Csv => writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{}",
+                    f.round,
+                    f.remaining,
+                    greens,
+                    letters(f.present),
+                    letters(f.absent),
+                    not_here.join(","),
+                    key,
+                    guess
+                )?,
+                Format::Ndjson => writeln!(
+                    out,
+                    r#"{{"round":{},"remaining":{},"greens":"{}","present":"{}","absent":"{}","not_here":["{}"],"history":"{}","guess":"{}"}}"#,
+                    f.round,
+                    f.remaining,
+                    greens,
+                    letters(f.present),
+                    letters(f.absent),
+                    not_here.join(r#"",""#),
+                    key,
+                    guess
+                )?,
+            }
+        }
+    }
+    out.flush()
+}
+
+// A saved file, as long as its header says it's the right kind and was made with the word lists
+// in use (and under `rules`, if they matter).
+fn read_artifact(
+    path: &Path,
+    kind: Kind,
+    rules: Option<Rules>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let text = std::fs::read_to_string(path)?;
+    let in_file = |e: HeaderError| format!("{}: {}", path.display(), e);
+    let found = Header::read(&text).map_err(in_file)?;
+    let rules = rules
+        .or(found.map(|header| header.rules))
+        .unwrap_or_default();
+    Header::new(kind, rules, WordlistHash::bundled())
+        .check(&text)
+        .map_err(in_file)?;
+    Ok(text)
+}
+
+fn read_tree(path: &Path, rules: Option<Rules>) -> Result<Tree, Box<dyn std::error::Error>> {
+    Ok(Tree::import(&read_artifact(path, Kind::Tree, rules)?)?)
+}
+
+fn selfcheck() -> Result<(), Box<dyn std::error::Error>> {
+    let mut checks = wordle_solver::selfcheck::run();
+    // hard.txt is the binary's, so the library doesn't know about it
+    let answers: HashSet<_> = GAMES.split_whitespace().collect();
+    let strays: Vec<_> = hard_answers()
+        .into_iter()
+        .filter(|word| !answers.contains(word))
+        .collect();
+    checks.push(wordle_solver::selfcheck::Check {
+        name: "every hard answer is an answer",
+        problem: (!strays.is_empty()).then(|| format!("{} aren't", strays.join(", "))),
+    });
+    let failed = checks
+        .iter()
+        .filter(|check| check.problem.is_some())
+        .count();
+    for check in &checks {
+        match &check.problem {
+            None => println!("ok    {}", check.name),
+            Some(problem) => println!("FAIL  {}: {}", check.name, problem),
+        }
+    }
+    match failed {
+        0 => Ok(()),
+        n => Err(format!("{} of {} checks failed", n, checks.len()).into()),
+    }
+}
+
+fn verify_tree(
+    path: &Path,
+    claimed_average: Option<f64>,
+    claimed_max: Option<usize>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // checking a tree works the same whatever rules it was made for
+    let tree = read_tree(path, None)?;
+    let mut total = 0;
+    let mut worst = 0;
+    let mut broken = 0;
+    let answers: Vec<_> = GAMES.split_whitespace().collect();
+    for &answer in &answers {
+        let (history, won) = tree.replay(answer);
+        if won {
+            total += history.len();
+            worst = worst.max(history.len());
+            continue;
+        }
+        broken += 1;
+        eprintln!("the tree does not solve '{}':", answer);
+        for guess in &history {
+            eprintln!("  {} {}", guess.word, guess.mask.to_compact_string());
+        }
+        eprintln!("  (no next guess for this mask)");
+    }
+    if broken != 0 {
+        return Err(format!("the tree fails on {} of {} answers", broken, answers.len()).into());
+    }
+
+    let average = total as f64 / answers.len() as f64;
+    println!(
+        "solves all {} answers in {} guesses (average {:.4}, max {})",
+        answers.len(),
+        total,
+        average,
+        worst
+    );
+    if let Some(claimed) = claimed_average {
+        if (claimed - average).abs() >= 0.00005 {
+            return Err(format!("claimed average {:.4}, but got {:.4}", claimed, average).into());
+        }
+    }
+    if let Some(claimed) = claimed_max {
+        if claimed != worst {
+            return Err(format!("claimed max {}, but got {}", claimed, worst).into());
+        }
+    }
+    Ok(())
+}
+
+fn bench(mut args: Bench, config: &Config, style: Style) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(BenchCommand::Replay {
+        answer,
+        from,
+        algorithm,
+    }) = args.command.take()
+    {
+        return replay_game(&answer, &from, algorithm.as_deref(), style);
+    }
+    let parse_implementation = |name: &str| {
+        Implementation::from_str(name, true)
+            .map_err(|_| format!("unknown algorithm '{}' in the config", name))
+    };
+    let mut implementations = Vec::new();
+    if let Some(name) = &args.profile {
+        let profile = config
+            .profiles
+            .get(name)
+            .ok_or_else(|| format!("no profile named '{}' in the config", name))?;
+        args.answers = args.answers.or_else(|| profile.answers.clone());
+        args.max = args.max.or(profile.sample);
+        args.output.extend(profile.outputs.iter().cloned());
+        for name in &profile.algorithms {
+            implementations.push(parse_implementation(name)?);
+        }
+    }
+    // fill in whatever wasn't given on the command line from the config
+    if args.implementation.is_none() && implementations.is_empty() {
+        if let Some(name) = &config.algorithm {
+            args.implementation = Some(parse_implementation(name)?);
+        }
+    }
+    let beam_width = args.beam_width.or(config.beam_width).unwrap_or(4);
+    args.scorer = args.scorer.with_config(config)?;
+    if args.openers.is_empty() {
+        args.openers.extend(config.opener.clone());
+    }
+    args.blocklist = args.blocklist.or_else(|| config.blocklist.clone());
+    args.variants = args.variants.or_else(|| config.variants.clone());
+
+    if args.tree.is_some() {
+        implementations = vec![Implementation::Optimal];
+    } else if let Some(implementation) = args.implementation {
+        implementations = vec![implementation];
+    } else if implementations.is_empty() {
+        implementations.push(Implementation::Naive);
+    }
+    let optimal = implementations
+        .iter()
+        .any(|i| matches!(i, Implementation::Optimal));
+    if args.dashboard && !std::io::stdout().is_terminal() {
+        return Err("--dashboard redraws in place, so it needs stdout to be a terminal".into());
+    }
+    // check these before spending ages on the games
+    let outputs = args
+        .output
+        .iter()
+        .map(|path| match results::Format::from_path(path) {
+            Some(results::Format::Parquet) if !cfg!(feature = "arrow") => Err(format!(
+                "can't save {}, since saving parquet needs the arrow feature",
+                path.display()
+            )),
+            Some(format) => Ok((path, format)),
+            None => Err(format!(
+                "can't tell what format to save {} in; use .csv, .ndjson, .json or .parquet",
+                path.display()
+            )),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    // the guessers and the game hold on to this for the rest of the program
+    let dictionary: Option<&'static Dictionary> = match &args.dictionary {
+        Some(path) => {
+            if implementations
+                .iter()
+                .any(|i| !matches!(i, Implementation::Greedy))
+            {
+                return Err("only the greedy implementation can use another dictionary".into());
+            }
+            let dictionary = read_dictionary(path, args.skip_bad_lines, 5)?;
+            Some(Box::leak(Box::new(dictionary)))
+        }
+        None => None,
+    };
+    if args.top_answers.is_some()
+        && implementations
+            .iter()
+            .any(|i| !matches!(i, Implementation::Naive | Implementation::Greedy))
+    {
+        return Err("only the naive and greedy implementations can take --top-answers".into());
+    }
+    if optimal && !args.openers.is_empty() {
+        return Err("the optimal implementation can't change its opener".into());
+    }
+    if optimal && matches!(args.last_guess, Risk::Win) {
+        return Err("the optimal implementation can only follow its own tree".into());
+    }
+    let blocklist_text = match &args.blocklist {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => String::new(),
+    };
+    let blocklist = Blocklist::parse(&blocklist_text);
+    // the bandit hands these out for the rest of the program
+    let openers: Vec<&'static str> = args
+        .openers
+        .iter()
+        .map(|opener| &*Box::leak(opener.clone().into_boxed_str()))
+        .collect();
+    let objective = match args.objective {
+        Goal::Average => algorithms::Objective::Average,
+        Goal::WithinSix => algorithms::Objective::WithinSix,
+    };
+    // the guessers hold on to these for the rest of the program
+    let variants_text: Option<&'static str> = match &args.variants {
+        Some(path) => Some(Box::leak(std::fs::read_to_string(path)?.into_boxed_str())),
+        None => None,
+    };
+    let variants = match variants_text {
+        Some(text) => Variants::parse(text)?,
+        None => Variants::bundled(),
+    };
+    let spelling = args.spelling.map(|dialect| match dialect {
+        Dialect::British => Spelling::British,
+        Dialect::American => Spelling::American,
+    });
+    let policy = GuessPolicy::from(args.guesses);
+    let answers_only = policy == GuessPolicy::AnswersOnly;
+    let mut wordle = Wordle::new().with_policy(policy);
+    if let Some(dictionary) = dictionary {
+        wordle = wordle.with_dictionary(dictionary.0.keys().map(String::as_str));
+    }
+    let mut answers: Vec<&'static str> = match &args.answers {
+        Some(path) => {
+            if optimal {
+                return Err("the optimal implementation only knows the bundled answers".into());
+            }
+            Box::leak(std::fs::read_to_string(path)?.into_boxed_str())
+                .split_whitespace()
+                .collect()
+        }
+        None if args.hard_only => hard_answers(),
+        None => GAMES.split_whitespace().collect(),
+    };
+    answers.truncate(args.max.unwrap_or(usize::MAX));
+    if let Some(record) = &args.replaying {
+        answers = vec![Box::leak(record.answer.clone().into_boxed_str())];
+    }
+    if let Some(spelling) = spelling {
+        if optimal {
+            return Err("the optimal implementation only knows the bundled answers".into());
+        }
+        wordle = wordle.with_variants(&variants);
+        for answer in &mut answers {
+            *answer = variants.normalize(answer, spelling);
+        }
+    }
+
+    // what the guessers could take a last shot at, going by the same words and rules they play by
+    let risk = algorithms::RiskMode::from(args.last_guess);
+    let likely: Vec<(&'static str, u64)> = match risk {
+        algorithms::RiskMode::Score => Vec::new(),
+        algorithms::RiskMode::Win => {
+            let words: &'static Dictionary =
+                dictionary.unwrap_or_else(|| Box::leak(Box::new(Dictionary::bundled())));
+            let official: HashSet<_> = GAMES.split_whitespace().collect();
+            words
+                .0
+                .iter()
+                .map(|(word, &count)| (word.as_str(), count))
+                .filter(|(word, _)| !answers_only || official.contains(word))
+                .filter(|&(word, _)| spelling.is_none_or(|s| variants.normalize(word, s) == word))
+                .collect()
+        }
+    };
+
+    // saved with everything written, so it can't be read back against other word lists or rules
+    let wordlist = match dictionary {
+        Some(dictionary) => {
+            WordlistHash::of(dictionary, &GAMES.split_whitespace().collect::<Vec<_>>())
+        }
+        None => WordlistHash::bundled(),
+    };
+    let rules = Rules { policy };
+    let cache_header = Header::new(Kind::Cache, rules, wordlist);
+    let mut records = Vec::new();
+    for &implementation in &implementations {
+        let name = implementation
+            .to_possible_value()
+            .map_or_else(String::new, |value| value.get_name().to_string());
+        if implementations.len() > 1 {
+            println!("{}:", name);
+        }
+        let mut bandit =
+            (!openers.is_empty()).then(|| algorithms::Bandit::new(&openers, objective));
+        let dashboard = || args.dashboard.then(|| Dashboard::new(answers.len()));
+        let replay = replay_flags(&args, &name, beam_width);
+        // lots of games go through the same states, so the guessers share what they work out
+        // (and with later runs, given somewhere to keep it)
+        let settings = format!(
+            "{:?} {} {:?} {:?} {:?}",
+            implementation, beam_width, args.scorer, spelling, policy
+        );
+        let fingerprint = algorithms::StateCache::fingerprint([
+            &settings,
+            &blocklist_text,
+            variants_text.unwrap_or_default(),
+        ]);
+        let cache_path = args
+            .cache_dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}-{:016x}.cache", name, fingerprint)));
+        let cache = match &cache_path {
+            Some(path) if path.exists() => {
+                let text = std::fs::read_to_string(path)?;
+                let loaded = match cache_header.check(&text) {
+                    Ok(()) => {
+                        algorithms::StateCache::load(&text, fingerprint).map_err(|e| e.to_string())
+                    }
+                    Err(e) => Err(e.to_string()),
+                };
+                match loaded {
+                    Ok(cache) => cache,
+                    Err(e) => {
+                        eprintln!("not using the cache in {}: {}", path.display(), e);
+                        algorithms::StateCache::new()
+                    }
+                }
+            }
+            _ => algorithms::StateCache::new(),
+        };
+        // every game gets a guesser of its own, and they count the guesses they didn't have to
+        // score here
+        let shortcuts = algorithms::Shortcuts::new();
+        let played = match implementation {
+            Implementation::Naive => play(
+                || {
+                    let naive = args.scorer.naive();
+                    let naive = match spelling {
+                        Some(spelling) => naive.with_variants(&variants, spelling),
+                        None => naive,
+                    };
+                    let naive = if answers_only {
+                        naive.answers_only()
+                    } else {
+                        naive
+                    };
+                    let naive = match args.top_answers {
+                        Some(n) => naive.top_answers(n),
+                        None => naive,
+                    };
+                    naive
+                        .with_blocklist(&blocklist)
+                        .with_shortcuts(shortcuts.clone())
+                },
+                &wordle,
+                &answers,
+                bandit.as_mut(),
+                (risk, &likely),
+                dashboard(),
+            ),
+            Implementation::Beam => play(
+                || {
+                    let beam = algorithms::Beam::new(beam_width);
+                    let beam = match spelling {
+                        Some(spelling) => beam.with_variants(&variants, spelling),
+                        None => beam,
+                    };
+                    let beam = if answers_only {
+                        beam.answers_only()
+                    } else {
+                        beam
+                    };
+                    beam.with_blocklist(&blocklist)
+                        .with_cache(cache.clone())
+                        .with_shortcuts(shortcuts.clone())
+                },
+                &wordle,
+                &answers,
+                bandit.as_mut(),
+                (risk, &likely),
+                dashboard(),
+            ),
+            Implementation::Greedy => {
+                let mk = || {
+                    let greedy = match dictionary {
+                        Some(dictionary) => algorithms::Greedy::with_words(
+                            args.scorer.scorer(),
+                            dictionary
+                                .0
+                                .iter()
+                                .map(|(word, &count)| (word.as_str(), count)),
+                        )
+                        .with_prior(&*args.scorer.prior()),
+                        None => args.scorer.greedy(),
+                    };
+                    let greedy = match spelling {
+                        Some(spelling) => greedy.with_variants(&variants, spelling),
+                        None => greedy,
+                    };
+                    let greedy = if answers_only {
+                        greedy.answers_only()
+                    } else {
+                        greedy
+                    };
+                    let greedy = match args.top_answers {
+                        Some(n) => greedy.top_answers(n),
+                        None => greedy,
+                    };
+                    greedy
+                        .with_blocklist(&blocklist)
+                        .with_cache(cache.clone())
+                        .with_shortcuts(shortcuts.clone())
+                };
+                // the opener is slow to work out and the same every game (unless the bandit is
+                // picking them), and the bundled one will do unless the words are different
+                let bundled = args.scorer.bundled_opener(answers_only).filter(|opener| {
+                    dictionary.is_none()
+                        && spelling.is_none()
+                        && args.top_answers.is_none()
+                        && !blocklist.contains(opener)
+                });
+                let opener = openers.is_empty().then(|| match bundled {
+                    Some(opener) => opener.to_string(),
+                    None => {
+                        timings::time(timings::Phase::Precompute, || mk().guess(&History::new()))
+                    }
+                });
+                play(
+                    || match &opener {
+                        Some(opener) => mk().with_opener(opener.clone()),
+                        None => mk(),
+                    },
+                    &wordle,
+                    &answers,
+                    bandit.as_mut(),
+                    (risk, &likely),
+                    dashboard(),
+                )
+            }
+            Implementation::Optimal => {
+                let tree = match &args.tree {
+                    Some(path) => read_tree(path, Some(Rules { policy }))?,
+                    None => {
+                        let tree = algorithms::Optimal::official(
+                            Some(args.optimal_width),
+                            &blocklist,
+                            policy,
+                            args.memory.table_limit(),
+                        );
+                        args.memory.report(&blocklist, policy);
+                        tree
+                    }
+                };
+                let tree = Rc::new(tree);
+                play(
+                    || algorithms::Optimal::new(Rc::clone(&tree)),
+                    &wordle,
+                    &answers,
+                    None,
+                    (algorithms::RiskMode::Score, &[]),
+                    dashboard(),
+                )
+            }
+        };
+        if !cache.is_empty() {
+            let (hits, misses) = cache.stats();
+            println!(
+                "knew {} states, and reused them {} times out of {}",
+                cache.len(),
+                hits,
+                hits + misses
+            );
+            if let Some(path) = &cache_path {
+                std::fs::create_dir_all(path.parent().expect("joined onto a directory"))?;
+                let mut out = BufWriter::new(std::fs::File::create(path)?);
+                writeln!(out, "{}", cache_header)?;
+                cache.save(fingerprint, out)?;
+            }
+        }
+        let (one, two) = shortcuts.counts();
+        if one + two > 0 {
+            println!(
+                "guessed without scoring {} times with one word left, and {} times with two",
+                one, two
+            );
+        }
+        if let Some(bandit) = bandit {
+            println!("openers, best first:");
+            for (opener, games, reward) in bandit.ranking() {
+                println!(
+                    "  {} (played {} times, mean reward {:.4})",
+                    opener, games, reward
+                );
+            }
+        }
+        let played: Vec<_> = played
+            .into_iter()
+            .map(|(answer, guesses, solved)| {
+                let history = History::from_answer(answer, guesses.iter().map(String::as_str));
+                let mut left: Vec<_> = GAMES.split_whitespace().collect();
+                let remaining = history
+                    .iter()
+                    .map(|guess| {
+                        left.retain(|word| guess.matches(word));
+                        left.len()
+                    })
+                    .collect();
+                // the bandit picks each game's opener, which is then the one to stick to
+                let mut replay = replay.clone();
+                if let (false, Some(opener)) = (openers.is_empty(), guesses.first()) {
+                    replay.extend(["--openers".to_string(), opener.clone()]);
+                }
+                results::GameRecord {
+                    algorithm: name.clone(),
+                    answer: answer.to_string(),
+                    guesses,
+                    solved,
+                    remaining,
+                    replay,
+                }
+            })
+            .collect();
+        println!("answers left after each guess:");
+        println!(
+            "{:>7} {:>6} {:>9} {:>7} {:>6}",
+            "round", "games", "mean", "median", "p95"
+        );
+        for round in results::candidate_curve(&played) {
+            println!(
+                "{:>7} {:>6} {:>9.2} {:>7} {:>6}",
+                round.round, round.games, round.mean, round.median, round.p95
+            );
+        }
+        let replaying = args.replaying.as_ref();
+        if replaying.is_some() || matches!(args.on_failure, Some(OnFailure::Trace)) {
+            // what the guessers are choosing between, which isn't always the answers played
+            let candidates: Vec<_> = GAMES.split_whitespace().collect();
+            let traced =
+                |r: &&results::GameRecord| replaying.is_some() || r.score().is_none_or(|n| n >= 6);
+            for record in played.iter().filter(traced) {
+                let history =
+                    History::from_answer(&record.answer, record.guesses.iter().map(String::as_str));
+                let outcome = match record.score() {
+                    Some(n) => format!("solved in {}", n),
+                    None => "failed".to_string(),
+                };
+                println!("\n'{}' ({}):", record.answer, outcome);
+                print!(
+                    "{}",
+                    Transcript::annotate(&history, &candidates).styled(style)
+                );
+            }
+        }
+        if let (Some(saved), Some(record)) = (replaying, played.first()) {
+            if saved.guesses == record.guesses {
+                println!("\nsame guesses as the saved game");
+            } else {
+                println!("\nthe saved game went {} instead", saved.guesses.join(" "));
+            }
+        }
+        records.extend(played);
+    }
+    for (path, format) in outputs {
+        let mut out = BufWriter::new(std::fs::File::create(path)?);
+        // JSON and Parquet have no comments, so the header goes in the report instead
+        let report = || results::RunReport {
+            rules: Some(rules.to_string()),
+            wordlist: Some(wordlist.to_string()),
+            ..results::RunReport::new(records.clone())
+        };
+        match format {
+            results::Format::Json => {
+                results::write_report(&report(), out)?;
+                continue;
+            }
+            results::Format::Parquet => {
+                results::write_parquet(&report(), out)?;
+                continue;
+            }
+            _ => {}
+        }
+        writeln!(out, "{}", Header::new(Kind::Results, rules, wordlist))?;
+        results::write(&records, format, out)?;
+    }
+    Ok(())
+}
+
+// the bundled hard.txt, hardest first
+fn hard_answers() -> Vec<&'static str> {
+    HARD.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+}
+
+// The flags that make bench play a game the way this run plays it (short of the opener, which the
+// bandit picks game by game), with everything the config or environment filled in spelled out.
+fn replay_flags(args: &Bench, implementation: &str, beam_width: usize) -> Vec<String> {
+    fn name(value: impl ValueEnum) -> String {
+        value
+            .to_possible_value()
+            .map_or_else(String::new, |value| value.get_name().to_string())
+    }
+    // relative paths would only work from the same directory
+    let path = |path: &Path| {
+        std::path::absolute(path)
+            .unwrap_or_else(|_| path.to_path_buf())
+            .display()
+            .to_string()
+    };
+    let scorer = args.scorer;
+    let mut flags = vec![
+        "--implementation".to_string(),
+        implementation.to_string(),
+        "--beam-width".to_string(),
+        beam_width.to_string(),
+        "--optimal-width".to_string(),
+        args.optimal_width.to_string(),
+        "--scorer".to_string(),
+        name(scorer.scorer),
+        "--entropy-weight".to_string(),
+        scorer.entropy_weight.to_string(),
+        "--prior-weight".to_string(),
+        scorer.prior_weight.to_string(),
+        "--worst-case-weight".to_string(),
+        scorer.worst_case_weight.to_string(),
+        "--guesses".to_string(),
+        name(args.guesses),
+        "--last-guess".to_string(),
+        name(args.last_guess),
+    ];
+    if scorer.answer_bonus {
+        flags.push("--answer-bonus".to_string());
+    }
+    if let Some(prior) = scorer.prior {
+        flags.extend(["--prior".to_string(), name(prior)]);
+    }
+    if args.skip_bad_lines {
+        flags.push("--skip-bad-lines".to_string());
+    }
+    if let Some(spelling) = args.spelling {
+        flags.extend(["--spelling".to_string(), name(spelling)]);
+    }
+    if let Some(n) = args.top_answers {
+        flags.extend(["--top-answers".to_string(), n.to_string()]);
+    }
+    let paths = [
+        ("--dictionary", &args.dictionary),
+        ("--tree", &args.tree),
+        ("--blocklist", &args.blocklist),
+        ("--variants", &args.variants),
+    ];
+    for (flag, value) in paths {
+        if let Some(value) = value {
+            flags.extend([flag.to_string(), path(value)]);
+        }
+    }
+    flags
+}
+
+// Play one saved game again from the flags saved with it, leaving out the config and the
+// environment (which the flags already account for), and trace it whether or not it failed.
+fn replay_game(
+    answer: &str,
+    from: &Path,
+    algorithm: Option<&str>,
+    style: Style,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let records = read_results(from, algorithm)?;
+    let mut games = records.into_iter().filter(|record| record.answer == answer);
+    let record = games
+        .next()
+        .ok_or_else(|| format!("{} has no game for '{}'", from.display(), answer))?;
+    if games.next().is_some() {
+        return Err(format!(
+            "{} has more than one game for '{}'; pick an algorithm with --algorithm",
+            from.display(),
+            answer
+        )
+        .into());
+    }
+    if record.replay.is_empty() {
+        return Err(format!(
+            "{} doesn't say how its games were played; only runs saved as ndjson or json can be \
+             replayed",
+            from.display()
+        )
+        .into());
+    }
+    let command = Cli::command();
+    let subcommand = command
+        .find_subcommand("bench")
+        .expect("bench is a subcommand");
+    for var in subcommand.get_arguments().filter_map(|arg| arg.get_env()) {
+        std::env::remove_var(var);
+    }
+    let argv = ["wordle_solver", "bench"].map(String::from);
+    let cli = Cli::try_parse_from(argv.into_iter().chain(record.replay.iter().cloned()))?;
+    let Command::Bench(mut args) = cli.command else {
+        unreachable!("parsed as bench");
+    };
+    println!("replaying with {}", record.replay.join(" "));
+    args.replaying = Some(record);
+    bench(*args, &Config::default(), style)
+}
+
+// Every game is played once per set of rules that changes how it's played (hard mode and which
+// guesses are allowed). The number of rounds doesn't, since no algorithm plays any differently
+// for having fewer guesses left, so each limit just cuts the same games off at a different point.
+fn sweep(args: Sweep, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    let implementation = match (args.implementation, &config.algorithm) {
+        (Some(implementation), _) => implementation,
+        (None, Some(name)) => Implementation::from_str(name, true)
+            .map_err(|_| format!("unknown algorithm '{}' in the config", name))?,
+        (None, None) => Implementation::Naive,
+    };
+    if !matches!(
+        implementation,
+        Implementation::Naive | Implementation::Greedy
+    ) {
+        return Err("only the naive and greedy implementations can be swept".into());
+    }
+    let scoring = args.scorer.with_config(config)?;
+    let answers: Vec<&'static str> = GAMES
+        .split_whitespace()
+        .take(args.max.unwrap_or(usize::MAX))
+        .collect();
+    println!(
+        "{:<5} {:<13} {:>6} {:>8} {:>8}",
+        "hard", "guesses", "rounds", "solved", "average"
+    );
+    for &hard in &args.hard {
+        for &policy in &args.guesses {
+            let policy = GuessPolicy::from(policy);
+            let mut wordle = Wordle::new().with_policy(policy);
+            if hard == Toggle::On {
+                wordle = wordle.with_hard_mode();
+            }
+            let answers_only = policy == GuessPolicy::AnswersOnly;
+            let mut mk: Box<dyn FnMut() -> Box<dyn Guesser>> = match implementation {
+                Implementation::Naive => Box::new(move || {
+                    let mut naive = scoring.naive();
+                    if answers_only {
+                        naive = naive.answers_only();
+                    }
+                    if hard == Toggle::On {
+                        naive = naive.hard_mode();
+                    }
+                    Box::new(naive)
+                }),
+                _ => {
+                    let greedy = move || {
+                        let mut greedy = scoring.greedy();
+                        if answers_only {
+                            greedy = greedy.answers_only();
+                        }
+                        if hard == Toggle::On {
+                            greedy = greedy.hard_mode();
+                        }
+                        greedy
+                    };
+                    // the opener doesn't depend on the answer, and hard mode doesn't limit it
+                    let opener = match scoring.bundled_opener(answers_only) {
+                        Some(opener) => opener.to_string(),
+                        None => greedy().guess(&History::new()),
+                    };
+                    Box::new(move || Box::new(greedy().with_opener(opener.clone())))
+                }
+            };
+            // how many guesses each game took to win, if it was won at all
+            let mut scores = Vec::new();
+            for &answer in &answers {
+                let game = wordle.play_out(answer, mk())?;
+                scores.push((game.status() == Status::Won).then(|| game.history().len()));
+            }
+            for &rounds in &args.rounds {
+                let won: Vec<_> = scores.iter().flatten().filter(|&&n| n <= rounds).collect();
+                let average = won.iter().copied().sum::<usize>() as f64 / won.len().max(1) as f64;
+                println!(
+                    "{:<5} {:<13} {:>6} {:>7.2}% {:>8.4}",
+                    format!("{:?}", hard).to_lowercase(),
+                    Rules { policy }.to_string(),
+                    rounds,
+                    100.0 * won.len() as f64 / answers.len().max(1) as f64,
+                    average
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+// Most answers fall under a handful of masks after the opener, so those get a section each
+// saying what to play and what every mask after that leaves; the long tail of masks that leave
+// only a few answers share one table at the end that just lists them.
+fn cheatsheet(
+    args: Cheatsheet,
+    config: &Config,
+    style: Style,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let implementation = match (args.implementation, &config.algorithm) {
+        (Some(implementation), _) => implementation,
+        (None, Some(name)) => Implementation::from_str(name, true)
+            .map_err(|_| format!("unknown algorithm '{}' in the config", name))?,
+        (None, None) => Implementation::Naive,
+    };
+    let beam_width = args.beam_width.or(config.beam_width).unwrap_or(4);
+    let scorer = args.scorer.with_config(config)?;
+    let mut guesser = analysis_guesser(implementation, beam_width, scorer)?;
+    let opener = match args.opener.or_else(|| config.opener.clone()) {
+        Some(opener) if Word::new(&opener).is_none() => {
+            return Err(format!("'{}' isn't a five letter word", opener).into())
+        }
+        Some(opener) => opener,
+        None => guesser.guess(&History::new()),
+    };
+    let answers: Vec<_> = GAMES.split_whitespace().collect();
+    // the squares for one mask, which print the same on paper as on screen
+    let squares = |word: &str, mask: Mask| {
+        let word = Word::new(word).expect("only words get played");
+        style.grid(&[Guess { word, mask }]).trim_end().to_string()
+    };
+    let shout = |word: &str| word.to_uppercase();
+
+    let outcomes = analysis::breakdown(&opener, &answers);
+    let (sections, rest): (Vec<_>, Vec<_>) = outcomes
+        .iter()
+        .filter(|outcome| !outcome.answers.is_empty() && !outcome.mask.is_win())
+        .partition(|outcome| outcome.answers.len() >= args.min_answers.max(2));
+    let covered: usize = sections.iter().map(|outcome| outcome.answers.len()).sum();
+    println!(
+        "# Wordle cheatsheet: open with {}
+",
+        shout(&opener)
+    );
+    println!(
+        "The sections cover {} of the {} answers ({:.1}%), most likely first. Find the row for \
+         your second mask to see what's left; the table at the end lists the answers behind \
+         every other mask.",
+        covered,
+        answers.len(),
+        100.0 * covered as f64 / answers.len() as f64
+    );
+    for outcome in sections {
+        let history = History::from(vec![Guess {
+            word: opener.parse()?,
+            mask: outcome.mask,
+        }]);
+        let next = guesser.guess(&history);
+        println!(
+            "\n## {} {} answers ({:.1}%): play {}\n",
+            squares(&opener, outcome.mask),
+            outcome.answers.len(),
+            100.0 * outcome.probability,
+            shout(&next)
+        );
+        println!("| then | left | play |");
+        println!("|---|---|---|");
+        for then in analysis::breakdown(&next, &outcome.answers) {
+            let play = match then.answers[..] {
+                [] => continue,
+                _ if then.mask.is_win() => "solved".to_string(),
+                [answer] => shout(answer),
+                [_, _] | [_, _, _] => then.answers.join(", "),
+                _ => {
+                    let mut history = history.clone();
+                    history.push(Guess {
+                        word: next.parse()?,
+                        mask: then.mask,
+                    });
+                    shout(&guesser.guess(&history))
+                }
+            };
+            println!(
+                "| {} | {} | {} |",
+                squares(&next, then.mask),
+                then.answers.len(),
+                play
+            );
+        }
+    }
+    if !rest.is_empty() {
+        println!("\n## Every other mask\n");
+        println!("| after {} | answers |", shout(&opener));
+        println!("|---|---|");
+        for outcome in rest {
+            println!(
+                "| {} | {} |",
+                squares(&opener, outcome.mask),
+                outcome.answers.join(", ")
+            );
+        }
+    }
+    Ok(())
+}
+
+// Every implementation plays the same answers with the same random rolls for each answer and
+// round (see `Noise::for_game`), so the comparisons after the table are paired game by game.
+// The inconsistent column is how many games showed the algorithm masks that contradict each
+// other, which is as much as it could have noticed on its own.
+fn noise(mut args: Noise, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    if args.implementation.contains(&Implementation::Optimal) {
+        return Err("the optimal implementation can only follow its own tree".into());
+    }
+    if let Some(rate) = args.rate.iter().find(|rate| !(0.0..=1.0).contains(*rate)) {
+        return Err(format!("the rate {} isn't between 0 and 1", rate).into());
+    }
+    let beam_width = args.beam_width.or(config.beam_width).unwrap_or(4);
+    args.scorer = args.scorer.with_config(config)?;
+    let answers: Vec<&'static str> = GAMES
+        .split_whitespace()
+        .take(args.max.unwrap_or(usize::MAX))
+        .collect();
+    println!(
+        "{:<8} {:>6} {:>8} {:>8} {:>10} {:>12}",
+        "", "rate", "solved", "average", "corrupted", "inconsistent"
+    );
+    // for each implementation and rate, how many guesses each answer was solved in
+    let mut solved: Vec<Vec<Vec<Option<usize>>>> = Vec::new();
+    for (i, &implementation) in args.implementation.iter().enumerate() {
+        // the greedy opener doesn't depend on the answer, so it's only worked out once
+        let opener = (implementation == Implementation::Greedy)
+            .then(|| analysis_guesser(implementation, beam_width, args.scorer))
+            .transpose()?
+            .map(|mut greedy| greedy.guess(&History::new()));
+        let seed = match args.independent {
+            true => args.seed.wrapping_add(i as u64),
+            false => args.seed,
+        };
+        let mut by_rate = Vec::new();
+        for &rate in &args.rate {
+            let (mut won, mut guesses, mut corrupted, mut inconsistent) = (0, 0, 0, 0);
+            let mut games = Vec::new();
+            for &answer in &answers {
+                let mut noise = wordle_solver::noise::Noise::for_game(rate, seed, answer);
+                let guesser: Box<dyn Guesser> = match &opener {
+                    Some(opener) => Box::new(args.scorer.greedy().with_opener(opener.clone())),
+                    None => analysis_guesser(implementation, beam_width, args.scorer)?,
+                };
+                let game = wordle_solver::noise::play(answer, guesser, &mut noise, args.rounds);
+                if game.won {
+                    won += 1;
+                    guesses += game.guesses.len();
+                }
+                games.push(game.won.then_some(game.guesses.len()));
+                corrupted += game.corrupted;
+                inconsistent += usize::from(game.inconsistent);
+            }
+            println!(
+                "{:<8} {:>6} {:>7.2}% {:>8.4} {:>10} {:>12}",
+                format!("{:?}", implementation).to_lowercase(),
+                rate,
+                100.0 * won as f64 / answers.len().max(1) as f64,
+                guesses as f64 / won.max(1) as f64,
+                corrupted,
+                inconsistent
+            );
+            by_rate.push(games);
+        }
+        solved.push(by_rate);
+    }
+
+    // each game's result minus the first implementation's on the same answer
+    let name = |i: usize| format!("{:?}", args.implementation[i]).to_lowercase();
+    for i in 1..solved.len() {
+        println!();
+        println!("{} against {}, game by game:", name(i), name(0));
+        for (r, &rate) in args.rate.iter().enumerate() {
+            let diffs: Vec<f64> = solved[i][r]
+                .iter()
+                .zip(&solved[0][r])
+                .map(|(b, a)| f64::from(u8::from(b.is_some())) - f64::from(u8::from(a.is_some())))
+                .collect();
+            let n = diffs.len().max(1) as f64;
+            let mean = diffs.iter().sum::<f64>() / n;
+            let variance =
+                diffs.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / (n - 1.0).max(1.0);
+            // only games both solved say anything about how many guesses it took
+            let both: Vec<f64> = solved[i][r]
+                .iter()
+                .zip(&solved[0][r])
+                .filter_map(|(&b, &a)| Some(b? as f64 - a? as f64))
+                .collect();
+            println!(
+                "  rate {}: {:+.2}% solved (standard error {:.2}), {:+.4} guesses when both solved it",
+                rate,
+                100.0 * mean,
+                100.0 * (variance / n).sqrt(),
+                both.iter().sum::<f64>() / both.len().max(1) as f64
+            );
+        }
+    }
+    Ok(())
+}
+
+// plays every answer, and hands back each one's guesses and whether it was solved
+fn play<G>(
+    mut mk: impl FnMut() -> G,
+    w: &Wordle,
+    answers: &[&'static str],
+    mut bandit: Option<&mut algorithms::Bandit>,
+    (risk, likely): (algorithms::RiskMode, &[(&'static str, u64)]),
+    mut dashboard: Option<Dashboard>,
+) -> Vec<(&'static str, Vec<String>, bool)>
+where
+    G: Guesser,
+{
+    let mut score = 0;
+    let mut games = 0;
+    let mut played = Vec::new();
+    // with the dashboard up, anything else printed would end up drawn over
+    let mut held = Vec::new();
+    let width = platform::terminal_width();
+    let mut stdout = std::io::stdout();
+    for &answer in answers {
+        if let Some(dashboard) = &mut dashboard {
+            dashboard.start(answer);
+            // a broken dashboard isn't worth stopping the run for
+            let _ = dashboard.tick(&mut stdout, width);
+        }
+        let guesser = algorithms::LastGuess::new((mk)(), risk, likely);
+        let result = match bandit.as_deref_mut() {
+            Some(bandit) => {
+                let arm = bandit.choose();
+                let result = w.play_out(answer, bandit.guesser(arm, guesser));
+                let won = |game: &Game| game.status() == Status::Won;
+                bandit.record(
+                    arm,
+                    result
+                        .as_ref()
+                        .ok()
+                        .filter(|game| won(game))
+                        .map(|game| game.history().len()),
+                );
+                result
+            }
+            None => w.play_out(answer, guesser),
+        };
+        let solved_in = match result {
+            Ok(game) => {
+                let guesses: Vec<_> = game.history().words().map(String::from).collect();
+                let solved = game.status() == Status::Won;
+                let solved_in = solved.then_some(guesses.len());
+                if solved {
+                    games += 1;
+                    score += guesses.len();
+                    if dashboard.is_none() {
+                        println!("guessed '{}' in {}", answer, guesses.len());
+                    }
+                } else {
+                    held.push(format!("failed to guess '{}'", answer));
+                }
+                played.push((answer, guesses, solved));
+                solved_in
+            }
+            Err(e) => {
+                held.push(format!("failed to guess '{}': {}", answer, e));
+                None
+            }
+        };
+        match &mut dashboard {
+            Some(dashboard) => {
+                dashboard.record(solved_in);
+                let _ = dashboard.tick(&mut stdout, width);
+            }
+            None => held.drain(..).for_each(|line| eprintln!("{}", line)),
+        }
+    }
+    if let Some(dashboard) = &mut dashboard {
+        let _ = dashboard.finish(&mut stdout, width);
+    }
+    for line in held {
+        eprintln!("{}", line);
+    }
+    if games != 0 {
+        println!("average score: {:.4}", score as f64 / games as f64);
+    }
+    played
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cli() {
+        // catches clashing flags and the like, which clap otherwise only reports when they're used
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn hard_answers_are_answers() {
+        // hard.txt has to be regenerated if the answers change
+        let answers: HashSet<_> = GAMES.split_whitespace().collect();
+        let hard = super::hard_answers();
+        assert!(!hard.is_empty());
+        assert!(hard.iter().all(|word| answers.contains(word)));
+    }
 
-fn main() {
-    let w = wordle_solver::Wordle::new();
-    for answer in GAMES.split_whitespace() {
-        let guesser = wordle_solver::algorithms::Naive::new();
-        w.play(answer, guesser);
+    #[test]
+    fn replay() {
+        let bench = |flags: &[String]| {
+            let argv = ["wordle_solver", "bench"].map(String::from);
+            match Cli::parse_from(argv.into_iter().chain(flags.iter().cloned())).command {
+                Command::Bench(args) => *args,
+                _ => unreachable!("parsed as bench"),
+            }
+        };
+        let given = [
+            "--scorer",
+            "composite",
+            "--answer-bonus",
+            "--spelling",
+            "british",
+            "--top-answers",
+            "500",
+            "--prior",
+            "sigmoid",
+        ];
+        let flags = replay_flags(&bench(&given.map(String::from)), "greedy", 3);
+        // what's saved parses back as the same settings
+        assert_eq!(replay_flags(&bench(&flags), "greedy", 3), flags);
+        assert!(flags
+            .windows(2)
+            .any(|pair| pair == ["--spelling", "british"]));
+        assert!(flags.contains(&"--answer-bonus".to_string()));
     }
 }
@@ -0,0 +1,159 @@
+// Games where some of the masks the guesser is shown are wrong, like when someone mistypes or
+// misreads the colors, for seeing how well the algorithms cope. The game itself still knows the
+// answer, so guessing it wins whatever the guesser was told along the way.
+
+use crate::core::stable_hash;
+use crate::{Correctness, Guess, Guesser, History, Mask, Word};
+
+pub struct Noise {
+    // the chance of each mask being wrong
+    rate: f64,
+    // xorshift64 state
+    rng: u64,
+}
+
+impl Noise {
+    pub fn new(rate: f64, seed: u64) -> Self {
+        Self {
+            rate: rate.clamp(0.0, 1.0),
+            // xorshift gets stuck on zero
+            rng: seed.max(1),
+        }
+    }
+
+    // Rolls for one game of `answer`, the same ones for every guesser given the same seed. That
+    // makes comparisons between guessers paired: each answer gets the same mistakes in the same
+    // rounds whoever plays it, so the difference in how they did comes down to the guessers and
+    // not the luck of the draw.
+    pub fn for_game(rate: f64, seed: u64, answer: &str) -> Self {
+        Self::new(rate, seed ^ stable_hash([answer]))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng
+    }
+
+    // The mask as the guesser gets to see it: with the chance `rate`, one square comes out as
+    // one of the other two colors. Also says whether it was changed. Every call takes the same
+    // number of rolls, so round n of a game always gets the same ones.
+    pub fn corrupt(&mut self, mut mask: Mask) -> (Mask, bool) {
+        // the top 53 bits as a fraction in [0, 1)
+        let roll = (self.next() >> 11) as f64 / (1u64 << 53) as f64;
+        let square = (self.next() % 5) as usize;
+        let other = (self.next() % 2) as usize;
+        if roll >= self.rate {
+            return (mask, false);
+        }
+        let others = match mask[square] {
+            Correctness::Correct => [Correctness::Misplaced, Correctness::Wrong],
+            Correctness::Misplaced => [Correctness::Correct, Correctness::Wrong],
+            Correctness::Wrong => [Correctness::Correct, Correctness::Misplaced],
+        };
+        mask[square] = others[other];
+        (mask, true)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoisyGame {
+    pub guesses: Vec<String>,
+    pub won: bool,
+    // how many of the masks the guesser was shown were wrong
+    pub corrupted: usize,
+    // whether what the guesser was shown contradicts itself (see `History::check`), which is
+    // the only way it could have noticed without a word list
+    pub inconsistent: bool,
+}
+
+// Play `answer` out with the guesser seeing masks run through `noise`, for at most
+// `max_guesses` guesses. A guess that isn't five lowercase letters ends the game as a loss.
+pub fn play(
+    answer: &str,
+    mut guesser: impl Guesser,
+    noise: &mut Noise,
+    max_guesses: usize,
+) -> NoisyGame {
+    let mut shown = History::new();
+    let mut game = NoisyGame {
+        guesses: Vec::new(),
+        won: false,
+        corrupted: 0,
+        inconsistent: false,
+    };
+    while game.guesses.len() < max_guesses {
+        let word = guesser.guess(&shown);
+        let Some(packed) = Word::new(&word) else {
+            break;
+        };
+        game.guesses.push(word.clone());
+        if word == answer {
+            game.won = true;
+            break;
+        }
+        let (mask, corrupted) = noise.corrupt(Correctness::compute(answer, &word));
+        game.corrupted += usize::from(corrupted);
+        shown.push(Guess { word: packed, mask });
+    }
+    game.inconsistent = shown.check().is_err();
+    game
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MaskExt;
+
+    #[test]
+    fn corrupt() {
+        let mask = Correctness::compute("right", "tares");
+        let mut never = Noise::new(0.0, 1);
+        assert_eq!(never.corrupt(mask), (mask, false));
+        let mut always = Noise::new(1.0, 1);
+        for _ in 0..20 {
+            let (noisy, changed) = always.corrupt(mask);
+            assert!(changed);
+            let differences = noisy.iter().zip(&mask).filter(|(a, b)| a != b).count();
+            assert_eq!(differences, 1);
+        }
+        // a win can be misread too, which is why the game checks the guess itself
+        assert!(!always.corrupt([Correctness::Correct; 5]).0.is_win());
+
+        // the same answer gets the same rolls, round by round, whatever the masks were
+        let (mut a, mut b) = (
+            Noise::for_game(0.5, 3, "right"),
+            Noise::for_game(0.5, 3, "right"),
+        );
+        for round in 0..20 {
+            let first = a.corrupt(mask).1;
+            let other = Correctness::compute("right", ["fight", "wrong"][round % 2]);
+            assert_eq!(b.corrupt(other).1, first);
+        }
+    }
+
+    #[test]
+    fn noisy_games() {
+        let clean = play(
+            "right",
+            crate::algorithms::Naive::new(),
+            &mut Noise::new(0.0, 1),
+            6,
+        );
+        assert!(clean.won);
+        assert_eq!((clean.corrupted, clean.inconsistent), (0, false));
+        // every mask is wrong, but the guesser keeps going rather than giving up
+        let noisy = play(
+            "right",
+            crate::algorithms::Naive::new(),
+            &mut Noise::new(1.0, 7),
+            6,
+        );
+        assert_eq!(
+            noisy.corrupted,
+            noisy.guesses.len() - usize::from(noisy.won)
+        );
+        assert!(noisy.guesses.len() == 6 || noisy.won);
+    }
+}
@@ -0,0 +1,147 @@
+// The first guess for each way of playing that always starts the same, worked out ahead of time
+// and bundled (see openers.txt), so starting a game doesn't mean scoring the whole dictionary
+// against itself. The table is tied to the bundled word lists by their hash, and a table made
+// with other lists is ignored rather than trusted.
+
+use std::sync::OnceLock;
+
+use crate::algorithms::{
+    Beam, Entropy, ExpectedLength, ExpectedSize, Frequency, Greedy, Naive, Scorer, WorstCase,
+};
+use crate::dict::WordlistHash;
+use crate::timings::{self, Phase};
+use crate::{Guesser, History};
+
+const OPENERS: &str = include_str!("../openers.txt");
+
+// every configuration the table has an opener for, in the order it's written
+pub const CONFIGURATIONS: [&str; 13] = [
+    "greedy:entropy",
+    "greedy:expected-size",
+    "greedy:worst-case",
+    "greedy:expected-length",
+    "greedy:frequency",
+    "greedy:entropy:answers-only",
+    "greedy:expected-size:answers-only",
+    "greedy:worst-case:answers-only",
+    "greedy:expected-length:answers-only",
+    "greedy:frequency:answers-only",
+    "naive",
+    "naive:answers-only",
+    // the default width
+    "beam:4",
+];
+
+// The bundled opener for `configuration`, like "greedy:entropy", "greedy:worst-case:answers-only"
+// or "beam:4", if there is one.
+pub fn bundled(configuration: &str) -> Option<&'static str> {
+    static TABLE: OnceLock<Vec<(&'static str, &'static str)>> = OnceLock::new();
+    let table = TABLE.get_or_init(|| match parse(OPENERS) {
+        Some((hash, table)) if hash == WordlistHash::bundled() => table,
+        _ => Vec::new(),
+    });
+    table
+        .iter()
+        .find(|&&(c, _)| c == configuration)
+        .map(|&(_, opener)| opener)
+}
+
+// the word list hash the bundled table says it was made with, whether or not it's these
+pub fn bundled_hash() -> Option<WordlistHash> {
+    parse(OPENERS).map(|(hash, _)| hash)
+}
+
+// the word list hash the table was made with, and the table
+fn parse(text: &str) -> Option<(WordlistHash, Vec<(&str, &str)>)> {
+    let mut hash = None;
+    let mut table = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line.split_once(' ')?;
+        match key {
+            "words" => hash = Some(WordlistHash(u64::from_str_radix(value, 16).ok()?)),
+            _ => table.push((key, value)),
+        }
+    }
+    Some((hash?, table))
+}
+
+// Work the opener for `configuration` out from scratch, which takes a while. This is what the
+// bundled table is made from.
+pub fn compute(configuration: &str) -> Option<String> {
+    let mut guesser: Box<dyn Guesser> = match configuration.split_once(':') {
+        None if configuration == "naive" => Box::new(Naive::new().without_bundled_opener()),
+        Some(("naive", "answers-only")) => {
+            Box::new(Naive::new().answers_only().without_bundled_opener())
+        }
+        Some(("beam", width)) => {
+            let width = width.parse().ok().filter(|&width| width > 0)?;
+            Box::new(Beam::new(width).without_bundled_opener())
+        }
+        Some(("greedy", rest)) => Box::new(greedy(rest)?),
+        _ => return None,
+    };
+    Some(timings::time(Phase::Precompute, || {
+        guesser.guess(&History::new())
+    }))
+}
+
+// greedy with the scorer named in `rest`, like "entropy" or "worst-case:answers-only"
+fn greedy(rest: &str) -> Option<Greedy<Box<dyn Scorer>>> {
+    let (scorer, answers_only) = match rest.split_once(':') {
+        Some((scorer, "answers-only")) => (scorer, true),
+        Some(_) => return None,
+        None => (rest, false),
+    };
+    let scorer: Box<dyn Scorer> = match scorer {
+        "entropy" => Box::new(Entropy),
+        "expected-size" => Box::new(ExpectedSize),
+        "worst-case" => Box::new(WorstCase),
+        "expected-length" => Box::new(ExpectedLength),
+        "frequency" => Box::new(Frequency),
+        _ => return None,
+    };
+    let greedy = Greedy::new(scorer);
+    Some(if answers_only {
+        greedy.answers_only()
+    } else {
+        greedy
+    })
+}
+
+// the table as openers.txt holds it
+pub fn write(openers: &[(&str, String)]) -> String {
+    let mut out = String::from(
+        "# The first guess for each configuration, from `wordle_solver dict openers`. Regenerate\n\
+         # it after changing the bundled word lists; the hash below is theirs.\n",
+    );
+    out.push_str(&format!("words {}\n", WordlistHash::bundled()));
+    for (configuration, opener) in openers {
+        out.push_str(&format!("{} {}\n", configuration, opener));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_is_current() {
+        let (hash, table) = parse(OPENERS).unwrap();
+        // openers.txt has to be regenerated when the word lists change
+        assert_eq!(hash, WordlistHash::bundled());
+        let configurations: Vec<_> = table.iter().map(|&(c, _)| c).collect();
+        assert_eq!(configurations, CONFIGURATIONS);
+        for configuration in CONFIGURATIONS {
+            let opener = bundled(configuration).unwrap();
+            assert!(crate::Word::new(opener).is_some());
+        }
+        assert_eq!(bundled("greedy:magic"), None);
+        assert_eq!(compute("greedy:magic"), None);
+        assert_eq!(compute("beam:entropy"), None);
+        assert_eq!(compute("naive:hard"), None);
+    }
+}
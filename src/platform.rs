@@ -0,0 +1,133 @@
+// The few things that work differently on Unix, Windows and WASI: where files go, whether the
+// terminal takes colors, and how wide it is. Everything here falls back to something plain
+// rather than failing, since WASI in particular often has no terminal or home directory at all.
+
+use std::io::IsTerminal;
+use std::path::PathBuf;
+
+// the environment, as a function so the decisions below can be tested without changing it
+type Env<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+fn env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+// Where the config file goes: $XDG_CONFIG_HOME (or ~/.config) on Unix and %APPDATA% on Windows,
+// followed by wordle_solver.
+pub fn config_dir() -> Option<PathBuf> {
+    config_dir_from(&env, cfg!(windows))
+}
+
+fn config_dir_from(env: Env, windows: bool) -> Option<PathBuf> {
+    let base = match env("XDG_CONFIG_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None if windows => PathBuf::from(env("APPDATA")?),
+        None => PathBuf::from(env("HOME")?).join(".config"),
+    };
+    Some(base.join("wordle_solver"))
+}
+
+// Where things that can be worked out again go: $XDG_CACHE_HOME (or ~/.cache) on Unix and
+// %LOCALAPPDATA% on Windows, followed by wordle_solver.
+pub fn cache_dir() -> Option<PathBuf> {
+    cache_dir_from(&env, cfg!(windows))
+}
+
+fn cache_dir_from(env: Env, windows: bool) -> Option<PathBuf> {
+    let base = match env("XDG_CACHE_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None if windows => PathBuf::from(env("LOCALAPPDATA")?),
+        None => PathBuf::from(env("HOME")?).join(".cache"),
+    };
+    Some(base.join("wordle_solver"))
+}
+
+// Whether to color what goes to stdout. NO_COLOR (https://no-color.org) always wins, then
+// FORCE_COLOR, and otherwise it has to be a terminal that understands ANSI codes: anything but
+// TERM=dumb on Unix, and on Windows the terminals that say so (the old console doesn't).
+pub fn color() -> bool {
+    color_from(&env, std::io::stdout().is_terminal(), cfg!(windows))
+}
+
+fn color_from(env: Env, terminal: bool, windows: bool) -> bool {
+    if env("NO_COLOR").is_some() {
+        return false;
+    }
+    if env("FORCE_COLOR").is_some() {
+        return true;
+    }
+    if !terminal || env("TERM").as_deref() == Some("dumb") {
+        return false;
+    }
+    if windows {
+        // Windows Terminal, ConEmu, and anything that sets TERM (like mintty)
+        return env("WT_SESSION").is_some()
+            || env("ConEmuANSI").as_deref() == Some("ON")
+            || env("TERM").is_some();
+    }
+    true
+}
+
+// How many columns the terminal has. There's no portable way to ask it, so this goes by
+// $COLUMNS (which most shells keep up to date) and assumes 80 otherwise.
+pub fn terminal_width() -> usize {
+    terminal_width_from(&env)
+}
+
+fn terminal_width_from(env: Env) -> usize {
+    env("COLUMNS")
+        .and_then(|columns| columns.trim().parse().ok())
+        .filter(|&columns| columns > 0)
+        .unwrap_or(80)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    #[test]
+    fn dirs() {
+        let unix = env(&[("HOME", "/home/me")]);
+        assert_eq!(
+            config_dir_from(&unix, false),
+            Some(PathBuf::from("/home/me/.config/wordle_solver"))
+        );
+        assert_eq!(
+            cache_dir_from(&unix, false),
+            Some(PathBuf::from("/home/me/.cache/wordle_solver"))
+        );
+        let windows = env(&[("APPDATA", "C:/Users/me/AppData/Roaming")]);
+        assert_eq!(
+            config_dir_from(&windows, true),
+            Some(PathBuf::from("C:/Users/me/AppData/Roaming/wordle_solver"))
+        );
+        // nowhere to put it, as under WASI
+        assert_eq!(config_dir_from(&env(&[]), false), None);
+    }
+
+    #[test]
+    fn color() {
+        assert!(color_from(&env(&[]), true, false));
+        assert!(!color_from(&env(&[]), false, false));
+        assert!(!color_from(&env(&[("NO_COLOR", "1")]), true, false));
+        assert!(color_from(&env(&[("FORCE_COLOR", "1")]), false, false));
+        assert!(!color_from(&env(&[("TERM", "dumb")]), true, false));
+        assert!(!color_from(&env(&[]), true, true));
+        assert!(color_from(&env(&[("WT_SESSION", "x")]), true, true));
+    }
+
+    #[test]
+    fn width() {
+        assert_eq!(terminal_width_from(&env(&[("COLUMNS", "120")])), 120);
+        assert_eq!(terminal_width_from(&env(&[("COLUMNS", "wide")])), 80);
+        assert_eq!(terminal_width_from(&env(&[])), 80);
+    }
+}
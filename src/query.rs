@@ -0,0 +1,166 @@
+use std::fmt;
+
+use crate::core::bits;
+use crate::dict::Dictionary;
+
+// Something known about the word from outside a normal game, like a crossword.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Constraint {
+    // letters known at some positions, from a pattern like "s_a_e"
+    Pattern([Option<u8>; 5]),
+    // letters that aren't anywhere in the word (a bitmask with bit 0 for 'a')
+    Absent(u32),
+    // the word has to be made from these letters (how many of each, from 'a'), each used at
+    // most once, and with `all` every one of them used, as in an anagram
+    LetterBank { counts: [u8; 26], all: bool },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePatternError(pub String);
+
+impl fmt::Display for ParsePatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "'{}' isn't a pattern of five letters and blanks like s_a_e",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParsePatternError {}
+
+impl Constraint {
+    // blanks can be written as '_', '.' or '?'
+    pub fn pattern(pattern: &str) -> Result<Self, ParsePatternError> {
+        let err = || ParsePatternError(pattern.to_string());
+        if pattern.chars().count() != 5 {
+            return Err(err());
+        }
+        let mut known = [None; 5];
+        for (k, c) in known.iter_mut().zip(pattern.chars()) {
+            *k = match c {
+                '_' | '.' | '?' => None,
+                c if c.is_ascii_alphabetic() => Some(c.to_ascii_lowercase() as u8),
+                _ => return Err(err()),
+            };
+        }
+        Ok(Self::Pattern(known))
+    }
+
+    // anything that isn't a letter is ignored
+    pub fn absent(letters: &str) -> Self {
+        Self::Absent(
+            letters
+                .bytes()
+                .filter(u8::is_ascii_alphabetic)
+                .fold(0, |set, b| set | 1 << (b.to_ascii_lowercase() - b'a')),
+        )
+    }
+
+    // anything that isn't a letter is ignored
+    pub fn letter_bank(letters: &str, all: bool) -> Self {
+        let mut counts = [0u8; 26];
+        for b in letters.bytes().filter(u8::is_ascii_alphabetic) {
+            let count = &mut counts[(b.to_ascii_lowercase() - b'a') as usize];
+            *count = count.saturating_add(1);
+        }
+        Self::LetterBank { counts, all }
+    }
+
+    pub fn matches(&self, word: &str) -> bool {
+        let word = word.as_bytes();
+        match self {
+            Self::Pattern(known) => known
+                .iter()
+                .zip(word)
+                .all(|(k, &b)| k.is_none_or(|k| k == b)),
+            Self::Absent(set) => word
+                .iter()
+                .all(|b| !b.is_ascii_lowercase() || set & 1 << (b - b'a') == 0),
+            Self::LetterBank { counts, all } => {
+                let mut left = *counts;
+                for &b in word {
+                    if !b.is_ascii_lowercase() || left[(b - b'a') as usize] == 0 {
+                        return false;
+                    }
+                    left[(b - b'a') as usize] -= 1;
+                }
+                !all || left.iter().all(|&n| n == 0)
+            }
+        }
+    }
+}
+
+// A word that fits every constraint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion<'d> {
+    pub word: &'d str,
+    // how common the word is
+    pub count: u64,
+    // how well guessing it would tell the completions apart, in bits
+    pub bits: f64,
+}
+
+// Every word in the dictionary that fits all the constraints, most common first.
+pub fn complete<'d>(dictionary: &'d Dictionary, constraints: &[Constraint]) -> Vec<Completion<'d>> {
+    let fits: Vec<(&str, u64)> = dictionary
+        .0
+        .iter()
+        .filter(|(word, _)| constraints.iter().all(|c| c.matches(word)))
+        .map(|(word, &count)| (word.as_str(), count))
+        .collect();
+    let mut completions: Vec<_> = fits
+        .iter()
+        .map(|&(word, count)| Completion {
+            word,
+            count,
+            bits: bits(&fits, word),
+        })
+        .collect();
+    completions.sort_by(|a, b| b.count.cmp(&a.count).then(a.word.cmp(b.word)));
+    completions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constraints() {
+        let pattern = Constraint::pattern("s_A_e").unwrap();
+        assert!(pattern.matches("snake"));
+        assert!(!pattern.matches("snore"));
+        assert!(Constraint::pattern("s_a_").is_err());
+        assert!(Constraint::pattern("s_a_1").is_err());
+        let absent = Constraint::absent("n, k");
+        assert!(!absent.matches("snake"));
+        assert!(absent.matches("stave"));
+    }
+
+    #[test]
+    fn letter_bank() {
+        let bank = Constraint::letter_bank("earthx", false);
+        assert!(bank.matches("heart"));
+        assert!(bank.matches("earth"));
+        assert!(!bank.matches("treat"));
+        let anagram = Constraint::letter_bank("earth", true);
+        assert!(anagram.matches("heart"));
+        assert!(!Constraint::letter_bank("earthx", true).matches("heart"));
+    }
+
+    #[test]
+    fn complete() {
+        let dictionary =
+            Dictionary::parse("snake 10\nstave 5\nshave 20\nspace 1\nslate 30\n").unwrap();
+        let constraints = [
+            Constraint::pattern("s_a_e").unwrap(),
+            Constraint::absent("n"),
+        ];
+        let words: Vec<_> = super::complete(&dictionary, &constraints)
+            .iter()
+            .map(|c| c.word)
+            .collect();
+        assert_eq!(words, ["slate", "shave", "stave", "space"]);
+    }
+}
@@ -0,0 +1,783 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::{self, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::tags::Tags;
+
+// The version of the JSON and NDJSON layouts. It goes up when a field changes meaning or goes
+// away; adding a field doesn't need it, since readers skip fields they don't know.
+pub const SCHEMA_VERSION: u32 = 1;
+
+// How one benchmark game went, for saving a run to look at or compare later.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameRecord {
+    pub algorithm: String,
+    pub answer: String,
+    // every guess played, in order
+    pub guesses: Vec<String>,
+    pub solved: bool,
+    // how many answers were still possible after each guess; empty when it wasn't recorded,
+    // which csv never is
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remaining: Vec<usize>,
+    // The bench flags that play this game again the same way, with whatever came from the
+    // config or the environment spelled out (nothing a benchmark does is random, so that's all
+    // it takes). Empty when it wasn't recorded, which csv never is.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replay: Vec<String>,
+}
+
+impl GameRecord {
+    // how many guesses it took, or None if it wasn't solved
+    pub fn score(&self) -> Option<usize> {
+        self.solved.then_some(self.guesses.len())
+    }
+}
+
+// A whole run in one document, as saved in .json files.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunReport {
+    pub schema_version: u32,
+    // what the run was played under, as in `artifact::Header` (which a JSON file can't start
+    // with)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rules: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wordlist: Option<String>,
+    pub games: Vec<GameRecord>,
+}
+
+impl RunReport {
+    pub fn new(games: Vec<GameRecord>) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            rules: None,
+            wordlist: None,
+            games,
+        }
+    }
+}
+
+// a line of an NDJSON file, which carries the version on every line so each stands on its own
+#[derive(Serialize, Deserialize)]
+struct Line<G> {
+    // lines from before there was a version are the same as version 1
+    #[serde(default = "first_version")]
+    schema_version: u32,
+    #[serde(flatten)]
+    game: G,
+}
+
+fn first_version() -> u32 {
+    1
+}
+
+fn check_version(version: u32) -> Result<(), String> {
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "written by a newer version (schema {}, but this reads up to {})",
+            version, SCHEMA_VERSION
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    // algorithm,answer,solved,guesses with the guesses separated by spaces
+    Csv,
+    // one JSON record per line
+    Ndjson,
+    // a `RunReport`
+    Json,
+    // a column per field of `GameRecord`, for runs too big for the others; this needs the arrow
+    // feature, and can only be written
+    Parquet,
+}
+
+impl Format {
+    // going by the file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "csv" => Some(Self::Csv),
+            "ndjson" | "jsonl" => Some(Self::Ndjson),
+            "json" => Some(Self::Json),
+            "parquet" => Some(Self::Parquet),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseResultsError {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseResultsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl std::error::Error for ParseResultsError {}
+
+// a .json file; the line in errors is where in the file the JSON went wrong
+pub fn read_report(text: &str) -> Result<RunReport, ParseResultsError> {
+    let report: RunReport = serde_json::from_str(text).map_err(|e| ParseResultsError {
+        line: e.line(),
+        reason: e.to_string(),
+    })?;
+    check_version(report.schema_version).map_err(|reason| ParseResultsError { line: 1, reason })?;
+    Ok(report)
+}
+
+// read back what `write` wrote, skipping lines that start with # (like a word list hash)
+pub fn read(text: &str, format: Format) -> Result<Vec<GameRecord>, ParseResultsError> {
+    match format {
+        Format::Json => return Ok(read_report(text)?.games),
+        Format::Parquet => {
+            return Err(ParseResultsError {
+                line: 0,
+                reason: "parquet files can only be written".to_string(),
+            })
+        }
+        _ => {}
+    }
+    let mut records = Vec::new();
+    let mut header = format == Format::Csv;
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if header {
+            header = false;
+            continue;
+        }
+        let err = |reason: String| ParseResultsError {
+            line: i + 1,
+            reason,
+        };
+        let record = match format {
+            Format::Ndjson => {
+                let line: Line<GameRecord> =
+                    serde_json::from_str(line).map_err(|e| err(e.to_string()))?;
+                check_version(line.schema_version).map_err(err)?;
+                line.game
+            }
+            Format::Csv => {
+                let fields: Vec<_> = line.split(',').collect();
+                let [algorithm, answer, solved, guesses] = fields[..] else {
+                    return Err(err(format!("expected 4 fields, got {}", fields.len())));
+                };
+                GameRecord {
+                    algorithm: algorithm.to_string(),
+                    answer: answer.to_string(),
+                    guesses: guesses.split_whitespace().map(str::to_string).collect(),
+                    solved: solved
+                        .parse()
+                        .map_err(|_| err(format!("'{}' isn't true or false", solved)))?,
+                    remaining: Vec::new(),
+                    replay: Vec::new(),
+                }
+            }
+            Format::Json | Format::Parquet => unreachable!("handled above"),
+        };
+        records.push(record);
+    }
+    Ok(records)
+}
+
+// How the games whose answers have one tag went (see `tags::Tags`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagStats {
+    pub tag: String,
+    pub games: usize,
+    pub solved: usize,
+    // guesses on average over the solved games
+    pub mean: f64,
+    // the games that took six guesses or weren't solved at all
+    pub close_calls: usize,
+}
+
+// The run broken down by the tags on its answers, hardest first (by the share of games that
+// weren't solved, then by average guesses). A game counts towards every tag its answer has, and
+// answers without tags don't show up at all.
+pub fn by_tag(records: &[GameRecord], tags: &Tags) -> Vec<TagStats> {
+    let mut by_tag: BTreeMap<&str, Vec<&GameRecord>> = BTreeMap::new();
+    for record in records {
+        for tag in tags.of(&record.answer) {
+            by_tag.entry(tag).or_default().push(record);
+        }
+    }
+    let mut stats: Vec<_> = by_tag
+        .into_iter()
+        .map(|(tag, games)| {
+            let scores: Vec<_> = games.iter().filter_map(|r| r.score()).collect();
+            TagStats {
+                tag: tag.to_string(),
+                games: games.len(),
+                solved: scores.len(),
+                mean: scores.iter().sum::<usize>() as f64 / scores.len().max(1) as f64,
+                close_calls: games
+                    .iter()
+                    .filter(|r| r.score().is_none_or(|n| n >= 6))
+                    .count(),
+            }
+        })
+        .collect();
+    // stable, so ties stay alphabetical
+    stats.sort_by(|a, b| {
+        let unsolved = |s: &TagStats| (s.games - s.solved) as f64 / s.games as f64;
+        unsolved(b)
+            .total_cmp(&unsolved(a))
+            .then(b.mean.total_cmp(&a.mean))
+    });
+    stats
+}
+
+// The `n` hardest games of a run, hardest first: the failed ones, then by guesses, then by how
+// many answers were still left along the way (so the ones that stayed open longest), and then
+// alphabetically.
+pub fn hardest(records: &[GameRecord], n: usize) -> Vec<&GameRecord> {
+    let mut games: Vec<_> = records.iter().collect();
+    games.sort_by(|a, b| {
+        let guesses = |r: &GameRecord| r.score().unwrap_or(usize::MAX);
+        let left = |r: &GameRecord| r.remaining.iter().sum::<usize>();
+        guesses(b)
+            .cmp(&guesses(a))
+            .then(left(b).cmp(&left(a)))
+            .then(a.answer.cmp(&b.answer))
+    });
+    games.truncate(n);
+    games
+}
+
+// How many answers were left after one round, over the games that got that far.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundStats {
+    // counting from 1
+    pub round: usize,
+    pub games: usize,
+    pub mean: f64,
+    pub median: usize,
+    pub p95: usize,
+}
+
+// How fast the games narrowed the answers down, round by round. Only games with the remaining
+// counts recorded count, and a game only counts for the rounds it played, so later rounds are
+// down to the harder answers.
+pub fn candidate_curve(records: &[GameRecord]) -> Vec<RoundStats> {
+    let rounds = records.iter().map(|r| r.remaining.len()).max().unwrap_or(0);
+    (0..rounds)
+        .map(|round| {
+            let mut counts: Vec<usize> = records
+                .iter()
+                .filter_map(|r| r.remaining.get(round).copied())
+                .collect();
+            counts.sort_unstable();
+            // nearest rank, so it's always one of the counts
+            let rank = |p: f64| counts[((p * counts.len() as f64).ceil() as usize).max(1) - 1];
+            RoundStats {
+                round: round + 1,
+                games: counts.len(),
+                mean: counts.iter().sum::<usize>() as f64 / counts.len() as f64,
+                median: rank(0.5),
+                p95: rank(0.95),
+            }
+        })
+        .collect()
+}
+
+// One answer that was played in both runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Change<'a> {
+    pub before: &'a GameRecord,
+    pub after: &'a GameRecord,
+}
+
+impl Change<'_> {
+    // how many more guesses the second run took, if both solved it
+    pub fn delta(&self) -> Option<i64> {
+        Some(self.after.score()? as i64 - self.before.score()? as i64)
+    }
+
+    // the first guess (counting from 1) where the runs went different ways
+    pub fn diverged_at(&self) -> Option<usize> {
+        let (a, b) = (&self.before.guesses, &self.after.guesses);
+        if a == b {
+            return None;
+        }
+        Some(a.iter().zip(b).take_while(|(a, b)| a == b).count() + 1)
+    }
+}
+
+// How a run did against an earlier one, answer by answer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison<'a> {
+    // the answers in both runs, biggest changes first
+    pub changes: Vec<Change<'a>>,
+    // answers only one of the runs played
+    pub only_before: Vec<&'a str>,
+    pub only_after: Vec<&'a str>,
+    // the mean change in guesses over the answers both runs solved, and the half-width of its
+    // 95% confidence interval
+    pub mean_delta: f64,
+    pub confidence: f64,
+}
+
+impl Comparison<'_> {
+    pub fn improved(&self) -> impl Iterator<Item = &Change<'_>> {
+        self.changes
+            .iter()
+            .filter(|c| c.delta().is_some_and(|d| d < 0) || (!c.before.solved && c.after.solved))
+    }
+
+    pub fn regressed(&self) -> impl Iterator<Item = &Change<'_>> {
+        self.changes
+            .iter()
+            .filter(|c| c.delta().is_some_and(|d| d > 0) || (c.before.solved && !c.after.solved))
+    }
+}
+
+// Each answer can only be in a run once, so pick out one algorithm first if a run has several.
+pub fn compare<'a>(
+    before: &'a [GameRecord],
+    after: &'a [GameRecord],
+) -> Result<Comparison<'a>, String> {
+    let index = |records: &'a [GameRecord]| {
+        let mut by_answer = HashMap::new();
+        for record in records {
+            if by_answer.insert(&*record.answer, record).is_some() {
+                return Err(format!("'{}' was played more than once", record.answer));
+            }
+        }
+        Ok(by_answer)
+    };
+    let (a, b) = (index(before)?, index(after)?);
+
+    let mut changes: Vec<_> = before
+        .iter()
+        .filter_map(|before| {
+            let after = b.get(&*before.answer)?;
+            Some(Change { before, after })
+        })
+        .collect();
+    // losing or gaining a win matters more than any number of guesses
+    changes.sort_by_key(|c| {
+        let flipped = c.before.solved != c.after.solved;
+        let size = c.delta().map_or(0, i64::abs);
+        (!flipped, -size, c.diverged_at().unwrap_or(usize::MAX))
+    });
+
+    let deltas: Vec<f64> = changes
+        .iter()
+        .filter_map(|c| c.delta())
+        .map(|d| d as f64)
+        .collect();
+    let n = deltas.len() as f64;
+    let mean_delta = if deltas.is_empty() {
+        0.0
+    } else {
+        deltas.iter().sum::<f64>() / n
+    };
+    let confidence = if deltas.len() < 2 {
+        f64::INFINITY
+    } else {
+        let variance = deltas.iter().map(|d| (d - mean_delta).powi(2)).sum::<f64>() / (n - 1.0);
+        1.96 * (variance / n).sqrt()
+    };
+
+    Ok(Comparison {
+        changes,
+        only_before: before
+            .iter()
+            .filter(|r| !b.contains_key(&*r.answer))
+            .map(|r| &*r.answer)
+            .collect(),
+        only_after: after
+            .iter()
+            .filter(|r| !a.contains_key(&*r.answer))
+            .map(|r| &*r.answer)
+            .collect(),
+        mean_delta,
+        confidence,
+    })
+}
+
+pub fn write_report(report: &RunReport, mut out: impl Write) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut out, report)?;
+    writeln!(out)?;
+    out.flush()
+}
+
+// How many games go in each row group of a Parquet file. Bigger groups compress better, but
+// every game in one is held in memory at once while writing.
+#[cfg(feature = "arrow")]
+const ROW_GROUP: usize = 64 * 1024;
+
+// The report's games as Parquet, with the schema version, rules and word list in the file's
+// metadata.
+#[cfg(feature = "arrow")]
+pub fn write_parquet(report: &RunReport, out: impl Write + Send) -> io::Result<()> {
+    use std::sync::Arc;
+
+    use arrow_array::builder::{BooleanBuilder, ListBuilder, StringBuilder, UInt64Builder};
+    use arrow_array::{ArrayRef, RecordBatch};
+    use arrow_schema::{DataType, Field, Schema};
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Compression;
+    use parquet::file::metadata::KeyValue;
+    use parquet::file::properties::WriterProperties;
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("algorithm", DataType::Utf8, false),
+        Field::new("answer", DataType::Utf8, false),
+        Field::new("solved", DataType::Boolean, false),
+        Field::new(
+            "guesses",
+            DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+            false,
+        ),
+        Field::new(
+            "remaining",
+            DataType::List(Arc::new(Field::new("item", DataType::UInt64, true))),
+            false,
+        ),
+        Field::new(
+            "replay",
+            DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+            false,
+        ),
+    ]));
+    let mut metadata = vec![KeyValue::new(
+        "schema_version".to_string(),
+        SCHEMA_VERSION.to_string(),
+    )];
+    metadata.extend(
+        report
+            .rules
+            .clone()
+            .map(|rules| KeyValue::new("rules".to_string(), rules)),
+    );
+    metadata.extend(
+        report
+            .wordlist
+            .clone()
+            .map(|wordlist| KeyValue::new("wordlist".to_string(), wordlist)),
+    );
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_max_row_group_size(ROW_GROUP)
+        .set_key_value_metadata(Some(metadata))
+        .build();
+    let mut writer =
+        ArrowWriter::try_new(out, schema.clone(), Some(properties)).map_err(io::Error::other)?;
+    for games in report.games.chunks(ROW_GROUP) {
+        let mut algorithm = StringBuilder::new();
+        let mut answer = StringBuilder::new();
+        let mut solved = BooleanBuilder::new();
+        let mut guesses = ListBuilder::new(StringBuilder::new());
+        let mut remaining = ListBuilder::new(UInt64Builder::new());
+        let mut replay = ListBuilder::new(StringBuilder::new());
+        for game in games {
+            algorithm.append_value(&game.algorithm);
+            answer.append_value(&game.answer);
+            solved.append_value(game.solved);
+            for guess in &game.guesses {
+                guesses.values().append_value(guess);
+            }
+            guesses.append(true);
+            for &n in &game.remaining {
+                remaining.values().append_value(n as u64);
+            }
+            remaining.append(true);
+            for arg in &game.replay {
+                replay.values().append_value(arg);
+            }
+            replay.append(true);
+        }
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(algorithm.finish()),
+            Arc::new(answer.finish()),
+            Arc::new(solved.finish()),
+            Arc::new(guesses.finish()),
+            Arc::new(remaining.finish()),
+            Arc::new(replay.finish()),
+        ];
+        let batch = RecordBatch::try_new(schema.clone(), columns).map_err(io::Error::other)?;
+        writer.write(&batch).map_err(io::Error::other)?;
+    }
+    writer.close().map_err(io::Error::other)?;
+    Ok(())
+}
+
+#[cfg(not(feature = "arrow"))]
+pub fn write_parquet(_report: &RunReport, _out: impl Write + Send) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "saving parquet needs the arrow feature",
+    ))
+}
+
+pub fn write(records: &[GameRecord], format: Format, mut out: impl Write + Send) -> io::Result<()> {
+    match format {
+        Format::Json => return write_report(&RunReport::new(records.to_vec()), out),
+        Format::Parquet => return write_parquet(&RunReport::new(records.to_vec()), out),
+        _ => {}
+    }
+    if let Format::Csv = format {
+        writeln!(out, "algorithm,answer,solved,guesses")?;
+    }
+    for record in records {
+        match format {
+            Format::Csv => writeln!(
+                out,
+                "{},{},{},{}",
+                record.algorithm,
+                record.answer,
+                record.solved,
+                record.guesses.join(" ")
+            )?,
+            Format::Ndjson => {
+                let line = Line {
+                    schema_version: SCHEMA_VERSION,
+                    game: record,
+                };
+                serde_json::to_writer(&mut out, &line)?;
+                writeln!(out)?;
+            }
+            Format::Json | Format::Parquet => unreachable!("handled above"),
+        }
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(answer: &str, guesses: &str, solved: bool) -> GameRecord {
+        GameRecord {
+            algorithm: "naive".to_string(),
+            answer: answer.to_string(),
+            guesses: guesses.split_whitespace().map(str::to_string).collect(),
+            solved,
+            remaining: Vec::new(),
+            replay: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn formats() {
+        let records = [record("crane", "tares crane", true)];
+        assert_eq!(records[0].score(), Some(2));
+
+        let mut csv = Vec::new();
+        write(&records, Format::Csv, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(
+            csv,
+            "algorithm,answer,solved,guesses\nnaive,crane,true,tares crane\n"
+        );
+        assert_eq!(read(&csv, Format::Csv), Ok(records.to_vec()));
+        let commented = format!("# wordlist 0123456789abcdef\n{}", csv);
+        assert_eq!(read(&commented, Format::Csv), Ok(records.to_vec()));
+
+        let mut ndjson = Vec::new();
+        write(&records, Format::Ndjson, &mut ndjson).unwrap();
+        let ndjson = String::from_utf8(ndjson).unwrap();
+        assert_eq!(
+            ndjson,
+            "{\"schema_version\":1,\"algorithm\":\"naive\",\"answer\":\"crane\",\"guesses\":[\"tares\",\"crane\"],\"solved\":true}\n"
+        );
+        assert_eq!(read(&ndjson, Format::Ndjson), Ok(records.to_vec()));
+
+        let mut json = Vec::new();
+        write(&records, Format::Json, &mut json).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert_eq!(read(&json, Format::Json), Ok(records.to_vec()));
+    }
+
+    #[test]
+    fn curve() {
+        let games = [
+            ("crane", "tares crane", vec![20, 1]),
+            ("humph", "tares doily mummy", vec![100, 8, 3]),
+            ("right", "tares fight", vec![30, 4]),
+        ];
+        let records: Vec<_> = games
+            .into_iter()
+            .map(|(answer, guesses, remaining)| GameRecord {
+                remaining,
+                ..record(answer, guesses, true)
+            })
+            .collect();
+        let curve = candidate_curve(&records);
+        assert_eq!(curve.len(), 3);
+        assert_eq!((curve[0].games, curve[0].mean), (3, 50.0));
+        assert_eq!((curve[0].median, curve[0].p95), (30, 100));
+        assert_eq!((curve[1].median, curve[2].games), (4, 1));
+        // remaining counts are saved when there are some
+        let mut ndjson = Vec::new();
+        write(&records, Format::Ndjson, &mut ndjson).unwrap();
+        let ndjson = String::from_utf8(ndjson).unwrap();
+        assert!(ndjson.contains("\"remaining\":[20,1]"));
+        assert_eq!(read(&ndjson, Format::Ndjson), Ok(records));
+    }
+
+    #[test]
+    fn schema_versions() {
+        // lines from before the version was saved are still read
+        let old = "{\"algorithm\":\"naive\",\"answer\":\"crane\",\"guesses\":[\"crane\"],\"solved\":true}\n";
+        assert_eq!(
+            read(old, Format::Ndjson),
+            Ok(vec![record("crane", "crane", true)])
+        );
+        let newer = old.replace("{", "{\"schema_version\":2,");
+        assert!(read(&newer, Format::Ndjson).is_err());
+        let newer = "{\"schema_version\":2,\"games\":[]}";
+        assert!(read(newer, Format::Json).is_err());
+    }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn parquet() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let report = RunReport {
+            wordlist: Some("4d109548e8502961".to_string()),
+            ..RunReport::new(vec![
+                record("crane", "tares crane", true),
+                record("humph", "tares doily mummy bumph nymph jumpy", false),
+            ])
+        };
+        let path = std::env::temp_dir().join(format!("results-{}.parquet", std::process::id()));
+        write_parquet(&report, std::fs::File::create(&path).unwrap()).unwrap();
+        let reader =
+            ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap()).unwrap();
+        let metadata = reader.metadata().file_metadata();
+        assert_eq!(metadata.num_rows(), 2);
+        let value = |key: &str| {
+            metadata
+                .key_value_metadata()?
+                .iter()
+                .find(|kv| kv.key == key)?
+                .value
+                .clone()
+        };
+        assert_eq!(value("schema_version").as_deref(), Some("1"));
+        assert_eq!(value("wordlist"), report.wordlist);
+        assert_eq!(value("rules"), None);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn replay() {
+        let records = [GameRecord {
+            replay: vec!["--implementation".to_string(), "naive".to_string()],
+            ..record("crane", "tares crane", true)
+        }];
+        let mut ndjson = Vec::new();
+        write(&records, Format::Ndjson, &mut ndjson).unwrap();
+        let ndjson = String::from_utf8(ndjson).unwrap();
+        assert!(ndjson.contains("\"replay\":[\"--implementation\",\"naive\"]"));
+        assert_eq!(read(&ndjson, Format::Ndjson), Ok(records.to_vec()));
+        // csv has nowhere to put it
+        let mut csv = Vec::new();
+        write(&records, Format::Csv, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(read(&csv, Format::Csv).unwrap()[0].replay.is_empty());
+    }
+
+    #[test]
+    fn tags() {
+        let records = [
+            record("llama", "tares llama", true),
+            record("sissy", "tares sissy", true),
+            record("fuzzy", "tares dumpy fuzzy", true),
+            record("humph", "tares doily mummy bumph nymph jumpy", false),
+        ];
+        let tags = Tags::parse("llama double\nsissy double\nfuzzy double\nhumph rare\nfjord rare");
+        let stats = by_tag(&records, &tags);
+        assert_eq!(stats.len(), 2);
+        // a failed game outweighs any number of guesses
+        assert_eq!((stats[0].tag.as_str(), stats[0].games), ("rare", 1));
+        assert_eq!((stats[0].solved, stats[0].close_calls), (0, 1));
+        assert_eq!((stats[1].tag.as_str(), stats[1].games), ("double", 3));
+        assert!((stats[1].mean - 7.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hardest() {
+        let records = [
+            GameRecord {
+                remaining: vec![20, 1, 1],
+                ..record("crane", "tares cling crane", true)
+            },
+            record("humph", "tares doily mummy bumph nymph jumpy", false),
+            record("right", "tares fight right", true),
+            record("awake", "tares awake", true),
+        ];
+        let answers = |n| {
+            super::hardest(&records, n)
+                .iter()
+                .map(|r| r.answer.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(answers(3), ["humph", "crane", "right"]);
+        assert_eq!(answers(10).len(), 4);
+    }
+
+    #[test]
+    fn format_from_path() {
+        assert_eq!(Format::from_path(Path::new("a/b.csv")), Some(Format::Csv));
+        assert_eq!(
+            Format::from_path(Path::new("b.jsonl")),
+            Some(Format::Ndjson)
+        );
+        assert_eq!(Format::from_path(Path::new("b.txt")), None);
+        assert_eq!(
+            Format::from_path(Path::new("b.parquet")),
+            Some(Format::Parquet)
+        );
+    }
+
+    #[test]
+    fn comparison() {
+        let before = [
+            record("crane", "tares crane", true),
+            record("sissy", "tares scion dimly abamp sissy", true),
+            record("humph", "tares doily humph", true),
+            record("awake", "tares awake", true),
+        ];
+        let after = [
+            record("crane", "tares crane", true),
+            record("sissy", "tares noily spasm sissy", true),
+            record("humph", "tares doily mummy bumph nymph jumpy", false),
+            record("rebut", "tares rebut", true),
+        ];
+        let comparison = compare(&before, &after).unwrap();
+        // the lost game first, then the biggest change in guesses
+        let answers: Vec<_> = comparison
+            .changes
+            .iter()
+            .map(|c| &*c.after.answer)
+            .collect();
+        assert_eq!(answers, ["humph", "sissy", "crane"]);
+        assert_eq!(comparison.changes[1].diverged_at(), Some(2));
+        assert_eq!(comparison.changes[2].diverged_at(), None);
+        assert_eq!(comparison.regressed().count(), 1);
+        assert_eq!(comparison.improved().count(), 1);
+        assert_eq!(comparison.mean_delta, -0.5);
+        assert_eq!(
+            (comparison.only_before, comparison.only_after),
+            (vec!["awake"], vec!["rebut"])
+        );
+
+        assert!(compare(&before, &[before[0].clone(), before[0].clone()]).is_err());
+    }
+}
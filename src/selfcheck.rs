@@ -0,0 +1,126 @@
+// Checks that what's bundled into the build agrees with itself: the word lists parse and fit
+// together, masks come out the way the rules say, and the tables worked out ahead of time were
+// made from these word lists. Any of these going wrong makes every answer after it quietly
+// wrong, so it's worth a look after changing a word list or porting to a new platform.
+
+use crate::algorithms::answers;
+use crate::dict::{Dictionary, WordlistHash};
+use crate::{openers, Correctness, Mask};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: &'static str,
+    // what's wrong, or None if it passed
+    pub problem: Option<String>,
+}
+
+// every check, in order (a check whose input failed an earlier one is skipped)
+pub fn run() -> Vec<Check> {
+    let mut checks = Vec::new();
+    let mut check = |name, result: Result<(), String>| {
+        checks.push(Check {
+            name,
+            problem: result.err(),
+        })
+    };
+    let dictionary = Dictionary::parse_all(crate::DICTIONARY);
+    check(
+        "the dictionary parses",
+        dictionary.as_ref().map(|_| ()).map_err(ToString::to_string),
+    );
+    if let Ok(dictionary) = &dictionary {
+        check("every answer is in the dictionary", answers_in(dictionary));
+        check(
+            "the masks agree with the reference",
+            masks_agree(dictionary),
+        );
+    }
+    check("the openers were made from these words", openers_match());
+    checks
+}
+
+fn answers_in(dictionary: &Dictionary) -> Result<(), String> {
+    let missing: Vec<_> = answers()
+        .filter(|answer| !dictionary.0.contains_key(*answer))
+        .collect();
+    match missing.len() {
+        0 => Ok(()),
+        n if n > 5 => Err(format!("{} aren't, like {}", n, missing[..5].join(", "))),
+        _ => Err(format!("{} aren't", missing.join(", "))),
+    }
+}
+
+// Every answer against an even spread of a couple of hundred guesses, and against itself.
+fn masks_agree(dictionary: &Dictionary) -> Result<(), String> {
+    let step = (dictionary.0.len() / 200).max(1);
+    let guesses: Vec<_> = dictionary.0.keys().step_by(step).collect();
+    for answer in answers() {
+        for guess in guesses.iter().map(|g| g.as_str()).chain([answer]) {
+            let (mask, expected) = (
+                Correctness::compute(answer, guess),
+                reference(answer, guess),
+            );
+            if mask != expected {
+                return Err(format!(
+                    "'{}' against '{}' gives {:?} instead of {:?}",
+                    guess, answer, mask, expected
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+// The rules written out as plainly as they go: greens first, then each other letter of the guess
+// is yellow while the answer has copies of it left that aren't green or already yellow.
+fn reference(answer: &str, guess: &str) -> Mask {
+    let (answer, guess) = (answer.as_bytes(), guess.as_bytes());
+    let mut mask = [Correctness::Wrong; 5];
+    let mut left = [0u8; 256];
+    for i in 0..5 {
+        if answer[i] == guess[i] {
+            mask[i] = Correctness::Correct;
+        } else {
+            left[answer[i] as usize] += 1;
+        }
+    }
+    for i in 0..5 {
+        if mask[i] != Correctness::Correct && left[guess[i] as usize] > 0 {
+            left[guess[i] as usize] -= 1;
+            mask[i] = Correctness::Misplaced;
+        }
+    }
+    mask
+}
+
+fn openers_match() -> Result<(), String> {
+    let bundled = WordlistHash::bundled();
+    match openers::bundled_hash() {
+        Some(hash) if hash == bundled => Ok(()),
+        Some(hash) => Err(format!(
+            "openers.txt is for words {}, but these are {}",
+            hash, bundled
+        )),
+        None => Err("openers.txt doesn't parse".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_rules() {
+        // repeated letters are where mask code goes wrong
+        assert_eq!(reference("abbey", "babes"), mask![M M C C W]);
+        assert_eq!(reference("cigar", "aaaaa"), mask![W W W C W]);
+        assert_eq!(reference("llama", "lolly"), mask![C W M W W]);
+    }
+
+    #[test]
+    fn bundled_passes() {
+        for check in run() {
+            assert_eq!(check.problem, None, "{}", check.name);
+        }
+    }
+}
@@ -0,0 +1,871 @@
+// A small JSON API around the solver, for running it as a service:
+//
+//   POST /suggest                    {"history": [{"word": "tares", "mask": "BYBBG"}]}
+//   POST /sessions                   {"algorithm": "naive", "hard_mode": true, "lang": "en"}
+//   POST /sessions/<id>/guesses      {"word": "tares", "mask": "BYBBG"}
+//   GET  /sessions/<id>/suggestion
+//
+// Masks use the B/Y/G spelling of the tree format. A session can ask for any algorithm and
+// language on the server's allowlists, so one server can offer cheap suggestions to everyone and
+// expensive ones to whoever it's set up for.
+//
+// Request bodies are checked strictly (unknown fields are an error) and every client is rate
+// limited by IP, so a public instance can't be knocked over with junk.
+//
+// With an audit log (see `with_audit_log`), every suggestion served is written to it as a line of
+// JSON, with the session and history it was for, for looking into complaints after the fact.
+
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::cancel::Cancel;
+use crate::core::bits;
+use crate::dict::Dictionary;
+use crate::hard_mode::is_legal_hard_mode;
+use crate::i18n::Lang;
+use crate::session::{self, parse_guess, Algorithm, SessionOptions, SessionStore, MAX_HISTORY};
+use crate::suggest::{suggest_from_history, SuggestOptions, Suggestion};
+use crate::{Guess, History, MaskExt, Wordle};
+
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    // how many requests each IP may make per window
+    pub rate_limit: usize,
+    pub rate_window: Duration,
+    // how long a session may sit idle before it's dropped
+    pub session_timeout: Duration,
+    // how many sessions there may be at once; creating one past that gets a 503
+    pub max_sessions: usize,
+    // the largest request body accepted, in bytes
+    pub max_body: usize,
+    // how many /suggest answers to remember, by the state they were for; 0 turns it off
+    pub cache_size: usize,
+    // How long a /suggest request (or a session's suggestion) may spend scoring guesses. Past
+    // that it gets the best guess found so far, marked as truncated (and not cached). None, the
+    // default, lets it take as long as it takes, so the same request always gets the same answer;
+    // `serve` sets one.
+    pub time_budget: Option<Duration>,
+    // what sessions may be set up with (see `Algorithm::covers` for beam widths)
+    pub algorithms: Vec<Algorithm>,
+    pub languages: Vec<Lang>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            rate_limit: 60,
+            rate_window: Duration::from_secs(60),
+            session_timeout: Duration::from_secs(30 * 60),
+            max_sessions: 10_000,
+            max_body: 16 * 1024,
+            cache_size: 1024,
+            time_budget: None,
+            algorithms: vec![
+                Algorithm::Naive,
+                Algorithm::Greedy,
+                Algorithm::Beam { width: 4 },
+            ],
+            languages: vec![Lang::English],
+        }
+    }
+}
+
+// Counts requests per IP in fixed windows.
+struct RateLimiter {
+    limit: usize,
+    window: Duration,
+    // when each client's current window started, and how many requests it has made in it
+    clients: HashMap<IpAddr, (Instant, usize)>,
+}
+
+impl RateLimiter {
+    fn new(limit: usize, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            clients: HashMap::new(),
+        }
+    }
+
+    fn allow(&mut self, ip: IpAddr, now: Instant) -> bool {
+        let window = self.window;
+        // forget clients whose windows are over, so the map doesn't grow forever
+        if self.clients.len() > 4096 {
+            self.clients
+                .retain(|_, (start, _)| now.duration_since(*start) < window);
+        }
+        let (start, count) = self.clients.entry(ip).or_insert((now, 0));
+        if now.duration_since(*start) >= window {
+            *start = now;
+            *count = 0;
+        }
+        *count += 1;
+        *count <= self.limit
+    }
+}
+
+// The suggestions most recently asked for, so the states lots of people are in at once (every
+// game starts from the same empty history, and the popular openers lead to the same few) are
+// only worked out once. The least recently used one makes room when it's full.
+struct SuggestionCache {
+    capacity: usize,
+    // bumped on every use, so the smallest is the least recently used
+    clock: u64,
+    entries: HashMap<String, (u64, Suggestion)>,
+    hits: usize,
+    misses: usize,
+}
+
+impl SuggestionCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            clock: 0,
+            entries: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    // The same state however it was asked for: the order of the guesses doesn't change what's
+    // left, so they're sorted.
+    fn key(history: &[Guess], candidates_only: bool) -> String {
+        let mut guesses = history.to_vec();
+        guesses.sort_by(|a, b| a.word.cmp(&b.word).then(a.mask.cmp(&b.mask)));
+        format!("{} {}", History::from(guesses), candidates_only)
+    }
+
+    fn get(&mut self, key: &str) -> Option<Suggestion> {
+        self.clock += 1;
+        match self.entries.get_mut(key) {
+            Some((used, suggestion)) => {
+                *used = self.clock;
+                self.hits += 1;
+                Some(suggestion.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn insert(&mut self, key: String, suggestion: Suggestion) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (used, _))| *used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(key, (self.clock, suggestion));
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    fn ok(body: serde_json::Value) -> Self {
+        Self {
+            status: 200,
+            body: body.to_string(),
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.into() }).to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GuessBody {
+    word: String,
+    mask: String,
+}
+
+impl GuessBody {
+    fn validate(self) -> Result<Guess, String> {
+        parse_guess(self.word, &self.mask)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SuggestBody {
+    history: Vec<GuessBody>,
+    #[serde(default)]
+    candidates_only: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SessionBody {
+    algorithm: String,
+    #[serde(default)]
+    hard_mode: bool,
+    // a language code, English when it's left out
+    #[serde(default)]
+    lang: Option<String>,
+}
+
+impl SessionBody {
+    fn validate(self, config: &ServerConfig) -> Result<SessionOptions, String> {
+        let algorithm = Algorithm::parse(&self.algorithm)
+            .ok_or_else(|| format!("unknown algorithm '{}'", self.algorithm))?;
+        let lang = match &self.lang {
+            Some(code) => code.parse()?,
+            None => Lang::English,
+        };
+        let options = SessionOptions {
+            algorithm,
+            hard_mode: self.hard_mode,
+            lang,
+        };
+        config.offers(&options)?;
+        options.check()?;
+        Ok(options)
+    }
+}
+
+impl ServerConfig {
+    // whether the allowlists let a session be set up like this
+    fn offers(&self, options: &SessionOptions) -> Result<(), String> {
+        if !self.algorithms.iter().any(|a| a.covers(options.algorithm)) {
+            return Err(format!("{} isn't offered here", options.algorithm));
+        }
+        if !self.languages.contains(&options.lang) {
+            return Err(format!("{:?} isn't offered here", options.lang));
+        }
+        Ok(())
+    }
+}
+
+pub struct Server {
+    config: ServerConfig,
+    dictionary: &'static Dictionary,
+    sessions: SessionStore,
+    limiter: RateLimiter,
+    cache: SuggestionCache,
+    // suggestions `warm_up` is still working out, to go in the cache as they come in
+    warming: Option<Receiver<(String, Suggestion)>>,
+    // shuts the server down, stopping whatever it's working out (see `canceller`)
+    cancel: Cancel,
+    audit: Option<Box<dyn Write + Send>>,
+}
+
+impl Server {
+    pub fn new(config: ServerConfig) -> Self {
+        let cancel = Cancel::new();
+        Self {
+            dictionary: Dictionary::shared(),
+            sessions: SessionStore::new(config.session_timeout)
+                .with_max(config.max_sessions)
+                .with_cancel(cancel.clone()),
+            limiter: RateLimiter::new(config.rate_limit, config.rate_window),
+            cache: SuggestionCache::new(config.cache_size),
+            warming: None,
+            cancel,
+            audit: None,
+            config,
+        }
+    }
+
+    // Write a line to `log` for every suggestion, before it's sent. Open files for appending, so
+    // nothing already logged is lost on a restart. A suggestion that can't be logged isn't sent
+    // (the request gets a 500) rather than served without a record.
+    pub fn with_audit_log(mut self, log: impl Write + Send + 'static) -> Self {
+        self.audit = Some(Box::new(log));
+        self
+    }
+
+    // Take over the sessions in `saved` (see `save_sessions`). The allowlists may have changed
+    // since, so every one of them has to be something this server would have set up.
+    pub fn with_sessions(mut self, saved: &str) -> Result<Self, String> {
+        let sessions =
+            SessionStore::load(saved, self.config.session_timeout).map_err(|e| e.to_string())?;
+        for (id, session) in sessions.iter() {
+            self.config
+                .offers(&session.options)
+                .map_err(|e| format!("session {}: {}", id, e))?;
+        }
+        self.sessions = sessions
+            .with_max(self.config.max_sessions)
+            .with_cancel(self.cancel.clone());
+        Ok(self)
+    }
+
+    // every session, for `with_sessions` to pick up again after a restart
+    pub fn save_sessions(&self) -> String {
+        self.sessions.save()
+    }
+
+    // `entry` as a line of the audit log, if there is one
+    fn audit(&mut self, mut entry: serde_json::Value, history: &[Guess]) -> Result<(), Response> {
+        let Some(log) = &mut self.audit else {
+            return Ok(());
+        };
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        entry["time_ms"] = json!(time.as_millis() as u64);
+        entry["history"] = history
+            .iter()
+            .map(|guess| json!({ "word": guess.word.as_str(), "mask": guess.mask.to_compact_string() }))
+            .collect();
+        // what the complaints are usually about: a suggestion that ignores a green or a letter
+        // that's known to be there
+        if let Some(guess) = entry["guess"].as_str() {
+            entry["uses_hints"] = json!(is_legal_hard_mode(history, guess));
+        }
+        writeln!(log, "{}", entry)
+            .and_then(|()| log.flush())
+            .map_err(|e| Response::error(500, format!("couldn't write the audit log: {}", e)))
+    }
+
+    // Cancelling this makes `run` return once it's done with the request it's on, and a
+    // suggestion being worked out stops where it is (the request gets a 503).
+    pub fn canceller(&self) -> Cancel {
+        self.cancel.clone()
+    }
+
+    // Start working out the first suggestion of a game (with and without `candidates_only`, which
+    // has to score every word against every word) on another thread, for the cache. Requests are
+    // answered as usual in the meantime. `run` calls this before taking any.
+    pub fn warm_up(&mut self) {
+        Wordle::warm_up();
+        // optimal sessions go without the tree until it's built, when there's a time budget
+        if self.config.algorithms.contains(&Algorithm::Optimal) {
+            let cancel = self.cancel.clone();
+            thread::spawn(move || session::build_tree(&cancel));
+        }
+        if self.config.cache_size == 0 {
+            return;
+        }
+        let (send, receive) = mpsc::channel();
+        let dictionary = self.dictionary;
+        let cancel = self.cancel.clone();
+        thread::spawn(move || {
+            for candidates_only in [false, true] {
+                let options = SuggestOptions {
+                    candidates_only,
+                    cancel: Some(&cancel),
+                    ..Default::default()
+                };
+                let suggestion = suggest_from_history(dictionary, &[], &options);
+                let key = SuggestionCache::key(&[], candidates_only);
+                // the server is gone, or going
+                if cancel.is_cancelled() || send.send((key, suggestion)).is_err() {
+                    return;
+                }
+            }
+        });
+        self.warming = Some(receive);
+    }
+
+    // put whatever `warm_up` has finished in the cache
+    fn warmed(&mut self) {
+        if let Some(warming) = &self.warming {
+            for (key, suggestion) in warming.try_iter() {
+                self.cache.insert(key, suggestion);
+            }
+        }
+    }
+
+    // answer requests on `addr` one at a time, until it's cancelled
+    pub fn run(mut self, addr: &str) -> io::Result<()> {
+        self.warm_up();
+        let server = tiny_http::Server::http(addr).map_err(io::Error::other)?;
+        let content_type = tiny_http::Header::from_bytes("Content-Type", "application/json")
+            .expect("the header is valid");
+        while !self.cancel.is_cancelled() {
+            // waiting a little at a time, to notice being cancelled when there's nothing to do
+            let Some(mut request) = server.recv_timeout(Duration::from_millis(100))? else {
+                continue;
+            };
+            let ip = request
+                .remote_addr()
+                .map_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED), |addr| addr.ip());
+            let mut body = String::new();
+            let read = request
+                .as_reader()
+                .take(self.config.max_body as u64 + 1)
+                .read_to_string(&mut body);
+            let response = match read {
+                Err(_) => Response::error(400, "the body is not utf-8"),
+                Ok(n) if n > self.config.max_body => Response::error(413, "the body is too big"),
+                Ok(_) => self.handle(ip, request.method().as_str(), request.url(), &body),
+            };
+            let response = tiny_http::Response::from_string(response.body)
+                .with_status_code(response.status)
+                .with_header(content_type.clone());
+            // the client hanging up early is its own problem
+            let _ = request.respond(response);
+        }
+        Ok(())
+    }
+
+    pub fn handle(&mut self, ip: IpAddr, method: &str, path: &str, body: &str) -> Response {
+        if !self.limiter.allow(ip, Instant::now()) {
+            return Response::error(429, "too many requests");
+        }
+        self.sessions.expire();
+        let segments: Vec<_> = path.trim_matches('/').split('/').collect();
+        match (method, segments.as_slice()) {
+            ("POST", ["suggest"]) => self.suggest(body),
+            ("POST", ["sessions"]) => self.create_session(body),
+            ("POST", ["sessions", id, "guesses"]) => self.record(id, body),
+            ("GET", ["sessions", id, "suggestion"]) => self.session_suggestion(id),
+            _ => Response::error(404, "no such endpoint"),
+        }
+    }
+
+    fn suggest(&mut self, body: &str) -> Response {
+        let body: SuggestBody = match serde_json::from_str(body) {
+            Ok(body) => body,
+            Err(e) => return Response::error(400, e.to_string()),
+        };
+        if body.history.len() > MAX_HISTORY {
+            return Response::error(400, format!("at most {} guesses", MAX_HISTORY));
+        }
+        let history = match body
+            .history
+            .into_iter()
+            .map(GuessBody::validate)
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(history) => history,
+            Err(e) => return Response::error(400, e),
+        };
+        self.warmed();
+        let key = SuggestionCache::key(&history, body.candidates_only);
+        let suggestion = match self.cache.get(&key) {
+            Some(suggestion) => suggestion,
+            None => {
+                let options = SuggestOptions {
+                    candidates_only: body.candidates_only,
+                    cancel: Some(&self.cancel),
+                    deadline: self
+                        .config
+                        .time_budget
+                        .map(|budget| Instant::now() + budget),
+                    ..Default::default()
+                };
+                let suggestion = suggest_from_history(self.dictionary, &history, &options);
+                if self.cancel.is_cancelled() {
+                    return Response::error(503, "the server is shutting down");
+                }
+                // another request with more time to spare might do better
+                if !suggestion.truncated {
+                    self.cache.insert(key, suggestion.clone());
+                }
+                suggestion
+            }
+        };
+        let entry = json!({
+            "session": null,
+            "candidates_only": body.candidates_only,
+            "guess": suggestion.guess,
+            "bits": suggestion.bits,
+            "remaining": suggestion.remaining,
+            "truncated": suggestion.truncated,
+        });
+        if let Err(response) = self.audit(entry, &history) {
+            return response;
+        }
+        Response::ok(json!({
+            "guess": suggestion.guess,
+            "remaining": suggestion.remaining,
+            "bits": suggestion.bits,
+            "p_answer": suggestion.p_answer,
+            "p_solved": suggestion.p_solved,
+            "truncated": suggestion.truncated,
+        }))
+    }
+
+    fn create_session(&mut self, body: &str) -> Response {
+        let body: SessionBody = match serde_json::from_str(body) {
+            Ok(body) => body,
+            Err(e) => return Response::error(400, e.to_string()),
+        };
+        let options = match body.validate(&self.config) {
+            Ok(options) => options,
+            Err(e) => return Response::error(400, e),
+        };
+        match self.sessions.create(options) {
+            Ok(id) => Response::ok(json!({ "id": id })),
+            Err(e) => Response::error(503, e.to_string()),
+        }
+    }
+
+    fn record(&mut self, id: &str, body: &str) -> Response {
+        let Ok(id) = id.parse() else {
+            return Response::error(404, "no such session");
+        };
+        let guess = match serde_json::from_str::<GuessBody>(body) {
+            Ok(guess) => guess,
+            Err(e) => return Response::error(400, e.to_string()),
+        };
+        let guess = match guess.validate() {
+            Ok(guess) => guess,
+            Err(e) => return Response::error(400, e),
+        };
+        match self.sessions.get(id) {
+            Some(session) if session.history.len() >= MAX_HISTORY => {
+                return Response::error(400, format!("at most {} guesses", MAX_HISTORY))
+            }
+            _ => {}
+        }
+        match self.sessions.record(id, guess) {
+            Ok(()) => Response::ok(json!({})),
+            Err(e) => Response::error(404, e.to_string()),
+        }
+    }
+
+    fn session_suggestion(&mut self, id: &str) -> Response {
+        let Ok(id) = id.parse() else {
+            return Response::error(404, "no such session");
+        };
+        let deadline = self
+            .config
+            .time_budget
+            .map(|budget| Instant::now() + budget);
+        let (guess, truncated) = match self.sessions.suggest(id, deadline) {
+            Ok(suggestion) => suggestion,
+            Err(e) => return Response::error(404, e.to_string()),
+        };
+        if self.cancel.is_cancelled() {
+            return Response::error(503, "the server is shutting down");
+        }
+        let session = self.sessions.get(id).expect("it was just used");
+        let remaining = session.remaining();
+        let entry = json!({
+            "session": id,
+            "options": session.options.to_string(),
+            "guess": guess,
+            "bits": bits(&remaining, &guess),
+            "remaining": remaining.len(),
+            "truncated": truncated,
+        });
+        let history = session.history.clone();
+        if let Err(response) = self.audit(entry, &history) {
+            return response;
+        }
+        Response::ok(json!({ "guess": guess, "truncated": truncated }))
+    }
+}
+
+// nothing's left to take what the warm-up thread works out
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IP: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+    #[test]
+    fn rate_limit() {
+        let mut limiter = RateLimiter::new(2, Duration::from_secs(60));
+        let now = Instant::now();
+        let other = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        assert!(limiter.allow(IP, now));
+        assert!(limiter.allow(IP, now));
+        assert!(!limiter.allow(IP, now));
+        assert!(limiter.allow(other, now));
+        assert!(limiter.allow(IP, now + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn suggest() {
+        let mut server = Server::new(ServerConfig::default());
+        let response = server.handle(IP, "POST", "/suggest", r#"{"history": []}"#);
+        assert_eq!(response.status, 200);
+        assert!(response.body.contains(r#""guess":"tares""#));
+    }
+
+    #[test]
+    fn cache() {
+        let mut server = Server::new(ServerConfig {
+            cache_size: 2,
+            // a suggestion that ran out of time isn't cached, which would throw the counts off
+            time_budget: None,
+            ..Default::default()
+        });
+        let suggest = |server: &mut Server, history: &str| {
+            let body = format!(r#"{{"history": [{}]}}"#, history);
+            server.handle(IP, "POST", "/suggest", &body).body
+        };
+        let tares = r#"{"word": "tares", "mask": "BYBBG"}"#;
+        let lorry = r#"{"word": "lorry", "mask": "BBBBB"}"#;
+        let first = suggest(&mut server, &format!("{},{}", tares, lorry));
+        // the same guesses the other way round are the same state
+        assert_eq!(suggest(&mut server, &format!("{},{}", lorry, tares)), first);
+        assert_eq!((server.cache.hits, server.cache.misses), (1, 1));
+        suggest(&mut server, "");
+        suggest(&mut server, tares);
+        // there's only room for two, and the pair was used longest ago
+        assert_eq!(server.cache.entries.len(), 2);
+        let pair = History::parse("tares=WMWWC lorry=WWWWW").unwrap();
+        assert!(!server
+            .cache
+            .entries
+            .contains_key(&SuggestionCache::key(&pair, false)));
+        suggest(&mut server, "");
+        assert_eq!(server.cache.hits, 2);
+    }
+
+    #[test]
+    fn warm_up() {
+        // working it out for real takes too long in a debug build, so stand in for the thread
+        let mut server = Server::new(ServerConfig::default());
+        let (send, receive) = mpsc::channel();
+        server.warming = Some(receive);
+        let suggestion = suggest_from_history(server.dictionary, &[], &Default::default());
+        send.send((SuggestionCache::key(&[], false), suggestion))
+            .unwrap();
+        let response = server.handle(IP, "POST", "/suggest", r#"{"history": []}"#);
+        assert!(response.body.contains(r#""guess":"tares""#));
+        assert_eq!((server.cache.hits, server.cache.misses), (1, 0));
+    }
+
+    #[test]
+    fn rejects_malformed_requests() {
+        let mut server = Server::new(ServerConfig::default());
+        for body in [
+            "",
+            "[]",
+            r#"{"history": [], "extra": 1}"#,
+            r#"{"history": [{"word": "tares"}]}"#,
+            r#"{"history": [{"word": "TARES", "mask": "BBBBB"}]}"#,
+            r#"{"history": [{"word": "tares", "mask": "BBBBX"}]}"#,
+        ] {
+            assert_eq!(server.handle(IP, "POST", "/suggest", body).status, 400);
+        }
+        let history = vec![r#"{"word": "tares", "mask": "BBBBB"}"#; MAX_HISTORY + 1].join(",");
+        let body = format!(r#"{{"history": [{}]}}"#, history);
+        assert_eq!(server.handle(IP, "POST", "/suggest", &body).status, 400);
+        assert_eq!(server.handle(IP, "GET", "/nope", "").status, 404);
+    }
+
+    #[test]
+    fn sessions() {
+        let mut server = Server::new(ServerConfig::default());
+        let response = server.handle(IP, "POST", "/sessions", r#"{"algorithm": "naive"}"#);
+        assert_eq!(response.body, r#"{"id":1}"#);
+        let response = server.handle(IP, "GET", "/sessions/1/suggestion", "");
+        assert_eq!(response.body, r#"{"guess":"tares","truncated":false}"#);
+        let guess = r#"{"word": "tares", "mask": "BYBBG"}"#;
+        assert_eq!(
+            server
+                .handle(IP, "POST", "/sessions/1/guesses", guess)
+                .status,
+            200
+        );
+        assert_eq!(
+            server
+                .handle(IP, "POST", "/sessions/2/guesses", guess)
+                .status,
+            404
+        );
+        let response = server.handle(IP, "POST", "/sessions", r#"{"algorithm": "magic"}"#);
+        assert_eq!(response.status, 400);
+    }
+
+    #[test]
+    fn max_sessions() {
+        let mut server = Server::new(ServerConfig {
+            max_sessions: 1,
+            ..Default::default()
+        });
+        let mut create = || {
+            server
+                .handle(IP, "POST", "/sessions", r#"{"algorithm": "naive"}"#)
+                .status
+        };
+        assert_eq!(create(), 200);
+        assert_eq!(create(), 503);
+    }
+
+    #[test]
+    fn allowlist() {
+        let mut server = Server::new(ServerConfig::default());
+        let mut create = |body: &str| server.handle(IP, "POST", "/sessions", body);
+        let hard = create(r#"{"algorithm": "greedy", "hard_mode": true, "lang": "en"}"#);
+        assert_eq!(hard.status, 200);
+        for body in [
+            // not on the lists
+            r#"{"algorithm": "optimal"}"#,
+            r#"{"algorithm": "beam:5"}"#,
+            r#"{"algorithm": "greedy", "lang": "es"}"#,
+            r#"{"algorithm": "greedy", "lang": "tlh"}"#,
+            // on them, but not together
+            r#"{"algorithm": "beam:2", "hard_mode": true}"#,
+        ] {
+            assert_eq!(create(body).status, 400, "{}", body);
+        }
+        assert_eq!(create(r#"{"algorithm": "beam:2"}"#).status, 200);
+        assert!(server.sessions.get(1).unwrap().options.hard_mode);
+    }
+
+    #[test]
+    fn restored_sessions() {
+        let mut server = Server::new(ServerConfig::default());
+        server.handle(IP, "POST", "/sessions", r#"{"algorithm": "beam:4"}"#);
+        let saved = server.save_sessions();
+        let server = Server::new(ServerConfig::default()).with_sessions(&saved);
+        assert_eq!(server.unwrap().sessions.len(), 1);
+        // beam:4 was offered when it was saved, but isn't any more
+        let narrower = ServerConfig {
+            algorithms: vec![Algorithm::Beam { width: 2 }],
+            ..Default::default()
+        };
+        assert!(Server::new(narrower).with_sessions(&saved).is_err());
+    }
+
+    #[test]
+    fn out_of_time() {
+        let mut server = Server::new(ServerConfig {
+            time_budget: Some(Duration::ZERO),
+            ..Default::default()
+        });
+        let body = r#"{"history": [{"word": "tares", "mask": "BYBBG"}]}"#;
+        let response = server.handle(IP, "POST", "/suggest", body);
+        assert_eq!(response.status, 200);
+        assert!(response.body.contains(r#""truncated":true"#));
+        assert!(!response.body.contains(r#""guess":null"#));
+        assert!(server.cache.entries.is_empty());
+        // and a session's suggestion, optimal ones too while the tree isn't built
+        server.config.algorithms.push(Algorithm::Optimal);
+        for algorithm in ["naive", "beam:4", "optimal"] {
+            let body = format!(r#"{{"algorithm": "{}"}}"#, algorithm);
+            let id = server.handle(IP, "POST", "/sessions", &body).body;
+            let id: serde_json::Value = serde_json::from_str(&id).unwrap();
+            let path = format!("/sessions/{}/guesses", id["id"]);
+            let body = r#"{"word": "tares", "mask": "BYBBG"}"#;
+            assert_eq!(server.handle(IP, "POST", &path, body).status, 200);
+            let path = format!("/sessions/{}/suggestion", id["id"]);
+            let response = server.handle(IP, "GET", &path, "");
+            assert_eq!(response.status, 200, "{}", algorithm);
+            assert!(
+                response.body.contains(r#""truncated":true"#),
+                "{}",
+                algorithm
+            );
+        }
+    }
+
+    #[test]
+    fn cancelled() {
+        let mut server = Server::new(ServerConfig::default());
+        server.canceller().cancel();
+        let body = r#"{"history": [{"word": "tares", "mask": "BYBBG"}]}"#;
+        let response = server.handle(IP, "POST", "/suggest", body);
+        assert_eq!(response.status, 503);
+        assert!(server.cache.entries.is_empty());
+        // and so does a session's, which a session for the optimal tree would otherwise spend
+        // building it
+        server.config.algorithms.push(Algorithm::Optimal);
+        for algorithm in ["naive", "beam:4", "optimal"] {
+            let body = format!(r#"{{"algorithm": "{}"}}"#, algorithm);
+            let id = server.handle(IP, "POST", "/sessions", &body).body;
+            let id: serde_json::Value = serde_json::from_str(&id).unwrap();
+            let path = format!("/sessions/{}/suggestion", id["id"]);
+            assert_eq!(server.handle(IP, "GET", &path, "").status, 503);
+        }
+        // it stops straight away, without waiting for a request
+        server.run("127.0.0.1:0").unwrap();
+    }
+
+    #[test]
+    fn audit_log() {
+        // a log that can still be read once the server has it
+        #[derive(Clone, Default)]
+        struct Log(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+        impl Write for Log {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        let log = Log::default();
+        let mut server = Server::new(ServerConfig::default()).with_audit_log(log.clone());
+        let guess = r#"{"word": "tares", "mask": "BYBBG"}"#;
+        server.handle(
+            IP,
+            "POST",
+            "/suggest",
+            &format!(r#"{{"history": [{}]}}"#, guess),
+        );
+        server.handle(
+            IP,
+            "POST",
+            "/sessions",
+            r#"{"algorithm": "naive", "hard_mode": true}"#,
+        );
+        server.handle(IP, "POST", "/sessions/1/guesses", guess);
+        server.handle(IP, "GET", "/sessions/1/suggestion", "");
+        // failed requests didn't get a suggestion, so they aren't logged
+        server.handle(IP, "GET", "/sessions/2/suggestion", "");
+
+        let text = String::from_utf8(log.0.lock().unwrap().clone()).unwrap();
+        let entries: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 2);
+        for entry in &entries {
+            assert_eq!(
+                entry["history"],
+                json!([{ "word": "tares", "mask": "BYBBG" }])
+            );
+            assert!(entry["guess"].is_string());
+            assert!(entry["time_ms"].as_u64().unwrap() > 0);
+        }
+        assert_eq!(entries[0]["session"], json!(null));
+        assert!(entries[0]["bits"].as_f64().unwrap() > 0.0);
+        assert_eq!(entries[1]["session"], json!(1));
+        assert_eq!(entries[1]["options"], json!("naive+hard"));
+        // measured the same way as for /suggest, against the same words
+        assert!(entries[1]["bits"].as_f64().unwrap() > 0.0);
+        assert_eq!(entries[1]["remaining"], entries[0]["remaining"]);
+        // hard mode keeps to the hints, by definition
+        assert_eq!(entries[1]["uses_hints"], json!(true));
+    }
+
+    #[test]
+    fn too_many_requests() {
+        let mut server = Server::new(ServerConfig {
+            rate_limit: 1,
+            ..Default::default()
+        });
+        assert_eq!(server.handle(IP, "GET", "/nope", "").status, 404);
+        assert_eq!(server.handle(IP, "GET", "/nope", "").status, 429);
+    }
+}
@@ -0,0 +1,670 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crate::algorithms::{Beam, Entropy, Greedy, Naive, Optimal};
+use crate::blocklist::Blocklist;
+use crate::cancel::{Cancel, Stop};
+use crate::dict::Dictionary;
+use crate::i18n::Lang;
+use crate::tree::Tree;
+use crate::{openers, Guess, GuessPolicy, Guesser, History, Mask, MaskExt, Word};
+
+// the most guesses a client's history may have, same as `Wordle::play` allows
+pub const MAX_HISTORY: usize = 32;
+
+// a guess from a client, checked to be five lowercase letters with a B/Y/G mask
+pub fn parse_guess(word: String, mask: &str) -> Result<Guess, String> {
+    let word =
+        Word::new(&word).ok_or_else(|| format!("'{}' is not five lowercase letters", word))?;
+    let mask = Mask::from_compact_str(mask)
+        .ok_or_else(|| format!("'{}' is not a mask like BYBBG", mask))?;
+    Ok(Guess { word, mask })
+}
+
+// Which algorithm a session gets its suggestions from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Algorithm {
+    #[default]
+    Naive,
+    // greedy on entropy, the one that can play in other languages
+    Greedy,
+    Beam {
+        width: usize,
+    },
+    // The decision tree `bench -i optimal` plays, shared by every session. It's
+    // built the first time a session asks for a suggestion, which takes a while (and is
+    // given up on if the store is cancelled, see `SessionStore::with_cancel`), or ahead of
+    // time by `build_tree`.
+    Optimal,
+}
+
+impl Algorithm {
+    // the reverse of the `Display` impl: "naive", "greedy", "beam:<width>" or "optimal"
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.split_once(':') {
+            None if s == "naive" => Some(Algorithm::Naive),
+            None if s == "greedy" => Some(Algorithm::Greedy),
+            None if s == "optimal" => Some(Algorithm::Optimal),
+            Some(("beam", width)) => match width.parse() {
+                Ok(width) if width > 0 => Some(Algorithm::Beam { width }),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    // Whether an allowlist with this on it lets a session use `other`: the same algorithm, and
+    // for beam search one no wider.
+    pub fn covers(self, other: Algorithm) -> bool {
+        match (self, other) {
+            (Algorithm::Beam { width: most }, Algorithm::Beam { width }) => width <= most,
+            _ => self == other,
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Algorithm::Naive => write!(f, "naive"),
+            Algorithm::Greedy => write!(f, "greedy"),
+            Algorithm::Beam { width } => write!(f, "beam:{}", width),
+            Algorithm::Optimal => write!(f, "optimal"),
+        }
+    }
+}
+
+// How a session is set up: the algorithm, and the rules and language of the game it's helping
+// with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SessionOptions {
+    pub algorithm: Algorithm,
+    // only suggest guesses that use every hint so far
+    pub hard_mode: bool,
+    pub lang: Lang,
+}
+
+impl SessionOptions {
+    pub fn new(algorithm: Algorithm) -> Self {
+        Self {
+            algorithm,
+            ..Default::default()
+        }
+    }
+
+    // whether the algorithm can play by these rules in this language (in this build)
+    pub fn check(&self) -> Result<(), String> {
+        if self.hard_mode && !matches!(self.algorithm, Algorithm::Naive | Algorithm::Greedy) {
+            return Err(format!("{} can't play hard mode", self.algorithm));
+        }
+        if self.lang != Lang::English {
+            if self.algorithm != Algorithm::Greedy {
+                return Err(format!("{} only plays in English", self.algorithm));
+            }
+            if words(self.lang).is_none() {
+                return Err(format!("no {:?} words in this build", self.lang));
+            }
+        }
+        Ok(())
+    }
+
+    // only called on options that passed `check`; the guesser stops short when `stop` says to
+    fn guesser(self, stop: &Stop) -> Box<dyn Guesser + Send> {
+        let stop = stop.clone();
+        match self.algorithm {
+            Algorithm::Naive if self.hard_mode => {
+                Box::new(Naive::new().hard_mode().with_stop(stop))
+            }
+            Algorithm::Naive => Box::new(Naive::new().with_stop(stop)),
+            Algorithm::Greedy => {
+                let greedy = match words(self.lang) {
+                    Some(words) if self.lang != Lang::English => {
+                        Greedy::with_words(Entropy, words.iter().copied())
+                    }
+                    _ => match openers::bundled("greedy:entropy") {
+                        Some(opener) => Greedy::new(Entropy).with_opener(opener),
+                        None => Greedy::new(Entropy),
+                    },
+                };
+                let greedy = greedy.with_stop(stop);
+                match self.hard_mode {
+                    true => Box::new(greedy.hard_mode()),
+                    false => Box::new(greedy),
+                }
+            }
+            Algorithm::Beam { width } => Box::new(Beam::new(width).with_stop(stop)),
+            Algorithm::Optimal => Box::new(FollowTree {
+                tree: None,
+                fallback: None,
+                stop,
+            }),
+        }
+    }
+
+    // The reverse of the `Display` impl: the algorithm, then "+hard" in hard mode and
+    // "+<language code>" for languages other than English, like "greedy+hard+es".
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.split('+');
+        let mut options = Self::new(Algorithm::parse(parts.next()?)?);
+        for part in parts {
+            match part {
+                "hard" => options.hard_mode = true,
+                code => options.lang = code.parse().ok()?,
+            }
+        }
+        Some(options)
+    }
+}
+
+impl fmt::Display for SessionOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.algorithm)?;
+        if self.hard_mode {
+            write!(f, "+hard")?;
+        }
+        if self.lang != Lang::English {
+            write!(f, "+{}", self.lang.code())?;
+        }
+        Ok(())
+    }
+}
+
+// A language's words and counts, or None without its lang-* feature. Guessers want words that
+// last as long as the program, so each language's list is loaded once and kept.
+fn words(lang: Lang) -> Option<&'static [(&'static str, u64)]> {
+    type Words = Vec<(&'static str, u64)>;
+    static WORDS: [OnceLock<Option<Words>>; 4] = [const { OnceLock::new() }; 4];
+    let slot = match lang {
+        Lang::English => 0,
+        Lang::Spanish => 1,
+        Lang::German => 2,
+        Lang::French => 3,
+    };
+    WORDS[slot]
+        .get_or_init(|| {
+            let dictionary: &'static Dictionary = Box::leak(Box::new(Dictionary::for_lang(lang)?));
+            Some(
+                dictionary
+                    .0
+                    .iter()
+                    .map(|(word, &count)| (word.as_str(), count))
+                    .collect(),
+            )
+        })
+        .as_deref()
+}
+
+static TREE: OnceLock<Tree> = OnceLock::new();
+
+// Build the tree optimal sessions follow, if it hasn't been yet, for a server to do before
+// they're asked for suggestions with a deadline (which never wait for it). None when it's
+// cancelled first.
+pub fn build_tree(cancel: &Cancel) -> Option<&'static Tree> {
+    if let Some(tree) = TREE.get() {
+        return Some(tree);
+    }
+    let tree = Optimal::official_until(
+        Some(Optimal::DEFAULT_WIDTH),
+        &Blocklist::default(),
+        GuessPolicy::Strict,
+        None,
+        cancel,
+    )
+    .ok()?;
+    Some(TREE.get_or_init(|| tree))
+}
+
+// Plays the tree while the history stays on it. People type in what their game said, which can
+// be a guess the tree wouldn't make or a mask no answer gives, so off the tree it carries on
+// with `Naive` instead of giving up.
+// The tree is built the first time it's asked for a guess (unless another session already
+// has), and a cancelled build goes to `Naive` too. So does a guess with a deadline while the
+// tree isn't there yet, since building it takes far longer than any deadline allows.
+struct FollowTree {
+    tree: Option<&'static Tree>,
+    fallback: Option<Naive>,
+    stop: Stop,
+}
+
+impl Guesser for FollowTree {
+    fn guess(&mut self, history: &History) -> String {
+        if self.tree.is_none() {
+            self.tree = match self.stop.deadline() {
+                Some(_) => TREE.get(),
+                None => build_tree(self.stop.cancel()),
+            };
+        }
+        match self.tree.and_then(|tree| tree.follow(history)) {
+            Some(node) => node.guess.clone(),
+            None => self
+                .fallback
+                .get_or_insert_with(|| Naive::new().with_stop(self.stop.clone()))
+                .guess(history),
+        }
+    }
+}
+
+pub type SessionId = u64;
+
+// One assisted game: what's been guessed so far, and who's suggesting the next guess.
+pub struct Session {
+    pub options: SessionOptions,
+    pub history: History,
+    last_used: Instant,
+    // built the first time it's needed; guessers can rebuild their state from the history, so
+    // this is never persisted
+    guesser: Option<Box<dyn Guesser + Send>>,
+}
+
+impl Session {
+    fn new(options: SessionOptions, history: History) -> Self {
+        Self {
+            options,
+            history,
+            last_used: Instant::now(),
+            guesser: None,
+        }
+    }
+
+    // the words of the session's language the history still allows, with their counts, for
+    // scoring a suggestion like `suggest` does
+    pub fn remaining(&self) -> Vec<(&'static str, u64)> {
+        words(self.options.lang)
+            .unwrap_or_default()
+            .iter()
+            .filter(|&&(word, _)| self.history.iter().all(|guess| guess.matches(word)))
+            .copied()
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionError {
+    // there is no such session, or it timed out
+    NotFound(SessionId),
+    // there are as many sessions as the store takes (see `with_max`)
+    Full,
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionError::NotFound(id) => write!(f, "no session {}", id),
+            SessionError::Full => write!(f, "too many sessions"),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSessionsError {
+    pub line: usize,
+    pub content: String,
+}
+
+impl fmt::Display for ParseSessionsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: expected an id, session options and guesses with masks, got '{}'",
+            self.line, self.content
+        )
+    }
+}
+
+impl std::error::Error for ParseSessionsError {}
+
+// Many games being assisted at once, as a server needs. Sessions that go unused for longer than
+// the timeout are dropped by `expire`, which the owner is expected to call now and then.
+pub struct SessionStore {
+    sessions: HashMap<SessionId, Session>,
+    next_id: SessionId,
+    timeout: Duration,
+    // how many sessions there may be at once, so creating them can't use up the memory
+    max: Option<usize>,
+    // handed to every guesser, to stop them mid-suggestion
+    stop: Stop,
+}
+
+impl SessionStore {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            sessions: HashMap::new(),
+            next_id: 1,
+            timeout,
+            max: None,
+            stop: Stop::default(),
+        }
+    }
+
+    // Refuse new sessions while there are `max` of them. Ones already there (say, loaded from a
+    // save) are kept even past that, and make room as they expire.
+    pub fn with_max(mut self, max: usize) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    // Cancelling `cancel` makes a suggestion being worked out stop where it is and go with the
+    // best guess so far, for shutting down without waiting on it.
+    pub fn with_cancel(mut self, cancel: Cancel) -> Self {
+        self.stop = Stop::new(cancel);
+        self
+    }
+
+    pub fn create(&mut self, options: SessionOptions) -> Result<SessionId, SessionError> {
+        if self.max.is_some_and(|max| self.sessions.len() >= max) {
+            return Err(SessionError::Full);
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        self.sessions
+            .insert(id, Session::new(options, History::new()));
+        Ok(id)
+    }
+
+    pub fn get(&self, id: SessionId) -> Option<&Session> {
+        self.sessions.get(&id)
+    }
+
+    pub fn remove(&mut self, id: SessionId) -> Option<Session> {
+        self.sessions.remove(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (SessionId, &Session)> {
+        self.sessions.iter().map(|(&id, session)| (id, session))
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    // add a guess and the mask the game gave it to a session's history
+    pub fn record(&mut self, id: SessionId, guess: Guess) -> Result<(), SessionError> {
+        let session = self.touch(id)?;
+        session.history.push(guess);
+        Ok(())
+    }
+
+    // What the session's algorithm would guess next, and whether that's only the best guess it
+    // found by `deadline` (or before it was cancelled). Guessers don't cache what they work out
+    // in a hurry, so asking again can do better.
+    pub fn suggest(
+        &mut self,
+        id: SessionId,
+        deadline: Option<Instant>,
+    ) -> Result<(String, bool), SessionError> {
+        let (mut guesser, history) = self.check_out(id)?;
+        // every guesser shares the store's `stop`, which is fine while this has `&mut self`
+        self.stop.start(deadline);
+        let guess = guesser.guess(&history);
+        let truncated = self.stop.stopped();
+        // so a guesser that's been checked out isn't held to it
+        self.stop.start(None);
+        self.check_in(id, guesser);
+        Ok((guess, truncated))
+    }
+
+    // Take a session's guesser (built if it hasn't been yet) out of the store, with a copy of the
+    // history, so the guess can be worked out without holding on to the store. `check_in` puts it
+    // back; a suggestion asked for in the meantime builds another one.
+    pub fn check_out(
+        &mut self,
+        id: SessionId,
+    ) -> Result<(Box<dyn Guesser + Send>, History), SessionError> {
+        let stop = self.stop.clone();
+        let session = self.touch(id)?;
+        let guesser = session
+            .guesser
+            .take()
+            .unwrap_or_else(|| session.options.guesser(&stop));
+        Ok((guesser, session.history.clone()))
+    }
+
+    // return a guesser from `check_out`, unless the session is gone or has another by now
+    pub fn check_in(&mut self, id: SessionId, guesser: Box<dyn Guesser + Send>) {
+        if let Some(session) = self.sessions.get_mut(&id) {
+            session.guesser.get_or_insert(guesser);
+        }
+    }
+
+    // drop every session that has been idle for longer than the timeout, returning how many
+    pub fn expire(&mut self) -> usize {
+        let before = self.sessions.len();
+        let timeout = self.timeout;
+        self.sessions
+            .retain(|_, session| session.last_used.elapsed() <= timeout);
+        before - self.sessions.len()
+    }
+
+    // One session per line: its id, its options, then each guess followed by its mask in B/Y/G,
+    // like "3 naive+hard tares BYBBG". Guessers aren't saved, since they can be rebuilt from the
+    // history.
+    pub fn save(&self) -> String {
+        let mut ids: Vec<_> = self.sessions.keys().copied().collect();
+        ids.sort_unstable();
+        let mut out = String::new();
+        for id in ids {
+            let session = &self.sessions[&id];
+            out.push_str(&format!("{} {}", id, session.options));
+            for guess in &session.history {
+                out.push_str(&format!(
+                    " {} {}",
+                    guess.word,
+                    guess.mask.to_compact_string()
+                ));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    // read back what `save` wrote; every session starts its timeout afresh
+    pub fn load(text: &str, timeout: Duration) -> Result<Self, ParseSessionsError> {
+        let mut store = Self::new(timeout);
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let err = || ParseSessionsError {
+                line: i + 1,
+                content: line.to_string(),
+            };
+            let mut fields = line.split_whitespace();
+            let id: SessionId = fields
+                .next()
+                .and_then(|id| id.parse().ok())
+                .ok_or_else(err)?;
+            let options = fields
+                .next()
+                .and_then(SessionOptions::parse)
+                .ok_or_else(err)?;
+            // building a guesser for options it can't play would panic the first time it's asked
+            options.check().map_err(|_| err())?;
+            let mut history = History::new();
+            while let Some(word) = fields.next() {
+                let mask = fields
+                    .next()
+                    .and_then(Mask::from_compact_str)
+                    .ok_or_else(err)?;
+                let word = Word::new(word).ok_or_else(err)?;
+                history.push(Guess { word, mask });
+            }
+            store.sessions.insert(id, Session::new(options, history));
+            // the last id there is would leave none for new sessions
+            store.next_id = store.next_id.max(id.checked_add(1).ok_or_else(err)?);
+        }
+        Ok(store)
+    }
+
+    fn touch(&mut self, id: SessionId) -> Result<&mut Session, SessionError> {
+        let session = self
+            .sessions
+            .get_mut(&id)
+            .ok_or(SessionError::NotFound(id))?;
+        session.last_used = Instant::now();
+        Ok(session)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sessions_are_independent() {
+        let mut store = SessionStore::new(Duration::from_secs(60));
+        let a = store.create(SessionOptions::default()).unwrap();
+        let b = store
+            // the default width, whose opener is bundled rather than worked out
+            .create(SessionOptions::new(Algorithm::Beam { width: 4 }))
+            .unwrap();
+        assert_ne!(a, b);
+        store
+            .record(
+                a,
+                Guess {
+                    word: "tares".parse().unwrap(),
+                    mask: mask![W W W W W],
+                },
+            )
+            .unwrap();
+        assert_eq!(store.get(a).unwrap().history.len(), 1);
+        assert!(store.get(b).unwrap().history.is_empty());
+        assert_eq!(store.suggest(b, None).unwrap(), ("tares".into(), false));
+        assert_eq!(store.suggest(99, None), Err(SessionError::NotFound(99)));
+    }
+
+    #[test]
+    fn check_out() {
+        let mut store = SessionStore::new(Duration::from_secs(60));
+        let id = store.create(SessionOptions::default()).unwrap();
+        let (mut guesser, history) = store.check_out(id).unwrap();
+        // the store can still answer for the session while its guesser is out
+        assert_eq!(store.suggest(id, None).unwrap().0, "tares");
+        assert_eq!(guesser.guess(&history), "tares");
+        store.check_in(id, guesser);
+        assert!(store.get(id).unwrap().guesser.is_some());
+        assert!(store.check_out(99).is_err());
+    }
+
+    #[test]
+    fn cancelled() {
+        let cancel = Cancel::new();
+        let mut store = SessionStore::new(Duration::from_secs(60)).with_cancel(cancel.clone());
+        let history = History::from_answer("right", ["tares"]);
+        for algorithm in [Algorithm::Naive, Algorithm::Beam { width: 4 }] {
+            let id = store.create(SessionOptions::new(algorithm)).unwrap();
+            store.record(id, history[0]).unwrap();
+            cancel.cancel();
+            // it gives up on scoring, but what it does suggest could still be the answer
+            let (guess, truncated) = store.suggest(id, None).unwrap();
+            assert!(history[0].matches(&guess), "{}: {}", algorithm, guess);
+            assert!(truncated);
+        }
+    }
+
+    #[test]
+    fn out_of_time() {
+        let mut store = SessionStore::new(Duration::from_secs(60));
+        let history = History::from_answer("right", ["tares"]);
+        for algorithm in [
+            Algorithm::Naive,
+            Algorithm::Greedy,
+            Algorithm::Beam { width: 4 },
+            // which doesn't wait for the tree to be built
+            Algorithm::Optimal,
+        ] {
+            let id = store.create(SessionOptions::new(algorithm)).unwrap();
+            store.record(id, history[0]).unwrap();
+            let (guess, truncated) = store.suggest(id, Some(Instant::now())).unwrap();
+            assert!(history[0].matches(&guess), "{}: {}", algorithm, guess);
+            assert!(truncated, "{}", algorithm);
+        }
+    }
+
+    #[test]
+    fn expire() {
+        let mut store = SessionStore::new(Duration::ZERO);
+        store.create(SessionOptions::default()).unwrap();
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(store.expire(), 1);
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn max() {
+        let mut store = SessionStore::new(Duration::from_secs(60)).with_max(2);
+        let a = store.create(SessionOptions::default()).unwrap();
+        store.create(SessionOptions::default()).unwrap();
+        assert_eq!(
+            store.create(SessionOptions::default()),
+            Err(SessionError::Full)
+        );
+        store.remove(a);
+        assert!(store.create(SessionOptions::default()).is_ok());
+    }
+
+    #[test]
+    fn save_and_load() {
+        let text = "1 naive tares BYBBG\n4 beam:3\n5 greedy+hard\n";
+        let mut store = SessionStore::load(text, Duration::from_secs(60)).unwrap();
+        assert_eq!(store.save(), text);
+        assert_eq!(store.get(1).unwrap().history[0].mask, mask![W M W W C]);
+        // new ids don't collide with loaded ones
+        assert_eq!(store.create(SessionOptions::default()), Ok(6));
+        assert_eq!(
+            SessionStore::load("1 magic", Duration::ZERO).err(),
+            Some(ParseSessionsError {
+                line: 1,
+                content: "1 magic".to_string()
+            })
+        );
+        // parses, but isn't something the session could play
+        assert!(SessionStore::load("1 beam:3+hard", Duration::ZERO).is_err());
+        let last = format!("{} naive", u64::MAX);
+        assert!(SessionStore::load(&last, Duration::ZERO).is_err());
+    }
+
+    #[test]
+    fn options() {
+        for text in ["naive", "greedy+hard+es", "beam:3", "optimal"] {
+            assert_eq!(SessionOptions::parse(text).unwrap().to_string(), text);
+        }
+        assert_eq!(SessionOptions::parse("naive+klingon"), None);
+        let hard_beam = SessionOptions::parse("beam:3+hard").unwrap();
+        assert!(hard_beam.check().is_err());
+        assert!(SessionOptions::parse("naive+es").unwrap().check().is_err());
+        assert!(SessionOptions::parse("greedy+hard")
+            .unwrap()
+            .check()
+            .is_ok());
+
+        let beam = |width| Algorithm::Beam { width };
+        assert!(beam(4).covers(beam(2)));
+        assert!(!beam(2).covers(beam(4)));
+        assert!(!Algorithm::Naive.covers(Algorithm::Greedy));
+    }
+
+    #[test]
+    fn off_the_tree() {
+        // a tree that only knows its first guess, rather than building the real one
+        static TREE: OnceLock<Tree> = OnceLock::new();
+        let tree = TREE.get_or_init(|| Tree::leaf("tares"));
+        let mut guesser = FollowTree {
+            tree: Some(tree),
+            fallback: None,
+            stop: Stop::default(),
+        };
+        assert_eq!(guesser.guess(&History::new()), "tares");
+        let history = History::from_answer("cigar", ["tares", "colin"]);
+        assert_eq!(guesser.guess(&history), "cigar");
+        assert!(guesser.fallback.is_some());
+    }
+}
@@ -0,0 +1,165 @@
+// Benchmarks from inside a program: play a guesser against a list of answers and get back the
+// same report `bench` saves, without going through the binary. Nothing is printed and nothing is
+// written; what to do with the report is up to the caller (`results::write_report` saves it).
+
+use crate::artifact::Rules;
+use crate::cancel::Cancel;
+use crate::dict::WordlistHash;
+use crate::results::{GameRecord, RunReport};
+use crate::{algorithms, game, timings, GuessPolicy, Guesser, History, Word, Wordle};
+
+#[derive(Debug, Clone)]
+pub struct SimulateOptions {
+    // what the games are recorded as having been played by
+    pub algorithm: String,
+    pub policy: GuessPolicy,
+    pub hard_mode: bool,
+    // How many guesses a game gets before it counts as unsolved. Like `bench`, it's more than six
+    // by default, so games that run long still say how long.
+    pub max_guesses: usize,
+    // stops before the next game once it's cancelled, and the report only has the games played
+    pub cancel: Option<Cancel>,
+}
+
+impl Default for SimulateOptions {
+    fn default() -> Self {
+        Self {
+            algorithm: "custom".to_string(),
+            policy: GuessPolicy::Strict,
+            hard_mode: false,
+            max_guesses: 32,
+            cancel: None,
+        }
+    }
+}
+
+// Play every one of `answers` with a fresh guesser from `factory`. A game where the guesser makes
+// a guess the options don't allow stops there, and is recorded as unsolved with the guesses that
+// were allowed. Answers don't have to be in the dictionary.
+pub fn simulate<G: Guesser>(
+    answers: impl IntoIterator<Item = Word>,
+    mut factory: impl FnMut() -> G,
+    options: &SimulateOptions,
+) -> RunReport {
+    let mut wordle = Wordle::new()
+        .with_policy(options.policy)
+        .allow_unknown_answers();
+    if options.hard_mode {
+        wordle = wordle.with_hard_mode();
+    }
+    let cancelled = || options.cancel.as_ref().is_some_and(Cancel::is_cancelled);
+    let games = answers
+        .into_iter()
+        .take_while(|_| !cancelled())
+        .map(|answer| play(&wordle, answer.as_str(), factory(), options))
+        .collect();
+    RunReport {
+        rules: Some(
+            Rules {
+                policy: options.policy,
+            }
+            .to_string(),
+        ),
+        wordlist: Some(WordlistHash::bundled().to_string()),
+        ..RunReport::new(games)
+    }
+}
+
+fn play(
+    wordle: &Wordle,
+    answer: &str,
+    mut guesser: impl Guesser,
+    options: &SimulateOptions,
+) -> GameRecord {
+    let mut game = wordle
+        .start(answer)
+        .expect("a word is always a well-formed answer")
+        .with_max_guesses(options.max_guesses);
+    while game.status() == game::Status::InProgress {
+        let round = timings::Phase::Scoring(game.history().len() + 1);
+        let guess = timings::time(round, || guesser.guess(game.history()));
+        if game.guess(&guess).is_err() {
+            break;
+        }
+    }
+    let guesses: Vec<_> = game.history().words().map(String::from).collect();
+    GameRecord {
+        algorithm: options.algorithm.clone(),
+        answer: answer.to_string(),
+        solved: game.status() == game::Status::Won,
+        remaining: remaining(answer, &guesses),
+        guesses,
+        replay: Vec::new(),
+    }
+}
+
+// how many of the official answers were still possible after each guess, as `bench` records it
+fn remaining(answer: &str, guesses: &[String]) -> Vec<usize> {
+    let history = History::from_answer(answer, guesses.iter().map(String::as_str));
+    let mut left: Vec<_> = algorithms::answers().collect();
+    history
+        .iter()
+        .map(|guess| {
+            left.retain(|word| guess.matches(word));
+            left.len()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulate() {
+        let answers = ["right", "cigar"].map(|word| word.parse::<Word>().unwrap());
+        let options = SimulateOptions {
+            algorithm: "stubborn".to_string(),
+            max_guesses: 6,
+            ..SimulateOptions::default()
+        };
+        let report = super::simulate(
+            answers,
+            || guesser!(|_history| { "right".to_string() }),
+            &options,
+        );
+        assert_eq!(report.rules.as_deref(), Some("strict"));
+        let [right, cigar] = &report.games[..] else {
+            panic!("two games, got {:?}", report.games);
+        };
+        assert_eq!(right.algorithm, "stubborn");
+        assert_eq!(
+            (right.score(), right.remaining.as_slice()),
+            (Some(1), &[1][..])
+        );
+        assert_eq!(cigar.score(), None);
+        assert_eq!(cigar.guesses.len(), 6);
+
+        // a guess that isn't allowed ends the game there
+        let report = super::simulate(
+            answers,
+            || guesser!(|_history| { "zzzzz".to_string() }),
+            &options,
+        );
+        assert!(report
+            .games
+            .iter()
+            .all(|g| !g.solved && g.guesses.is_empty()));
+
+        // the guesser cancels the run in the first game, which is the last one played
+        let cancel = Cancel::new();
+        let options = SimulateOptions {
+            cancel: Some(cancel.clone()),
+            ..options
+        };
+        struct Quitter(Cancel);
+        impl Guesser for Quitter {
+            fn guess(&mut self, _history: &History) -> String {
+                self.0.cancel();
+                "right".to_string()
+            }
+        }
+        let report = super::simulate(answers, || Quitter(cancel.clone()), &options);
+        assert_eq!(report.games.len(), 1);
+    }
+}
@@ -0,0 +1,195 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::daily::Day;
+
+// One attempt at a day's word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    pub day: Day,
+    pub answer: String,
+    // how many guesses it took, or None if it wasn't solved
+    pub guesses: Option<usize>,
+    // how long it took, for speed runs
+    pub time: Option<Duration>,
+}
+
+// Every game of the daily word so far, one per line like "2026-10-15 crane 4 83.214" (the
+// guesses are an X for a loss, and the time is in seconds, or a - for games that weren't timed).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Leaderboard {
+    pub runs: Vec<Run>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLeaderboardError {
+    pub line: usize,
+    pub content: String,
+}
+
+impl fmt::Display for ParseLeaderboardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: expected a day, an answer, guesses and a time, got '{}'",
+            self.line, self.content
+        )
+    }
+}
+
+impl std::error::Error for ParseLeaderboardError {}
+
+// The official game's statistics panel, plus speed run bests. Only the first game each day
+// counts, and streaks count days in a row with a win; a loss or a missed day starts over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary<'a> {
+    pub played: usize,
+    pub won: usize,
+    // how many wins took one guess, two guesses, and so on
+    pub distribution: Vec<usize>,
+    pub current_streak: usize,
+    pub max_streak: usize,
+    pub fastest: Option<&'a Run>,
+    pub fewest_guesses: Option<&'a Run>,
+}
+
+impl Leaderboard {
+    pub fn parse(text: &str) -> Result<Self, ParseLeaderboardError> {
+        let mut runs = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let err = || ParseLeaderboardError {
+                line: i + 1,
+                content: line.to_string(),
+            };
+            let fields: Vec<_> = line.split_whitespace().collect();
+            let [day, answer, guesses, time] = fields[..] else {
+                return Err(err());
+            };
+            let guesses = match guesses {
+                "X" => None,
+                n => Some(n.parse().map_err(|_| err())?),
+            };
+            let time = match time {
+                "-" => None,
+                secs => Some(
+                    secs.parse()
+                        .ok()
+                        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                        .ok_or_else(err)?,
+                ),
+            };
+            runs.push(Run {
+                day: Day::parse(day).ok_or_else(err)?,
+                answer: answer.to_string(),
+                guesses,
+                time,
+            });
+        }
+        Ok(Self { runs })
+    }
+
+    pub fn played(&self, day: Day) -> bool {
+        self.runs.iter().any(|run| run.day == day)
+    }
+
+    pub fn summary(&self, today: Day) -> Summary<'_> {
+        let mut runs: Vec<&Run> = self.runs.iter().collect();
+        runs.sort_by_key(|run| run.day);
+        // only the first attempt at a day counts
+        runs.dedup_by_key(|run| run.day);
+
+        let mut distribution = vec![0; 6];
+        for guesses in runs.iter().filter_map(|run| run.guesses) {
+            if guesses > distribution.len() {
+                distribution.resize(guesses, 0);
+            }
+            distribution[guesses - 1] += 1;
+        }
+
+        let mut streak = 0;
+        let mut max_streak = 0;
+        let mut last: Option<Day> = None;
+        for run in &runs {
+            let consecutive = last.is_some_and(|last| run.day.0 == last.0 + 1);
+            streak = match (run.guesses, consecutive) {
+                (None, _) => 0,
+                (Some(_), true) => streak + 1,
+                (Some(_), false) => 1,
+            };
+            max_streak = max_streak.max(streak);
+            last = Some(run.day);
+        }
+        // the streak is only still going if the last win was today or yesterday
+        if last.is_none_or(|last| last.0 < today.0 - 1) {
+            streak = 0;
+        }
+
+        let won = || runs.iter().copied().filter(|run| run.guesses.is_some());
+        Summary {
+            played: runs.len(),
+            won: won().count(),
+            distribution,
+            current_streak: streak,
+            max_streak,
+            fastest: won()
+                .filter(|run| run.time.is_some())
+                .min_by_key(|run| run.time),
+            fewest_guesses: won().min_by_key(|run| (run.guesses, run.time)),
+        }
+    }
+}
+
+impl fmt::Display for Leaderboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for run in &self.runs {
+            let guesses = run
+                .guesses
+                .map_or_else(|| "X".to_string(), |n| n.to_string());
+            let time = run
+                .time
+                .map_or_else(|| "-".to_string(), |t| format!("{:.3}", t.as_secs_f64()));
+            writeln!(f, "{} {} {} {}", run.day, run.answer, guesses, time)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let text = "2026-10-14 crane 4 83.214\n2026-10-15 slate X 120.000\n2026-10-16 tares 3 -\n";
+        let leaderboard = Leaderboard::parse(text).unwrap();
+        assert_eq!(leaderboard.runs[1].guesses, None);
+        assert_eq!(leaderboard.to_string(), text);
+        assert!(Leaderboard::parse("2026-10-14 crane 4").is_err());
+    }
+
+    #[test]
+    fn summary() {
+        let leaderboard = Leaderboard::parse(
+            "2026-10-01 cigar 5 60.0\n\
+             2026-10-02 rebut 3 90.0\n\
+             2026-10-03 sissy X 30.0\n\
+             2026-10-04 humph 4 45.0\n\
+             2026-10-05 awake 4 40.0\n\
+             2026-10-05 awake 2 -\n",
+        )
+        .unwrap();
+        let today = Day::parse("2026-10-06").unwrap();
+        let summary = leaderboard.summary(today);
+        assert_eq!((summary.played, summary.won), (5, 4));
+        assert_eq!(summary.distribution, [0, 0, 1, 2, 1, 0]);
+        assert_eq!((summary.current_streak, summary.max_streak), (2, 2));
+        assert_eq!(summary.fastest.unwrap().answer, "awake");
+        assert_eq!(summary.fewest_guesses.unwrap().answer, "rebut");
+        // missing a day breaks the streak
+        let summary = leaderboard.summary(Day(today.0 + 1));
+        assert_eq!(summary.current_streak, 0);
+    }
+}
@@ -0,0 +1,145 @@
+// How masks get drawn for people: colored letters in a terminal, plain B/Y/G where there's no
+// color, and the squares of a share grid. Green and yellow are hard to tell apart for a lot of
+// people, so there's also the orange and blue of Wordle's own high contrast mode.
+
+use std::str::FromStr;
+
+use crate::{Correctness, Guess, Mask, MaskExt};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Palette {
+    // green and yellow
+    #[default]
+    Standard,
+    // orange and blue
+    HighContrast,
+}
+
+impl FromStr for Palette {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "standard" => Ok(Self::Standard),
+            "high-contrast" => Ok(Self::HighContrast),
+            _ => Err(format!(
+                "unknown palette '{}' (expected standard or high-contrast)",
+                s
+            )),
+        }
+    }
+}
+
+impl Palette {
+    fn square(self, c: Correctness) -> char {
+        match (self, c) {
+            (Self::Standard, Correctness::Correct) => '🟩',
+            (Self::Standard, Correctness::Misplaced) => '🟨',
+            (Self::HighContrast, Correctness::Correct) => '🟧',
+            (Self::HighContrast, Correctness::Misplaced) => '🟦',
+            (_, Correctness::Wrong) => '⬛',
+        }
+    }
+
+    // the SGR parameters for a letter, as background then a foreground that reads on it
+    fn ansi(self, c: Correctness) -> &'static str {
+        match (self, c) {
+            (Self::Standard, Correctness::Correct) => "42;30",
+            (Self::Standard, Correctness::Misplaced) => "43;30",
+            (Self::HighContrast, Correctness::Correct) => "48;5;208;30",
+            (Self::HighContrast, Correctness::Misplaced) => "48;5;33;97",
+            (_, Correctness::Wrong) => "100;97",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+    pub color: bool,
+    pub palette: Palette,
+}
+
+impl Style {
+    // no escape codes at all, for files and pipes
+    pub fn plain() -> Self {
+        Self::default()
+    }
+
+    // color if stdout can show it (see `platform::color`)
+    pub fn detect(palette: Palette) -> Self {
+        Self {
+            color: crate::platform::color(),
+            palette,
+        }
+    }
+
+    // The mask on its own: the guess in colored letters, or B/Y/G without color.
+    pub fn mask(&self, word: &str, mask: &Mask) -> String {
+        if !self.color {
+            return mask.to_compact_string();
+        }
+        self.letters(word, mask)
+    }
+
+    // A guess and its mask: the guess in colored letters, or the guess then B/Y/G without color.
+    pub fn guess(&self, guess: &Guess) -> String {
+        self.played(guess.word.as_str(), &guess.mask)
+    }
+
+    // like `guess`, for what stands in for a word, like ????? for one that's kept hidden
+    pub fn played(&self, word: &str, mask: &Mask) -> String {
+        if !self.color {
+            return format!("{} {}", word, mask.to_compact_string());
+        }
+        self.letters(word, mask)
+    }
+
+    fn letters(&self, word: &str, mask: &Mask) -> String {
+        let mut out = String::new();
+        for (letter, &c) in word.chars().zip(mask) {
+            out.push_str(&format!(
+                "\x1b[{}m{}\x1b[0m",
+                self.palette.ansi(c),
+                letter.to_ascii_uppercase()
+            ));
+        }
+        out
+    }
+
+    // The squares people share their games with, one line per guess. These are emoji rather than
+    // escape codes, so they're the same with or without color.
+    pub fn grid(&self, history: &[Guess]) -> String {
+        history
+            .iter()
+            .map(|guess| {
+                let row: String = guess.mask.iter().map(|&c| self.palette.square(c)).collect();
+                row + "\n"
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render() {
+        let guess = Guess {
+            word: "masts".parse().unwrap(),
+            mask: Correctness::compute("right", "masts"),
+        };
+        assert_eq!(Style::plain().guess(&guess), "masts BBBYB");
+        assert_eq!(Style::plain().mask("masts", &guess.mask), "BBBYB");
+        let color = Style {
+            color: true,
+            palette: Palette::HighContrast,
+        };
+        assert!(color.guess(&guess).contains("\x1b[48;5;33;97mT\x1b[0m"));
+        let history = [guess];
+        assert_eq!(Style::plain().grid(&history), "⬛⬛⬛🟨⬛\n");
+        assert_eq!(color.grid(&history), "⬛⬛⬛🟦⬛\n");
+        assert_eq!("high-contrast".parse(), Ok(Palette::HighContrast));
+        assert!("rainbow".parse::<Palette>().is_err());
+    }
+}
@@ -0,0 +1,518 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+use crate::algorithms::{likeliest, RiskMode};
+use crate::blocklist::Blocklist;
+use crate::cancel::Cancel;
+use crate::core::{bits, bits_n, entropy, partition_counts_on_gpu, pattern_index};
+use crate::dict::Dictionary;
+use crate::openers;
+use crate::timings::{self, Phase};
+use crate::{Correctness, Guess};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SuggestOptions<'a> {
+    // never suggest these unless they're the only way to win
+    pub blocklist: Option<&'a Blocklist>,
+    // only suggest words that could still be the answer
+    pub candidates_only: bool,
+    // the words that could be the answer, when that isn't every word in the dictionary (see
+    // `Dictionary::plausible_answers` for lists that don't say)
+    pub answers: Option<&'a Dictionary>,
+    // how many guesses the game allows, for the chance of solving it; six when None
+    pub max_guesses: Option<usize>,
+    // what to suggest for the last guess
+    pub risk: RiskMode,
+    // words and letters the suggestion has to stay away from (or use)
+    pub constraints: Option<&'a Constraints>,
+    // Stops scoring guesses once it's cancelled or the deadline has passed, and suggests the best
+    // of the ones scored so far (or one of the words left, if none were). See `truncated`.
+    pub cancel: Option<&'a Cancel>,
+    pub deadline: Option<Instant>,
+}
+
+// Rules for what gets suggested, on top of the scoring. Unlike the blocklist, a banned word is
+// never suggested, even as the only way left to win: it was already played on another board, say,
+// or someone just won't play it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Constraints {
+    banned: HashSet<String>,
+    // letter sets, a bit per letter
+    with: u32,
+    without: u32,
+}
+
+impl Constraints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ban<S: AsRef<str>>(mut self, words: impl IntoIterator<Item = S>) -> Self {
+        self.banned
+            .extend(words.into_iter().map(|word| word.as_ref().to_lowercase()));
+        self
+    }
+
+    // only suggest words with every one of these letters
+    pub fn with_letters(mut self, letters: &str) -> Self {
+        self.with |= letter_set(letters);
+        self
+    }
+
+    // only suggest words with none of these letters
+    pub fn without_letters(mut self, letters: &str) -> Self {
+        self.without |= letter_set(letters);
+        self
+    }
+
+    pub fn allows(&self, word: &str) -> bool {
+        let letters = letter_set(word);
+        !self.banned.contains(word)
+            && letters & self.with == self.with
+            && letters & self.without == 0
+    }
+}
+
+// anything but a to z is left out
+fn letter_set(letters: &str) -> u32 {
+    letters
+        .bytes()
+        .map(|b| b.to_ascii_lowercase())
+        .filter(u8::is_ascii_lowercase)
+        .fold(0, |acc, b| acc | 1 << (b - b'a'))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    // None when no word in the dictionary fits the history, or the constraints rule out every
+    // word that would do
+    pub guess: Option<String>,
+    // how many words could still be the answer
+    pub remaining: usize,
+    // expected information gained from the guess, in bits
+    pub bits: f64,
+    // the chance the guess is the answer, going by the word counts
+    pub p_answer: f64,
+    // the chance of solving the game in the guesses left, playing this and then only words that
+    // could be the answer, likeliest first (see `confidence`)
+    pub p_solved: f64,
+    // scoring stopped before every guess was scored, so there may be a better one
+    pub truncated: bool,
+}
+
+// The guess `Naive` would make, worked out from nothing but the dictionary and the history, so
+// callers like web servers don't need to keep a guesser around between turns. Nothing carries
+// over from one call to the next, so every call pays for filtering the whole dictionary again.
+pub fn suggest_from_history(
+    dictionary: &Dictionary,
+    history: &[Guess],
+    options: &SuggestOptions,
+) -> Suggestion {
+    suggest_n(dictionary, history, options)
+}
+
+// `suggest_from_history` for words of any length, which the dictionary's have to be (see
+// `length::suggest`)
+pub(crate) fn suggest_n<const N: usize>(
+    dictionary: &Dictionary,
+    history: &[Guess<N>],
+    options: &SuggestOptions,
+) -> Suggestion {
+    let remaining = timings::time(Phase::Filtering, || {
+        remaining(options.answers.unwrap_or(dictionary), history)
+    });
+    let left = options
+        .max_guesses
+        .unwrap_or(6)
+        .saturating_sub(history.len());
+    let suggestion = |guess: Option<&str>| {
+        let (p_answer, p_solved) =
+            guess.map_or((0.0, 0.0), |g| confidence_n::<N>(&remaining, g, left));
+        Suggestion {
+            guess: guess.map(str::to_string),
+            remaining: remaining.len(),
+            bits: guess.map_or(0.0, |guess| score::<N>(&remaining, guess)),
+            p_answer,
+            p_solved,
+            truncated: false,
+        }
+    };
+    let allowed = |word: &str| options.constraints.is_none_or(|c| c.allows(word));
+    match remaining.len() {
+        0 => return suggestion(None),
+        1 if allowed(remaining[0].0) => return suggestion(Some(remaining[0].0)),
+        _ => {}
+    }
+
+    if options.risk == RiskMode::Win && left == 1 {
+        let allowed: Vec<_> = remaining
+            .iter()
+            .copied()
+            .filter(|&(w, _)| allowed(w))
+            .collect();
+        return suggestion(likeliest(history, &allowed));
+    }
+
+    let blocked = |word: &str| options.blocklist.is_some_and(|b| b.contains(word));
+    // `Naive`'s bundled opener, since it's expensive to compute, but only for the words and
+    // counts it was worked out with
+    let opener = openers::bundled("naive").filter(|&opener| {
+        N == 5
+            && history.is_empty()
+            && !options.candidates_only
+            && options.answers.is_none()
+            && !blocked(opener)
+            && allowed(opener)
+            && dictionary == Dictionary::shared()
+    });
+    if opener.is_some() {
+        return suggestion(opener);
+    }
+
+    let guesses: Vec<&str> = if options.candidates_only {
+        remaining.iter().map(|&(word, _)| word).collect()
+    } else {
+        dictionary.0.keys().map(String::as_str).collect()
+    };
+    let guesses: Vec<&str> = guesses
+        .into_iter()
+        .filter(|&guess| !blocked(guess) && allowed(guess))
+        .collect();
+    // the GPU only knows five letter words
+    let on_gpu = (N == 5)
+        .then(|| partition_counts_on_gpu(&guesses, &remaining))
+        .flatten();
+    let stop = || {
+        options.cancel.is_some_and(Cancel::is_cancelled)
+            || options
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    };
+    let mut best: Option<(&str, f64)> = None;
+    let mut truncated = false;
+    for (i, &guess) in guesses.iter().enumerate() {
+        if stop() {
+            truncated = true;
+            break;
+        }
+        let goodness = match &on_gpu {
+            Some(counts) => entropy(counts[i]),
+            None => score::<N>(&remaining, guess),
+        };
+        // a guess that can't split the candidates up gets us nowhere
+        if goodness > 0.0 && best.is_none_or(|(_, b)| goodness > b) {
+            best = Some((guess, goodness));
+        }
+    }
+    let suggestion = match best {
+        Some((guess, _)) => suggestion(Some(guess)),
+        // only blocked words could tell the rest apart (or there was no time to find out), so
+        // just try one of them (that's allowed)
+        None => suggestion(remaining.iter().map(|&(w, _)| w).find(|&w| allowed(w))),
+    };
+    Suggestion {
+        truncated,
+        ..suggestion
+    }
+}
+
+// The chance `guess` is the answer, and the chance of solving the game within `left` guesses
+// (this one included) by playing it and then the likeliest word that could still be the answer
+// every time after. That's the best there is with two guesses left or fewer; with more it's a
+// floor, since a guess that can't win can still narrow things down for the ones after it.
+pub fn confidence(remaining: &[(&str, u64)], guess: &str, left: usize) -> (f64, f64) {
+    confidence_n::<5>(remaining, guess, left)
+}
+
+fn confidence_n<const N: usize>(remaining: &[(&str, u64)], guess: &str, left: usize) -> (f64, f64) {
+    let total: u64 = remaining.iter().map(|&(_, count)| count).sum();
+    if total == 0 || left == 0 {
+        return (0.0, 0.0);
+    }
+    let mut buckets: HashMap<usize, Vec<u64>> = HashMap::new();
+    let mut answer = 0;
+    for &(word, count) in remaining {
+        if word == guess {
+            answer = count;
+        } else {
+            let mask = Correctness::compute_n::<N>(word, guess);
+            buckets.entry(pattern_index(mask)).or_default().push(count);
+        }
+    }
+    let solved_later: u64 = buckets
+        .into_values()
+        .map(|mut counts| {
+            counts.sort_unstable_by(|a, b| b.cmp(a));
+            counts.iter().take(left - 1).sum::<u64>()
+        })
+        .sum();
+    let p_answer = answer as f64 / total as f64;
+    (p_answer, p_answer + solved_later as f64 / total as f64)
+}
+
+// How good one guess of a game was, next to the guess we'd have suggested instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grade {
+    pub guess: String,
+    // expected information the guess gained, in bits
+    pub bits: f64,
+    pub best: Suggestion,
+    // 0 to 100, where 100 is as good as the suggestion
+    pub score: u32,
+    // how many words were left after it
+    pub left: usize,
+    // the information it actually gained, in bits, and how much more (or less) that was than
+    // expected
+    pub gained: f64,
+    pub luck: f64,
+}
+
+// Grade every guess of a game by how much it could be expected to narrow things down, next to
+// the best guess at that point. Only the information counts, not whether the guess happened to
+// be lucky.
+pub fn grade(dictionary: &Dictionary, history: &[Guess]) -> Vec<Grade> {
+    (0..history.len())
+        .map(|i| {
+            let before = &history[..i];
+            let best = suggest_from_history(dictionary, before, &SuggestOptions::default());
+            let remaining = remaining(dictionary, before);
+            let guess = history[i].word.as_str();
+            let bits = bits(&remaining, guess);
+            let score = if best.bits > 0.0 {
+                // the opener we suggest isn't quite the best there is
+                (100.0 * bits / best.bits).round().min(100.0) as u32
+            } else if remaining.iter().any(|&(word, _)| word == guess) {
+                // nothing left to learn, so the only good guess is the answer
+                100
+            } else {
+                0
+            };
+            let total: u64 = remaining.iter().map(|&(_, count)| count).sum();
+            let after: Vec<_> = remaining
+                .iter()
+                .filter(|(word, _)| history[i].matches(word))
+                .collect();
+            let kept: u64 = after.iter().map(|&&(_, count)| count).sum();
+            // nothing's left when the masks contradict each other, and then there's nothing to go by
+            let gained = if kept > 0 {
+                (total as f64 / kept as f64).log2()
+            } else {
+                0.0
+            };
+            Grade {
+                guess: guess.to_string(),
+                bits,
+                best,
+                score,
+                left: after.len(),
+                gained,
+                luck: gained - bits,
+            }
+        })
+        .collect()
+}
+
+// `bits`, with `bits_n` only for the lengths that need it, since it allocates its buckets
+fn score<const N: usize>(remaining: &[(&str, u64)], guess: &str) -> f64 {
+    if N == 5 {
+        bits(remaining, guess)
+    } else {
+        bits_n::<N>(remaining, guess)
+    }
+}
+
+// the words that could still be the answer, with their counts
+fn remaining<'d, const N: usize>(
+    dictionary: &'d Dictionary,
+    history: &[Guess<N>],
+) -> Vec<(&'d str, u64)> {
+    dictionary
+        .0
+        .iter()
+        .filter(|(word, _)| history.iter().all(|guess| guess.matches(word)))
+        .map(|(word, &count)| (word.as_str(), count))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::Naive;
+    use crate::{Guesser, History};
+
+    fn history(answer: &str, guesses: &[&str]) -> History {
+        History::from_answer(answer, guesses.iter().copied())
+    }
+
+    #[test]
+    fn agrees_with_naive() {
+        let dictionary = Dictionary::bundled();
+        let history = history("right", &["tares"]);
+        let suggestion = suggest_from_history(&dictionary, &history, &Default::default());
+        assert_eq!(suggestion.guess, Some(Naive::new().guess(&history)));
+        assert!(suggestion.bits > 0.0);
+    }
+
+    #[test]
+    fn opener() {
+        let dictionary = Dictionary::bundled();
+        let opener = suggest_from_history(&dictionary, &[], &Default::default());
+        assert_eq!(opener.guess.as_deref(), openers::bundled("naive"));
+        assert!(opener.bits > 0.0);
+        // other counts (or words) can have another opener, so it's worked out for them
+        let dictionary = Dictionary::parse("tares 1\nbaker 5\ncaker 5\ndaker 5\n").unwrap();
+        let opener = suggest_from_history(&dictionary, &[], &Default::default());
+        assert_eq!(opener.guess.as_deref(), Some("baker"));
+    }
+
+    #[test]
+    fn small_dictionary() {
+        let dictionary = Dictionary::parse("baker 1\ncaker 1\ndaker 1\neaten 1\n").unwrap();
+        let history = history("caker", &["eaten"]);
+        let options = SuggestOptions {
+            candidates_only: true,
+            ..Default::default()
+        };
+        let suggestion = suggest_from_history(&dictionary, &history, &options);
+        assert_eq!(suggestion.remaining, 3);
+        assert_eq!(suggestion.guess.as_deref(), Some("baker"));
+
+        let history = self::history("zzzzz", &["baker"]);
+        let suggestion = suggest_from_history(&dictionary, &history, &options);
+        assert_eq!(suggestion.remaining, 0);
+        assert_eq!(suggestion.guess, None);
+    }
+
+    #[test]
+    fn cancelled() {
+        let dictionary = Dictionary::parse("baker 1\ncaker 1\ndaker 1\neaten 1\n").unwrap();
+        let history = history("caker", &["eaten"]);
+        let cancel = Cancel::new();
+        cancel.cancel();
+        let options = SuggestOptions {
+            cancel: Some(&cancel),
+            ..Default::default()
+        };
+        // nothing got scored, but there's still something to play
+        let suggestion = suggest_from_history(&dictionary, &history, &options);
+        assert_eq!(suggestion.remaining, 3);
+        assert!(suggestion.truncated);
+        assert!(suggestion.guess.is_some_and(|guess| guess != "eaten"));
+
+        let options = SuggestOptions {
+            deadline: Some(Instant::now()),
+            ..Default::default()
+        };
+        assert!(suggest_from_history(&dictionary, &history, &options).truncated);
+        assert!(!suggest_from_history(&dictionary, &history, &Default::default()).truncated);
+    }
+
+    #[test]
+    fn separate_answers() {
+        let dictionary = Dictionary::parse("baker 1\ncaker 1\ndaker 1\neaten 1\n").unwrap();
+        let answers = Dictionary::parse("baker 1\ncaker 1\n").unwrap();
+        let options = SuggestOptions {
+            answers: Some(&answers),
+            ..Default::default()
+        };
+        let suggestion = suggest_from_history(&dictionary, &[], &options);
+        assert_eq!(suggestion.remaining, 2);
+        // either tells the two apart, so it may as well be one of them
+        assert_eq!(suggestion.guess.as_deref(), Some("baker"));
+    }
+
+    #[test]
+    fn confidence() {
+        let remaining = [("baker", 3), ("caker", 1), ("daker", 1), ("eaten", 5)];
+        assert_eq!(super::confidence(&remaining, "eaten", 1), (0.5, 0.5));
+        // after eaten, the rest look the same, and baker is the likeliest of them
+        assert_eq!(super::confidence(&remaining, "eaten", 2), (0.5, 0.8));
+        assert_eq!(super::confidence(&remaining, "eaten", 4), (0.5, 1.0));
+        assert_eq!(super::confidence(&remaining, "zzzzz", 1), (0.0, 0.0));
+        assert_eq!(super::confidence(&remaining, "eaten", 0), (0.0, 0.0));
+
+        let dictionary = Dictionary::parse("baker 1\ncaker 1\n").unwrap();
+        let options = SuggestOptions {
+            max_guesses: Some(2),
+            ..Default::default()
+        };
+        let history = history("caker", &["eaten"]);
+        let suggestion = suggest_from_history(&dictionary, &history, &options);
+        assert_eq!((suggestion.p_answer, suggestion.p_solved), (0.5, 0.5));
+    }
+
+    #[test]
+    fn constraints() {
+        let constraints = Constraints::new()
+            .ban(["Tares"])
+            .with_letters("e")
+            .without_letters("o");
+        assert!(!constraints.allows("tares"));
+        assert!(constraints.allows("crane"));
+        assert!(!constraints.allows("stair"));
+        assert!(!constraints.allows("stoke"));
+
+        let dictionary = Dictionary::bundled();
+        let options = SuggestOptions {
+            constraints: Some(&constraints),
+            ..Default::default()
+        };
+        let history = history("right", &["lunch"]);
+        let guess = suggest_from_history(&dictionary, &history, &options).guess;
+        assert!(constraints.allows(&guess.unwrap()));
+
+        // banning the only word left leaves nothing to suggest
+        let dictionary = Dictionary::parse("baker 1\neaten 1\n").unwrap();
+        let history = self::history("baker", &["eaten"]);
+        let banned = Constraints::new().ban(["baker"]);
+        let options = SuggestOptions {
+            constraints: Some(&banned),
+            ..Default::default()
+        };
+        assert_eq!(
+            suggest_from_history(&dictionary, &history, &options).guess,
+            None
+        );
+    }
+
+    #[test]
+    fn last_guess() {
+        let dictionary =
+            Dictionary::parse("fight 10\nmight 40\nright 30\nsight 20\nfjord 1\n").unwrap();
+        let options = SuggestOptions {
+            max_guesses: Some(1),
+            ..Default::default()
+        };
+        // fjord tells the most apart, but can't win
+        let score = suggest_from_history(&dictionary, &[], &options);
+        assert_eq!(score.guess.as_deref(), Some("fjord"));
+        let options = SuggestOptions {
+            risk: RiskMode::Win,
+            ..options
+        };
+        let win = suggest_from_history(&dictionary, &[], &options);
+        assert_eq!(win.guess.as_deref(), Some("might"));
+        assert!(win.p_solved > score.p_solved);
+    }
+
+    #[test]
+    fn grades() {
+        let dictionary = Dictionary::parse("baker 1\ncaker 1\ndaker 1\neaten 1\n").unwrap();
+        let grades = grade(
+            &dictionary,
+            &history("caker", &["eaten", "zzzzz", "baker", "caker"]),
+        );
+        let scores: Vec<_> = grades.iter().map(|g| g.score).collect();
+        // eaten only splits off itself, zzzzz tells us nothing, and by the end there's nothing
+        // left to learn
+        assert_eq!(scores, [54, 0, 100, 100]);
+        assert_eq!(grades[1].best.remaining, 3);
+        // eaten could have been the answer, but only split itself off
+        assert_eq!(grades[0].left, 3);
+        assert!(grades[0].luck < 0.0);
+        // baker leaves caker and daker
+        assert_eq!(grades[2].left, 2);
+        assert!((grades[2].gained - 1.5f64.log2()).abs() < 1e-9);
+        assert_eq!(grades[1].gained, 0.0);
+    }
+}
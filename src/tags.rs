@@ -0,0 +1,104 @@
+// Labels on answers, for breaking benchmark results down by the kind of word (see
+// `results::by_tag`), since an average over every answer hides which words an algorithm has a
+// hard time with. Some come built in, worked out from the word itself; any others come from a
+// file.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::dict::Dictionary;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tags {
+    tags: HashMap<String, BTreeSet<String>>,
+}
+
+impl Tags {
+    // The built-in tags for each of `answers`: whether a letter shows up more than once, whether
+    // it ends in a vowel, and which quarter of the answers it's in by how common the word is in
+    // `dictionary` (q1 being the most common, and words it doesn't have counting as rarest).
+    pub fn builtin(answers: &[&str], dictionary: &Dictionary) -> Self {
+        let mut tags = Self::default();
+        let mut by_count: Vec<_> = answers.to_vec();
+        by_count.sort_unstable();
+        by_count.dedup();
+        // stable, so ties stay alphabetical
+        by_count.sort_by_key(|word| std::cmp::Reverse(dictionary.0.get(*word).copied()));
+        let n = by_count.len();
+        for (rank, word) in by_count.into_iter().enumerate() {
+            let letters: BTreeSet<_> = word.chars().collect();
+            if letters.len() < word.chars().count() {
+                tags.add(word, "repeated-letters");
+            }
+            if word.ends_with(['a', 'e', 'i', 'o', 'u']) {
+                tags.add(word, "ends-in-vowel");
+            }
+            tags.add(word, &format!("frequency-q{}", 4 * rank / n + 1));
+        }
+        tags
+    }
+
+    // A word and then its tags on each line, like "llama repeated-letters animal". Blank lines
+    // and lines starting with '#' are ignored, and a word on several lines gets all their tags.
+    pub fn parse(text: &str) -> Self {
+        let mut tags = Self::default();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let word = fields.next().expect("the line isn't blank").to_lowercase();
+            for tag in fields {
+                tags.add(&word, tag);
+            }
+        }
+        tags
+    }
+
+    pub fn add(&mut self, word: &str, tag: &str) {
+        self.tags
+            .entry(word.to_string())
+            .or_default()
+            .insert(tag.to_string());
+    }
+
+    // every tag in `other` as well
+    pub fn merge(mut self, other: Tags) -> Self {
+        for (word, tags) in other.tags {
+            self.tags.entry(word).or_default().extend(tags);
+        }
+        self
+    }
+
+    // a word's tags, in alphabetical order
+    pub fn of(&self, word: &str) -> impl Iterator<Item = &str> {
+        self.tags
+            .get(word)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags() {
+        let dictionary = Dictionary::parse("llama 10\nright 40\nsissy 5\nfjord 20").unwrap();
+        let builtin = Tags::builtin(&["sissy", "llama", "right", "fjord"], &dictionary);
+        fn of<'a>(tags: &'a Tags, word: &str) -> Vec<&'a str> {
+            tags.of(word).collect()
+        }
+        assert_eq!(
+            of(&builtin, "llama"),
+            ["ends-in-vowel", "frequency-q3", "repeated-letters"]
+        );
+        assert_eq!(of(&builtin, "right"), ["frequency-q1"]);
+        assert_eq!(of(&builtin, "sissy"), ["frequency-q4", "repeated-letters"]);
+
+        let tags = builtin.merge(Tags::parse("# mine\nFjord place\n\nfjord nordic\n"));
+        assert_eq!(of(&tags, "fjord"), ["frequency-q2", "nordic", "place"]);
+        assert_eq!(of(&tags, "cigar"), Vec::<&str>::new());
+    }
+}
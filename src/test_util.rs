@@ -0,0 +1,124 @@
+// proptest strategies for words, masks and histories, and checks for guessers written outside
+// this crate, so they can be put through the same kind of tests as the ones in it:
+//
+//   proptest! {
+//       #[test]
+//       fn hard_mode((_, history) in test_util::game(4)) {
+//           test_util::check_hard_mode(&mut MyGuesser::new().hard_mode(), &history)?;
+//       }
+//   }
+
+use proptest::prelude::*;
+use proptest::sample::select;
+use proptest::test_runner::TestCaseError;
+
+use crate::hard_mode::is_legal_hard_mode;
+use crate::{Correctness, Guesser, History, Mask};
+
+// any five lowercase letters, whether or not they make a word
+pub fn letters() -> impl Strategy<Value = String> {
+    "[a-z]{5}"
+}
+
+// a word from the dictionary
+pub fn word() -> impl Strategy<Value = String> {
+    let words: Vec<&'static str> = crate::algorithms::dictionary().map(|(w, _)| w).collect();
+    select(words).prop_map(str::to_string)
+}
+
+// a word from the answer list
+pub fn answer() -> impl Strategy<Value = String> {
+    let answers: Vec<&'static str> = crate::algorithms::answers().collect();
+    select(answers).prop_map(str::to_string)
+}
+
+// Any five colors. Some of these can't come up in a game (four greens and a yellow, say), which
+// is what to use for testing how a guesser handles masks that were typed in wrong.
+pub fn mask() -> impl Strategy<Value = Mask> {
+    let correctness = prop_oneof![
+        Just(Correctness::Correct),
+        Just(Correctness::Misplaced),
+        Just(Correctness::Wrong),
+    ];
+    [
+        correctness.clone(),
+        correctness.clone(),
+        correctness.clone(),
+        correctness.clone(),
+        correctness,
+    ]
+}
+
+// An answer, and the history of up to `max_guesses` dictionary words guessed at it. The game
+// isn't over yet, so there's always a next guess to ask for.
+pub fn game(max_guesses: usize) -> impl Strategy<Value = (String, History)> {
+    (answer(), prop::collection::vec(word(), 0..=max_guesses))
+        .prop_map(|(answer, words)| {
+            let history = History::from_answer(&answer, words.iter().map(String::as_str));
+            (answer, history)
+        })
+        .prop_filter("the game was already won", |(_, history)| !history.is_won())
+}
+
+// The guesser's next guess after `history` is five lowercase letters, and one hard mode
+// allows: every green in place and every letter found used again.
+pub fn check_hard_mode(guesser: &mut impl Guesser, history: &History) -> Result<(), TestCaseError> {
+    let guess = guesser.guess(history);
+    prop_assert!(
+        guess.len() == 5 && guess.bytes().all(|b| b.is_ascii_lowercase()),
+        "'{}' isn't a five letter guess",
+        guess
+    );
+    prop_assert!(
+        is_legal_hard_mode(history, &guess),
+        "'{}' ignores a hint in {:?}",
+        guess,
+        history
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn games_are_consistent((answer, history) in game(4)) {
+            prop_assert!(history.check().is_ok());
+            prop_assert!(history.iter().all(|guess| guess.matches(&answer)));
+        }
+
+        #[test]
+        fn greedy_hard_mode((_, history) in game(3)) {
+            // over the answers only and with the opener given, since a debug build takes a while
+            // to work through the whole dictionary
+            let answers = crate::algorithms::answers().map(|word| (word, 1));
+            let mut greedy =
+                crate::algorithms::Greedy::with_words(crate::algorithms::Entropy, answers)
+                    .with_opener("tares")
+                    .hard_mode();
+            check_hard_mode(&mut greedy, &history)?;
+        }
+    }
+
+    proptest! {
+        // naive goes through the whole dictionary, so fewer of these
+        #![proptest_config(ProptestConfig::with_cases(4))]
+
+        #[test]
+        fn naive_hard_mode((_, history) in game(3)) {
+            check_hard_mode(&mut crate::algorithms::Naive::new().hard_mode(), &history)?;
+        }
+    }
+
+    #[test]
+    fn catches_easy_mode() {
+        // after tares, lorry keeps the green r but drops the yellow s
+        let history = History::from_answer("sprig", ["tares"]);
+        let mut easy = guesser!(|_history| { "lorry".to_string() });
+        assert!(check_hard_mode(&mut easy, &history).is_err());
+    }
+}
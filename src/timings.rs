@@ -0,0 +1,130 @@
+// How long the program spends in each part of the work, for telling which one is to blame when
+// it's slow. It's off until `enable` is called, and then costs two clock reads per timed call.
+//
+// Phases nest (picking a guess includes filtering by the history first, and building an opener
+// includes scoring), and time spent in an inner phase only counts towards the inner one, so the
+// phases add up to no more than the time they cover.
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    // reading and parsing word lists
+    Load,
+    // work done up front to speed up the games, like decision trees, mask tables and openers
+    Precompute,
+    // cutting the candidates down to the ones that fit the history
+    Filtering,
+    // picking a guess, by round (the first is 1, and everything from `ROUNDS` on is lumped in)
+    Scoring(usize),
+}
+
+// rounds timed separately, the last one standing for every round after it as well
+pub const ROUNDS: usize = 7;
+
+const SLOTS: usize = 3 + ROUNDS;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static NANOS: [AtomicU64; SLOTS] = [const { AtomicU64::new(0) }; SLOTS];
+static CALLS: [AtomicU64; SLOTS] = [const { AtomicU64::new(0) }; SLOTS];
+
+thread_local! {
+    // time spent in phases nested inside the one running on this thread
+    static INNER: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+impl Phase {
+    fn slot(self) -> usize {
+        match self {
+            Phase::Load => 0,
+            Phase::Precompute => 1,
+            Phase::Filtering => 2,
+            Phase::Scoring(round) => 3 + round.clamp(1, ROUNDS) - 1,
+        }
+    }
+
+    fn from_slot(slot: usize) -> Self {
+        match slot {
+            0 => Phase::Load,
+            1 => Phase::Precompute,
+            2 => Phase::Filtering,
+            _ => Phase::Scoring(slot - 2),
+        }
+    }
+}
+
+impl std::fmt::Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phase::Load => write!(f, "loading words"),
+            Phase::Precompute => write!(f, "precomputing"),
+            Phase::Filtering => write!(f, "filtering"),
+            Phase::Scoring(round) if *round >= ROUNDS => write!(f, "scoring round {}+", ROUNDS),
+            Phase::Scoring(round) => write!(f, "scoring round {}", round),
+        }
+    }
+}
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+// run `f`, counting the time it takes towards `phase` (less whatever it spends in other phases)
+pub fn time<T>(phase: Phase, f: impl FnOnce() -> T) -> T {
+    if !enabled() {
+        return f();
+    }
+    let outer = INNER.with(|inner| inner.replace(Duration::ZERO));
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    let inner = INNER.with(|inner| inner.replace(outer + elapsed));
+    let slot = phase.slot();
+    let own = elapsed.saturating_sub(inner).as_nanos() as u64;
+    NANOS[slot].fetch_add(own, Ordering::Relaxed);
+    CALLS[slot].fetch_add(1, Ordering::Relaxed);
+    result
+}
+
+// every phase that was timed at least once, with its total time and how many times it ran
+pub fn report() -> Vec<(Phase, Duration, u64)> {
+    (0..SLOTS)
+        .filter_map(|slot| {
+            let calls = CALLS[slot].load(Ordering::Relaxed);
+            let nanos = NANOS[slot].load(Ordering::Relaxed);
+            (calls > 0).then(|| (Phase::from_slot(slot), Duration::from_nanos(nanos), calls))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested() {
+        enable();
+        let (answer, inner) = time(Phase::Precompute, || {
+            let answer = time(Phase::Scoring(ROUNDS + 1), || {
+                std::thread::sleep(Duration::from_millis(20));
+                42
+            });
+            // what gets taken out of this phase's time for the one inside it
+            (answer, INNER.with(Cell::get))
+        });
+        assert_eq!(answer, 42);
+        assert!(inner >= Duration::from_millis(20));
+        // later rounds all go under the last one
+        assert!(report().iter().any(|&(phase, time, _)| {
+            phase == Phase::Scoring(ROUNDS) && time >= Duration::from_millis(20)
+        }));
+        assert_eq!(Phase::Scoring(ROUNDS).to_string(), "scoring round 7+");
+        assert_eq!(Phase::Scoring(3).to_string(), "scoring round 3");
+    }
+}
@@ -0,0 +1,136 @@
+use std::fmt;
+
+use crate::style::Style;
+use crate::Guess;
+
+// One guess of a game, with what it did to the candidates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step<'a> {
+    pub guess: Guess,
+    // how many candidates were left before and after the guess
+    pub before: usize,
+    pub after: usize,
+    // whether the guess could still have been the answer when it was played
+    pub candidate: bool,
+    // the candidates left after the guess
+    pub left: Vec<&'a str>,
+}
+
+impl Step<'_> {
+    // the information the guess gained
+    pub fn bits(&self) -> f64 {
+        if self.after == 0 {
+            return 0.0;
+        }
+        (self.before as f64 / self.after as f64).log2()
+    }
+}
+
+// A game played out with every guess annotated, for looking into why it went badly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transcript<'a> {
+    pub steps: Vec<Step<'a>>,
+}
+
+impl<'a> Transcript<'a> {
+    pub fn annotate(history: &[Guess], candidates: &[&'a str]) -> Self {
+        let mut left = candidates.to_vec();
+        let mut steps = Vec::new();
+        for guess in history {
+            let before = left.len();
+            let candidate = left.contains(&guess.word.as_str());
+            left.retain(|word| guess.matches(word));
+            steps.push(Step {
+                guess: *guess,
+                before,
+                after: left.len(),
+                candidate,
+                left: left.clone(),
+            });
+        }
+        Self { steps }
+    }
+
+    // shown with the masks drawn in `style`, rather than plain like `Display` does
+    pub fn styled(&self, style: Style) -> Styled<'_, 'a> {
+        Styled {
+            transcript: self,
+            style,
+        }
+    }
+}
+
+// how many of the candidates left to list before eliding the rest
+const SHOW_LEFT: usize = 12;
+
+// a `Transcript` shown in a `Style`
+pub struct Styled<'t, 'a> {
+    transcript: &'t Transcript<'a>,
+    style: Style,
+}
+
+impl fmt::Display for Transcript<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.styled(Style::plain()).fmt(f)
+    }
+}
+
+impl fmt::Display for Styled<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, step) in self.transcript.steps.iter().enumerate() {
+            write!(
+                f,
+                "{}. {}  {} -> {} left, {:.2} bits",
+                i + 1,
+                self.style.guess(&step.guess),
+                step.before,
+                step.after,
+                step.bits()
+            )?;
+            if !step.candidate {
+                write!(f, " (couldn't have been the answer)")?;
+            }
+            writeln!(f)?;
+            if step.after > 1 {
+                let shown: Vec<_> = step.left.iter().take(SHOW_LEFT).copied().collect();
+                let more = step.after.saturating_sub(SHOW_LEFT);
+                write!(f, "   left: {}", shown.join(" "))?;
+                if more > 0 {
+                    write!(f, " and {} more", more)?;
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Correctness;
+
+    #[test]
+    fn annotate() {
+        let candidates = ["right", "might", "sight", "fight"];
+        let history: Vec<_> = ["masts", "fight", "right"]
+            .iter()
+            .map(|word| Guess {
+                word: word.parse().unwrap(),
+                mask: Correctness::compute("right", word),
+            })
+            .collect();
+        let transcript = Transcript::annotate(&history, &candidates);
+        let steps: Vec<_> = transcript
+            .steps
+            .iter()
+            .map(|step| (step.before, step.after, step.candidate))
+            .collect();
+        assert_eq!(steps, [(4, 2, false), (2, 1, true), (1, 1, true)]);
+        assert_eq!(transcript.steps[0].left, ["right", "fight"]);
+        assert_eq!(transcript.steps[0].bits(), 1.0);
+        assert!(transcript
+            .to_string()
+            .starts_with("1. masts BBBYB  4 -> 2 left, 1.00 bits (couldn't have been the answer)\n   left: right fight\n"));
+    }
+}
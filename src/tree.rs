@@ -0,0 +1,286 @@
+use std::fmt;
+
+use crate::{Correctness, Guess, Mask, MaskExt, Word};
+
+// A complete strategy: what to guess, and where to go next for each mask that guess can get
+// back. A node with no children is a guess we expect to be right.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tree {
+    pub guess: String,
+    pub children: Vec<([Correctness; 5], Tree)>,
+}
+
+impl Tree {
+    pub fn leaf(guess: impl Into<String>) -> Self {
+        Self {
+            guess: guess.into(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn child(&self, mask: &[Correctness; 5]) -> Option<&Tree> {
+        self.children
+            .iter()
+            .find(|(m, _)| m == mask)
+            .map(|(_, tree)| tree)
+    }
+
+    // walk down the tree along `history`; None if the game has strayed from the strategy
+    pub fn follow(&self, history: &[Guess]) -> Option<&Tree> {
+        let mut node = self;
+        for guess in history {
+            if node.guess != guess.word.as_str() {
+                return None;
+            }
+            node = node.child(&guess.mask)?;
+        }
+        Some(node)
+    }
+
+    // play `answer` by following the tree, returning every guess made and whether the last one
+    // won; a game the tree can't finish stops at the first mask it has no answer for
+    pub fn replay(&self, answer: &str) -> (Vec<Guess>, bool) {
+        let mut history = Vec::new();
+        let mut node = self;
+        loop {
+            let mask = Correctness::compute(answer, &node.guess);
+            let word = Word::new(&node.guess).expect("trees only guess five letter words");
+            history.push(Guess { word, mask });
+            if mask.is_win() {
+                return (history, true);
+            }
+            match node.child(&mask) {
+                Some(child) => node = child,
+                None => return (history, false),
+            }
+        }
+    }
+
+    // Write the tree in the plain-text format other solvers use: one line per answer with every
+    // guess and the mask it got (B for gray, Y for yellow, G for green), ending with GGGGG and the
+    // number of guesses. Whatever a line shares with the line above is blanked out, which makes
+    // the tree structure show up as indentation:
+    //
+    //   salet BBBBB courd BBBBB nymph GGGGG3
+    //                     BBBBY pygmy GGGGG3
+    //
+    // Only `answers` show up in the output, since the tree doesn't know which of its inner
+    // guesses could also be right.
+    pub fn export(&self, answers: &[&str]) -> String {
+        let mut lines = Vec::new();
+        let mut path = Vec::new();
+        self.export_into(answers, &mut path, &mut lines);
+
+        let mut out = String::new();
+        let mut previous: &[(String, String)] = &[];
+        for line in &lines {
+            let shared = line
+                .iter()
+                .zip(previous)
+                .take(line.len() - 1)
+                .take_while(|(a, b)| a == b)
+                .count();
+            // the guess after a shared prefix is always the same too, so blank that out as well
+            let rest: Vec<_> = line[shared..]
+                .iter()
+                .map(|(guess, mask)| format!("{} {}", guess, mask))
+                .collect();
+            let rest = rest.join(" ");
+            if previous.len() > shared {
+                out.push_str(&" ".repeat(shared * 12 + 6));
+                out.push_str(&rest[6..]);
+            } else {
+                out.push_str(&" ".repeat(shared * 12));
+                out.push_str(&rest);
+            }
+            out.push_str(&line.len().to_string());
+            out.push('\n');
+            previous = line;
+        }
+        out
+    }
+
+    fn export_into(
+        &self,
+        answers: &[&str],
+        path: &mut Vec<(String, String)>,
+        lines: &mut Vec<Vec<(String, String)>>,
+    ) {
+        if answers.contains(&&*self.guess) {
+            let mut line = path.clone();
+            line.push((self.guess.clone(), "GGGGG".to_string()));
+            lines.push(line);
+        }
+        for (mask, child) in &self.children {
+            let bucket: Vec<&str> = answers
+                .iter()
+                .copied()
+                .filter(|answer| Correctness::compute(answer, &self.guess) == *mask)
+                .collect();
+            if bucket.is_empty() {
+                continue;
+            }
+            path.push((self.guess.clone(), mask.to_compact_string()));
+            child.export_into(&bucket, path, lines);
+            path.pop();
+        }
+    }
+
+    // Read a tree in the format written by `export`, skipping lines that start with #.
+    pub fn import(text: &str) -> Result<Self, ParseTreeError> {
+        let mut root: Option<Tree> = None;
+        let mut previous = String::new();
+        for (i, line) in text.lines().enumerate() {
+            let err = |reason: &str| ParseTreeError {
+                line: i + 1,
+                reason: reason.to_string(),
+            };
+            // comments, like the word list the tree was made with, aren't part of the tree
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            // fill the blanked-out prefix back in from the line above
+            let indent = line.len() - line.trim_start().len();
+            if indent > previous.len() || !previous.is_char_boundary(indent) {
+                return Err(err("indented past the end of the previous line"));
+            }
+            let expanded = format!("{}{}", &previous[..indent], line.trim_start());
+
+            let tokens: Vec<&str> = expanded.split_whitespace().collect();
+            if !tokens.len().is_multiple_of(2) {
+                return Err(err("expected guess and mask pairs"));
+            }
+            let mut path = Vec::new();
+            for pair in tokens.chunks(2) {
+                let (guess, mask) = (pair[0], pair[1]);
+                if guess.len() != 5 || !guess.bytes().all(|b| b.is_ascii_lowercase()) {
+                    return Err(err("guesses must be five lowercase letters"));
+                }
+                path.push((guess, mask));
+            }
+            let length = path.len();
+            let (_, last) = path.last_mut().expect("line isn't empty");
+            let guesses = last
+                .strip_prefix("GGGGG")
+                .ok_or_else(|| err("lines must end with a GGGGG mask"))?;
+            if guesses.parse::<usize>().ok() != Some(length) {
+                return Err(err("guess count doesn't match the line"));
+            }
+            *last = "GGGGG";
+
+            let node = root.get_or_insert_with(|| Tree::leaf(path[0].0));
+            node.insert(&path).map_err(err)?;
+            previous = expanded;
+        }
+        root.ok_or(ParseTreeError {
+            line: 0,
+            reason: "the tree is empty".to_string(),
+        })
+    }
+
+    fn insert(&mut self, path: &[(&str, &str)]) -> Result<(), &'static str> {
+        let (guess, mask) = path[0];
+        if self.guess != guess {
+            return Err("conflicts with an earlier guess at the same point in the tree");
+        }
+        let mask = Mask::from_compact_str(mask).ok_or("masks must be five of B, Y, and G")?;
+        if mask.is_win() {
+            return if path.len() == 1 {
+                Ok(())
+            } else {
+                Err("the game continues after a win")
+            };
+        }
+        let next = path.get(1).ok_or("the game ends without a win")?;
+        let child = match self.children.iter().position(|(m, _)| *m == mask) {
+            Some(i) => &mut self.children[i].1,
+            None => {
+                self.children.push((mask, Tree::leaf(next.0)));
+                &mut self.children.last_mut().expect("just pushed").1
+            }
+        };
+        child.insert(&path[1..])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTreeError {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseTreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl std::error::Error for ParseTreeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Tree {
+        Tree {
+            guess: "baker".to_string(),
+            children: vec![
+                (mask![W C C C C], Tree::leaf("caker")),
+                (
+                    mask![W W C W W],
+                    Tree {
+                        guess: "silly".to_string(),
+                        children: vec![(mask![W W W W W], Tree::leaf("hokum"))],
+                    },
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn export() {
+        let text = example().export(&["baker", "caker", "hokum"]);
+        assert_eq!(
+            text,
+            "baker GGGGG1\n\
+             \x20     BGGGG caker GGGGG2\n\
+             \x20     BBGBB silly BBBBB hokum GGGGG3\n"
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        let tree = example();
+        let text = tree.export(&["baker", "caker", "hokum"]);
+        assert_eq!(Tree::import(&text), Ok(tree.clone()));
+        let commented = format!("# wordlist 0123456789abcdef\n{}", text);
+        assert_eq!(Tree::import(&commented), Ok(tree));
+    }
+
+    #[test]
+    fn replay() {
+        let tree = example();
+        let (history, won) = tree.replay("hokum");
+        assert!(won);
+        let words: Vec<_> = history.iter().map(|g| g.word.as_str()).collect();
+        assert_eq!(words, ["baker", "silly", "hokum"]);
+
+        // the tree has nothing for this mask after "silly"
+        let (history, won) = tree.replay("hokus");
+        assert!(!won);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].mask, mask![M W W W W]);
+    }
+
+    #[test]
+    fn import_rejects_conflicts() {
+        let err = Tree::import("baker BGGGG caker GGGGG2\nsilly GGGGG1\n").unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+
+    #[test]
+    fn import_checks_counts() {
+        assert!(Tree::import("baker BGGGG caker GGGGG3\n").is_err());
+        assert!(Tree::import("baker BGGGG caker BGGGG\n").is_err());
+    }
+}
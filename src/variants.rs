@@ -0,0 +1,135 @@
+use std::fmt;
+
+const BUNDLED: &str = include_str!("../variants.txt");
+
+// Which spelling of a word with regional variants counts as the answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Spelling {
+    British,
+    American,
+}
+
+// Links words that differ only by regional spelling (like "fibre" and "fiber"), so a game can
+// accept either as a guess while only ever using one of them as the answer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Variants<'a> {
+    // british, american
+    pairs: Vec<(&'a str, &'a str)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseVariantsError {
+    pub line: usize,
+    pub content: String,
+}
+
+impl fmt::Display for ParseVariantsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: expected a british and an american spelling of the same length, got '{}'",
+            self.line, self.content
+        )
+    }
+}
+
+impl std::error::Error for ParseVariantsError {}
+
+impl Variants<'static> {
+    // the pairs that ship with the crate
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED).expect("the bundled variants are well-formed")
+    }
+}
+
+impl<'a> Variants<'a> {
+    // One pair per line, british spelling first; blank lines and lines starting with '#' are
+    // ignored. Both spellings have to be the same length, since only those can stand in for each
+    // other in a game.
+    pub fn parse(text: &'a str) -> Result<Self, ParseVariantsError> {
+        let mut pairs = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            match (words.next(), words.next(), words.next()) {
+                (Some(british), Some(american), None) if british.len() == american.len() => {
+                    pairs.push((british, american));
+                }
+                _ => {
+                    return Err(ParseVariantsError {
+                        line: i + 1,
+                        content: line.to_string(),
+                    })
+                }
+            }
+        }
+        Ok(Self { pairs })
+    }
+
+    // `word` in the given spelling, or `word` itself if it has no variants
+    pub fn normalize<'w>(&self, word: &'w str, spelling: Spelling) -> &'w str
+    where
+        'a: 'w,
+    {
+        for &(british, american) in &self.pairs {
+            if word == british || word == american {
+                return match spelling {
+                    Spelling::British => british,
+                    Spelling::American => american,
+                };
+            }
+        }
+        word
+    }
+
+    // the other spelling of `word`, if it has one
+    pub fn alternative(&self, word: &str) -> Option<&'a str> {
+        self.pairs.iter().find_map(|&(british, american)| {
+            if word == british {
+                Some(american)
+            } else if word == american {
+                Some(british)
+            } else {
+                None
+            }
+        })
+    }
+
+    // every spelling of every linked word
+    pub fn words(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.pairs
+            .iter()
+            .flat_map(|&(british, american)| [british, american])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize() {
+        let variants = Variants::parse("fibre fiber\n# comment\n\nmetre meter\n").unwrap();
+        assert_eq!(variants.normalize("fiber", Spelling::British), "fibre");
+        assert_eq!(variants.normalize("fibre", Spelling::British), "fibre");
+        assert_eq!(variants.normalize("metre", Spelling::American), "meter");
+        assert_eq!(variants.normalize("crane", Spelling::American), "crane");
+        assert_eq!(variants.alternative("meter"), Some("metre"));
+        assert_eq!(variants.alternative("crane"), None);
+    }
+
+    #[test]
+    fn parse_rejects_mismatched_lengths() {
+        let err = Variants::parse("fibre fiber\ncolour color\n").unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+
+    #[test]
+    fn bundled() {
+        let variants = Variants::bundled();
+        assert!(variants.words().all(|word| word.len() == 5));
+    }
+}